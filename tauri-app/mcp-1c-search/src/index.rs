@@ -8,6 +8,10 @@ use rayon::prelude::*;
 use rusqlite::{params, Connection};
 
 use crate::parser::bsl_ast;
+use crate::parser::bsl_doc::{BslDoc, BslDocParam, BslDocReturn};
+use crate::parser::bsl_layout;
+use crate::parser::bsl_query;
+use crate::parser::form_xml::{FormAttribute, FormCommand, FormElement, FormEvent, FormStructure};
 
 /// Robustly read a file to string, handling UTF-8 (with BOM) and Windows-1251 fallback.
 pub fn read_file_to_string_lossy(path: &Path) -> Result<String, String> {
@@ -145,6 +149,7 @@ fn decode_one_cp1251(b: u8) -> char {
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct SymbolMatch {
     pub name: String,
     pub kind: String,
@@ -152,6 +157,255 @@ pub struct SymbolMatch {
     pub start_line: u32,
     pub end_line: u32,
     pub is_export: bool,
+    pub directive: Option<String>,
+    /// Parameter list as written: `Знач Объект, Отказ = Ложь` (empty for no parameters).
+    pub params: String,
+    /// Extension the symbol comes from, `None` for the base configuration.
+    pub origin: Option<String>,
+}
+
+impl SymbolMatch {
+    /// `Имя(Знач Объект, Отказ = Ложь)`
+    pub fn signature(&self) -> String {
+        format!("{}({})", self.name, self.params)
+    }
+}
+
+/// Optional filters shared by symbol queries (find_symbol, get_module_functions).
+#[derive(Default)]
+pub struct SymbolFilter {
+    /// Canonical compilation directive (`НаСервере`, ...), see `bsl_ast::normalize_directive`.
+    pub directive: Option<String>,
+    /// `#Область` name; methods of nested regions match their enclosing regions too.
+    pub region: Option<String>,
+    /// Subsystem (see `normalize_subsystem`); modules of objects in nested subsystems match too.
+    pub subsystem: Option<String>,
+    /// Common module flag (a `MODULE_FLAG_CONDITIONS` key): only methods of such common modules.
+    pub module_flag: Option<String>,
+}
+
+impl SymbolFilter {
+    /// Extra `AND ...` SQL conditions for the `symbols` table and their bound values.
+    fn sql_conditions(&self) -> (String, Vec<String>) {
+        let mut sql = String::new();
+        let mut values = Vec::new();
+        if let Some(d) = &self.directive {
+            sql.push_str(" AND directive = ?");
+            values.push(d.clone());
+        }
+        if let Some(r) = &self.region {
+            sql.push_str(
+                " AND EXISTS (SELECT 1 FROM module_regions r WHERE r.file = symbols.file AND r.name_lower = ? \
+                 AND symbols.start_line BETWEEN r.start_line AND r.end_line)",
+            );
+            values.push(r.to_lowercase());
+        }
+        if let Some(sub) = &self.subsystem {
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM indexed_files f WHERE f.filepath = symbols.file AND {})",
+                subsystem_member_sql("f.object_type", "f.object_name", "?")
+            ));
+            values.push(sub.clone());
+            values.push(sub.clone());
+        }
+        if let Some(condition) = self.module_flag.as_deref().and_then(|f| module_flag_sql(f, "symbols.file")) {
+            sql.push_str(" AND ");
+            sql.push_str(&condition);
+        }
+        (sql, values)
+    }
+}
+
+// ─── Common module flags ─────────────────────────────────────────────────────
+
+/// Flags of a common module as stored in `objects` (see `metadata::build_metadata`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommonModuleFlags {
+    pub global: bool,
+    pub client_managed: bool,
+    pub client_ordinary: bool,
+    pub server: bool,
+    pub external_connection: bool,
+    pub server_call: bool,
+    pub privileged: bool,
+    /// `DontUse`, `DuringRequest` or `DuringSession`.
+    pub return_values_reuse: Option<String>,
+}
+
+/// `module_flag` filter values → condition on the common module row `mo` of `objects`.
+pub const MODULE_FLAG_CONDITIONS: &[(&str, &str)] = &[
+    ("server",              "mo.server = 1"),
+    ("server_call",         "mo.server_call = 1"),
+    ("client",              "(mo.client_managed = 1 OR mo.client_ordinary = 1)"),
+    ("external_connection", "mo.external_connection = 1"),
+    ("privileged",          "mo.privileged = 1"),
+    ("global",              "mo.global = 1"),
+    ("cached",              "mo.return_values_reuse IN ('DuringRequest', 'DuringSession')"),
+];
+
+/// SQL condition: the module file in `file_col` belongs to a common module with `flag`
+/// (a `MODULE_FLAG_CONDITIONS` key). `None` for an unknown flag.
+pub fn module_flag_sql(flag: &str, file_col: &str) -> Option<String> {
+    let (_, condition) = MODULE_FLAG_CONDITIONS.iter().find(|(key, _)| *key == flag)?;
+    Some(format!(
+        "EXISTS (SELECT 1 FROM indexed_files mf \
+         JOIN objects mo ON mo.obj_type = 'CommonModule' AND mo.name = mf.object_name \
+         WHERE mf.filepath = {} AND mf.object_type = 'CommonModule' AND {})",
+        file_col, condition
+    ))
+}
+
+fn read_common_module_flags(conn: &Connection, name: &str) -> Option<CommonModuleFlags> {
+    conn.query_row(
+        "SELECT global, client_managed, client_ordinary, server, external_connection, server_call, \
+                privileged, return_values_reuse \
+         FROM objects WHERE obj_type = 'CommonModule' AND name = ?1 AND server IS NOT NULL",
+        params![name],
+        |r| {
+            let flag = |i: usize| r.get::<_, Option<i64>>(i).map(|v| v == Some(1));
+            Ok(CommonModuleFlags {
+                global: flag(0)?,
+                client_managed: flag(1)?,
+                client_ordinary: flag(2)?,
+                server: flag(3)?,
+                external_connection: flag(4)?,
+                server_call: flag(5)?,
+                privileged: flag(6)?,
+                return_values_reuse: r.get(7)?,
+            })
+        },
+    )
+    .ok()
+}
+
+/// Flags of the common module a source file belongs to (`CommonModules/X/Ext/Module.bsl`).
+pub fn common_module_flags(db_path: &Path, file: &str) -> Option<CommonModuleFlags> {
+    let (obj_type, obj_name, _) = infer_object_from_path(file);
+    if obj_type.as_deref() != Some("CommonModule") {
+        return None;
+    }
+    let conn = Connection::open(db_path).ok()?;
+    read_common_module_flags(&conn, &obj_name?)
+}
+
+// ─── Subsystems ──────────────────────────────────────────────────────────────
+
+/// Canonical form of a `subsystem` argument: `Продажи.ОптовыеПродажи`, `Подсистема.Продажи`
+/// or `Продажи/ОптовыеПродажи` → lowercased `/`-separated path (or a bare name).
+pub fn normalize_subsystem(name: &str) -> String {
+    let name = name.trim();
+    let lower = name.to_lowercase();
+    let name = ["subsystem.", "подсистема."]
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))
+        .unwrap_or(&lower);
+    name.replace(['.', '\\'], "/").trim_matches('/').to_string()
+}
+
+/// SQL condition: the object `type_col`.`name_col` belongs to the subsystem bound twice at
+/// `param` (a `normalize_subsystem` value matched against path or name) or to one of its
+/// nested subsystems.
+pub fn subsystem_member_sql(type_col: &str, name_col: &str, param: &str) -> String {
+    format!(
+        "EXISTS (SELECT 1 FROM subsystem_content c \
+         JOIN subsystems s ON s.id = c.subsystem_id \
+         JOIN subsystems root ON (root.path_lower = {p} OR root.name_lower = {p}) \
+         AND (s.path_lower = root.path_lower \
+              OR substr(s.path_lower, 1, length(root.path_lower) + 1) = root.path_lower || '/') \
+         WHERE c.obj_type = {t} AND c.obj_name = {n})",
+        p = param, t = type_col, n = name_col
+    )
+}
+
+/// Path of the first subsystem a normalized `subsystem` argument refers to.
+pub fn find_subsystem(db_path: &Path, subsystem: &str) -> Option<String> {
+    let conn = Connection::open(db_path).ok()?;
+    conn.query_row(
+        "SELECT path FROM subsystems WHERE path_lower = ?1 OR name_lower = ?1 ORDER BY length(path) LIMIT 1",
+        params![subsystem],
+        |r| r.get(0),
+    )
+    .ok()
+}
+
+/// Names of the top-level subsystems, for hints when a subsystem is not found.
+pub fn top_subsystems(db_path: &Path) -> Vec<String> {
+    let Ok(conn) = Connection::open(db_path) else { return Vec::new() };
+    let Ok(mut stmt) = conn.prepare("SELECT name FROM subsystems WHERE parent_id IS NULL ORDER BY name") else {
+        return Vec::new();
+    };
+    stmt.query_map([], |r| r.get(0))
+        .map(|rows| rows.flatten().collect())
+        .unwrap_or_default()
+}
+
+/// Source files of the objects in a subsystem (nested subsystems included), BSL modules first.
+pub fn subsystem_files(db_path: &Path, subsystem: &str) -> Vec<String> {
+    let Ok(conn) = Connection::open(db_path) else { return Vec::new() };
+    let sql = format!(
+        "SELECT filepath FROM indexed_files WHERE {} ORDER BY extension <> 'bsl', filepath",
+        subsystem_member_sql("indexed_files.object_type", "indexed_files.object_name", "?1")
+    );
+    let Ok(mut stmt) = conn.prepare(&sql) else { return Vec::new() };
+    stmt.query_map(params![subsystem], |r| r.get(0))
+        .map(|rows| rows.flatten().collect())
+        .unwrap_or_default()
+}
+
+/// SQL expression with the comma-separated parameter list of the symbol row `alias`
+/// (`Знач Объект, Отказ = Ложь`), empty string when the method has no parameters.
+pub fn params_list_sql(alias: &str) -> String {
+    format!(
+        "COALESCE((SELECT group_concat(CASE WHEN p.by_value THEN 'Знач ' ELSE '' END || p.name || \
+                   COALESCE(' = ' || p.default_value, ''), ', ' ORDER BY p.position) \
+                   FROM symbol_params p WHERE p.symbol_id = {}.id), '')",
+        alias
+    )
+}
+
+/// Columns read by `symbol_row_mapper`, in order (queries select `FROM symbols` without alias).
+fn symbol_columns() -> String {
+    format!(
+        "name, kind, file, start_line, end_line, is_export, directive, {}, origin",
+        params_list_sql("symbols")
+    )
+}
+
+/// Tables keyed by `symbol_id` that hold per-symbol data; cleared together with `symbols`.
+const SYMBOL_CHILD_TABLES: &[&str] = &["symbol_params", "symbol_docs", "symbol_doc_params", "symbol_interceptions"];
+
+/// Tables keyed by `file` with per-module layout (regions, module variables).
+const MODULE_LAYOUT_TABLES: &[&str] = &["module_regions", "module_vars"];
+
+/// Tables keyed by `file` with what the string literals of a module hold: query texts and
+/// method names called by name.
+const MODULE_LITERAL_TABLES: &[&str] = &["queries", "query_tables", "string_refs"];
+
+/// Prepare one `DELETE` per child and layout table removing rows of file `?1`.
+/// Must run before the symbols themselves are deleted.
+fn prepare_child_deletes(conn: &Connection) -> Result<Vec<rusqlite::Statement<'_>>, String> {
+    let symbol_children = SYMBOL_CHILD_TABLES.iter().map(|table| {
+        format!("DELETE FROM {} WHERE symbol_id IN (SELECT id FROM symbols WHERE file = ?1)", table)
+    });
+    let layout = MODULE_LAYOUT_TABLES
+        .iter()
+        .chain(MODULE_LITERAL_TABLES)
+        .map(|table| format!("DELETE FROM {} WHERE file = ?1", table));
+    symbol_children
+        .chain(layout)
+        .map(|sql| conn.prepare(&sql).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// `DELETE FROM <table>;` for every child and layout table.
+fn clear_child_tables_sql() -> String {
+    SYMBOL_CHILD_TABLES
+        .iter()
+        .chain(MODULE_LAYOUT_TABLES)
+        .chain(MODULE_LITERAL_TABLES)
+        .map(|t| format!("DELETE FROM {};", t))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// Derive database path from config root.
@@ -177,8 +431,176 @@ fn fnv_hash(s: &str) -> u64 {
     hash
 }
 
-/// Initialize the database schema (creates all tables if they don't exist).
-/// Safe to call multiple times — all statements use CREATE IF NOT EXISTS.
+// ─── Configuration roots ────────────────────────────────────────────────────
+
+/// How a configuration is laid out on disk. Index paths are relative to the source root in
+/// both layouts, so objects, modules and forms resolve the same way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SourceLayout {
+    /// Designer XML dump: `Catalogs/Валюты.xml`, modules and forms under `Catalogs/Валюты/Ext/`.
+    #[default]
+    Designer,
+    /// 1C:EDT project: `Catalogs/Валюты/Валюты.mdo`, modules and `Forms/<Форма>/Form.form` beside it.
+    Edt,
+}
+
+impl SourceLayout {
+    /// EDT when the source root holds `Configuration/Configuration.mdo`.
+    pub fn detect(root: &Path) -> SourceLayout {
+        if root.join("Configuration").join("Configuration.mdo").is_file() {
+            SourceLayout::Edt
+        } else {
+            SourceLayout::Designer
+        }
+    }
+
+    /// Description file of an object in `folder`: `Catalogs/Валюты.xml` or `Catalogs/Валюты/Валюты.mdo`.
+    pub fn object_file(self, folder: &str, name: &str) -> String {
+        match self {
+            SourceLayout::Designer => format!("{}/{}.xml", folder, name),
+            SourceLayout::Edt => format!("{}/{}/{}.mdo", folder, name, name),
+        }
+    }
+
+    /// Directory with the modules of an object: `Catalogs/Валюты/Ext` or `Catalogs/Валюты`.
+    pub fn module_dir(self, folder: &str, name: &str) -> String {
+        match self {
+            SourceLayout::Designer => format!("{}/{}/Ext", folder, name),
+            SourceLayout::Edt => format!("{}/{}", folder, name),
+        }
+    }
+
+    /// Description and module of the form in `form_dir` (`Documents/Заказ/Forms/ФормаДокумента`,
+    /// `CommonForms/Форма`): `Ext/Form.xml` + `Ext/Form/Module.bsl` or `Form.form` + `Module.bsl`.
+    pub fn form_files(self, form_dir: &str) -> (String, String) {
+        match self {
+            SourceLayout::Designer => (format!("{}/Ext/Form.xml", form_dir), format!("{}/Ext/Form/Module.bsl", form_dir)),
+            SourceLayout::Edt => (format!("{}/Form.form", form_dir), format!("{}/Module.bsl", form_dir)),
+        }
+    }
+
+    /// Rights of a role: `Roles/<Имя>/Ext/Rights.xml` or `Roles/<Имя>/Rights.rights`.
+    pub fn rights_file(self, role: &str) -> String {
+        match self {
+            SourceLayout::Designer => format!("Roles/{}/Ext/Rights.xml", role),
+            SourceLayout::Edt => format!("Roles/{}/Rights.rights", role),
+        }
+    }
+}
+
+/// Source root of a configuration directory: the `src` folder of an EDT project
+/// (`.project` + `src/Configuration/Configuration.mdo`), the directory itself otherwise.
+pub fn source_root(path: &Path) -> PathBuf {
+    let src = path.join("src");
+    if SourceLayout::detect(&src) == SourceLayout::Edt {
+        src
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// An extension (CFE) dump indexed together with the base configuration.
+#[derive(Debug, Clone)]
+pub struct ExtensionRoot {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Extension roots for this process, set once at startup (`ONEC_EXTENSION_PATHS`).
+static EXTENSION_ROOTS: std::sync::OnceLock<Vec<ExtensionRoot>> = std::sync::OnceLock::new();
+
+pub fn set_extension_roots(roots: Vec<ExtensionRoot>) {
+    let _ = EXTENSION_ROOTS.set(roots);
+}
+
+pub fn extension_roots() -> &'static [ExtensionRoot] {
+    EXTENSION_ROOTS.get().map(Vec::as_slice).unwrap_or(&[])
+}
+
+/// Extension name from `Configuration.xml` of its dump (first `<Name>`) or from
+/// `Configuration/Configuration.mdo` of an EDT project (`<name>`), or the directory name.
+pub fn extension_name(root: &Path) -> String {
+    let (file, tag) = match SourceLayout::detect(root) {
+        SourceLayout::Designer => (root.join("Configuration.xml"), "Name"),
+        SourceLayout::Edt => (root.join("Configuration").join("Configuration.mdo"), "name"),
+    };
+    fs::read_to_string(file)
+        .ok()
+        .and_then(|xml| {
+            let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+            let start = xml.find(&open)? + open.len();
+            let end = start + xml[start..].find(&close)?;
+            Some(xml[start..end].trim().to_string())
+        })
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| {
+            root.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+        })
+}
+
+/// Index paths of extension files are prefixed with `@<extension>/`; base files have no prefix.
+const ORIGIN_MARKER: char = '@';
+
+/// Split an index path into (extension name, path inside its dump):
+/// `@Расш1/CommonModules/М/Ext/Module.bsl` → (`Some("Расш1")`, `CommonModules/М/Ext/Module.bsl`).
+pub fn split_origin(rel: &str) -> (Option<&str>, &str) {
+    match rel.strip_prefix(ORIGIN_MARKER).and_then(|r| r.split_once('/')) {
+        Some((origin, rest)) => (Some(origin), rest),
+        None => (None, rel),
+    }
+}
+
+/// Absolute path of an indexed file: extension files resolve against their own root.
+pub fn source_path(base_root: &Path, rel: &str) -> PathBuf {
+    let (origin, inner) = split_origin(rel);
+    let root = origin
+        .and_then(|o| extension_roots().iter().find(|e| e.name == o))
+        .map(|e| e.path.as_path())
+        .unwrap_or(base_root);
+    root.join(inner.replace('/', std::path::MAIN_SEPARATOR_STR))
+}
+
+/// Index path of an absolute file path (see `split_origin`).
+pub fn index_rel_path(base_root: &Path, path: &Path) -> String {
+    let to_slash = |p: &Path| p.to_string_lossy().replace('\\', "/");
+    for ext in extension_roots() {
+        if let Ok(inner) = path.strip_prefix(&ext.path) {
+            return format!("{}{}/{}", ORIGIN_MARKER, ext.name, to_slash(inner));
+        }
+    }
+    path.strip_prefix(base_root).map(to_slash).unwrap_or_else(|_| to_slash(path))
+}
+
+/// All `.bsl` files of the base configuration and the extensions as (index path, absolute path).
+/// Extension roots nested inside the base root are not indexed twice.
+fn collect_bsl_files(base_root: &Path) -> Vec<(String, PathBuf)> {
+    let walk = |root: &Path, skip_extensions: bool| -> Vec<PathBuf> {
+        WalkBuilder::new(root)
+            .standard_filters(true)
+            .follow_links(false)
+            .filter_entry(move |e| {
+                !(skip_extensions && extension_roots().iter().any(|ext| e.path() == ext.path))
+            })
+            .build()
+            .flatten()
+            .filter(|e| {
+                e.path().is_file() && e.path().extension().and_then(|x| x.to_str()) == Some("bsl")
+            })
+            .map(|e| e.into_path())
+            .collect()
+    };
+    let mut files = walk(base_root, true);
+    for ext in extension_roots() {
+        files.extend(walk(&ext.path, false));
+    }
+    files
+        .into_iter()
+        .map(|path| (index_rel_path(base_root, &path), path))
+        .collect()
+}
+
+/// Initialize the database schema: create it, or migrate an existing one to `SCHEMA_VERSION`.
+/// Safe to call multiple times.
 pub fn ensure_schema(db_path: &Path) -> Result<(), String> {
     init_db(db_path).map(|_| ()).map_err(|e| e.to_string())
 }
@@ -188,10 +610,169 @@ fn init_db(db_path: &Path) -> Result<Connection, rusqlite::Error> {
         let _ = fs::create_dir_all(parent);
     }
     let conn = Connection::open(db_path)?;
+    conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")?;
+    migrate_schema(&conn)?;
+    Ok(conn)
+}
+
+// ─── Schema migrations ───────────────────────────────────────────────────────
+
+/// Version of the index schema, stored in `meta` as `schema_version`. Bump it together with
+/// a new `MIGRATIONS` entry when a table changes or the parser starts extracting new data.
+const SCHEMA_VERSION: i64 = 4;
+
+/// Upgrade of an index from `version - 1` to `version`.
+struct Migration {
+    version: i64,
+    description: &'static str,
+    /// Upgrades the previous version in place (`ALTER TABLE ... ADD COLUMN`, or clearing
+    /// `indexed_files` and the parsed tables so the parser re-reads every module). `None` when
+    /// the index can't be upgraded and is rebuilt from scratch.
+    apply: Option<fn(&Connection) -> rusqlite::Result<()>>,
+}
+
+/// Upgrades in ascending `version` order. New tables and indexes need no step:
+/// `create_schema` adds them once the migrations have run.
+const MIGRATIONS: &[Migration] = &[
+    // Earlier releases patched the schema by guessing from the data what was missing
+    Migration { version: 1, description: "versioned schema", apply: None },
+    Migration { version: 2, description: "trigram code index", apply: Some(clear_parsed_modules) },
+    Migration { version: 3, description: "method names in string literals", apply: Some(clear_parsed_modules) },
+    Migration { version: 4, description: "query table filters in SQL", apply: Some(add_query_table_lower_columns) },
+];
+
+/// Forget every parsed module (symbols, layout, queries, call sites, `indexed_files`), keeping
+/// metadata: startup finds no symbols and re-reads all modules with a full `build_index`.
+fn clear_parsed_modules(conn: &Connection) -> rusqlite::Result<()> {
+    // Parsed tables added since the index was built must exist to be cleared
+    create_schema(conn)?;
+    conn.execute_batch(&format!(
+        "DELETE FROM symbols;
+         {}
+         DELETE FROM indexed_files;
+         DELETE FROM calls WHERE edge_kind = 'call';
+         DELETE FROM symbol_terms;",
+        clear_child_tables_sql()
+    ))
+}
+
+/// Lowercased `part` and `fields` of `query_tables`, filled from the stored values.
+/// An earlier step of the same upgrade may have created the table with them already.
+fn add_query_table_lower_columns(conn: &Connection) -> rusqlite::Result<()> {
+    let has_column: bool = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('query_tables') WHERE name = 'part_lower'",
+        [],
+        |r| r.get::<_, i64>(0).map(|n| n > 0),
+    )?;
+    if has_column {
+        return Ok(());
+    }
+    conn.execute_batch(
+        "ALTER TABLE query_tables ADD COLUMN part_lower TEXT;
+         ALTER TABLE query_tables ADD COLUMN fields_lower TEXT NOT NULL DEFAULT '';",
+    )?;
+    let rows: Vec<(i64, Option<String>, String)> = conn
+        .prepare("SELECT rowid, part, fields FROM query_tables")?
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    let mut update = conn.prepare("UPDATE query_tables SET part_lower = ?2, fields_lower = ?3 WHERE rowid = ?1")?;
+    for (rowid, part, fields) in rows {
+        update.execute(params![rowid, part.map(|p| p.to_lowercase()), fields.to_lowercase()])?;
+    }
+    Ok(())
+}
+
+/// Bring the schema to `SCHEMA_VERSION`. A new database is created at the current version.
+/// An existing one (version 0 if `meta` has no `schema_version`) runs every migration above
+/// its version, each in its own transaction. If a step can't run in place or fails, or the
+/// database comes from a newer release, all tables are dropped and created anew — startup
+/// then finds no symbols and no metadata and runs a full `build_index` and `build_metadata`.
+fn migrate_schema(conn: &Connection) -> rusqlite::Result<()> {
+    match stored_schema_version(conn)? {
+        None => {}
+        Some(version) if version > SCHEMA_VERSION => {
+            eprintln!("[1c-search] Index schema v{} is newer than v{}, rebuilding...", version, SCHEMA_VERSION);
+            drop_all_tables(conn)?;
+        }
+        Some(version) => {
+            for migration in MIGRATIONS.iter().filter(|m| m.version > version) {
+                eprintln!("[1c-search] Migrating index schema to v{}: {}", migration.version, migration.description);
+                let upgraded = migration.apply.is_some_and(|apply| {
+                    run_migration(conn, migration.version, apply)
+                        .map_err(|e| eprintln!("[1c-search] Migration to v{} failed: {}", migration.version, e))
+                        .is_ok()
+                });
+                if !upgraded {
+                    eprintln!("[1c-search] Rebuilding the index from scratch...");
+                    drop_all_tables(conn)?;
+                    break;
+                }
+            }
+        }
+    }
+    create_schema(conn)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', ?1)",
+        params![SCHEMA_VERSION.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Stored schema version: `None` for a new (empty) database, 0 for an index of a release
+/// before `schema_version` was recorded.
+fn stored_schema_version(conn: &Connection) -> rusqlite::Result<Option<i64>> {
+    let tables: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        [],
+        |r| r.get(0),
+    )?;
+    if tables == 0 {
+        return Ok(None);
+    }
+    let version = conn
+        .query_row("SELECT value FROM meta WHERE key = 'schema_version'", [], |r| r.get::<_, String>(0))
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    Ok(Some(version))
+}
+
+fn run_migration(conn: &Connection, version: i64, apply: fn(&Connection) -> rusqlite::Result<()>) -> rusqlite::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    apply(&tx)?;
+    tx.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', ?1)",
+        params![version.to_string()],
+    )?;
+    tx.commit()
+}
+
+/// Drop every table. FTS5 tables go first: their shadow tables are dropped with them.
+fn drop_all_tables(conn: &Connection) -> rusqlite::Result<()> {
+    for virtual_tables in [true, false] {
+        let names: Vec<String> = conn
+            .prepare(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' \
+                 AND (sql LIKE 'CREATE VIRTUAL TABLE%') = ?1",
+            )?
+            .query_map([virtual_tables], |r| r.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        for name in names {
+            conn.execute_batch(&format!("DROP TABLE IF EXISTS \"{}\";", name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Tables and indexes of the current `SCHEMA_VERSION` (CREATE IF NOT EXISTS).
+/// `calls.callee_file` is filled by `resolve_calls` after every build/sync; `calls.edge_kind` is
+/// `call` for a call site in code, other kinds are added by `build_metadata` for methods the
+/// platform invokes: `event_subscription`, `scheduled_job`, `http_service`, `web_service`,
+/// `common_command`.
+/// `code_trigrams` holds module texts (rowid = `code_files.id`) for `code_candidate_files`.
+fn create_schema(conn: &Connection) -> rusqlite::Result<()> {
     conn.execute_batch(
-        "PRAGMA journal_mode=WAL;
-         PRAGMA synchronous=NORMAL;
-         CREATE TABLE IF NOT EXISTS symbols (
+        "CREATE TABLE IF NOT EXISTS symbols (
              id INTEGER PRIMARY KEY,
              name TEXT NOT NULL,
              name_lower TEXT NOT NULL,
@@ -199,10 +780,89 @@ fn init_db(db_path: &Path) -> Result<Connection, rusqlite::Error> {
              file TEXT NOT NULL,
              start_line INTEGER NOT NULL,
              end_line INTEGER NOT NULL,
-             is_export INTEGER NOT NULL DEFAULT 0
+             is_export INTEGER NOT NULL DEFAULT 0,
+             directive TEXT DEFAULT NULL,
+             origin TEXT DEFAULT NULL
          );
          CREATE INDEX IF NOT EXISTS idx_name_lower ON symbols(name_lower);
          CREATE INDEX IF NOT EXISTS idx_file ON symbols(file);
+         CREATE TABLE IF NOT EXISTS symbol_params (
+             symbol_id INTEGER NOT NULL,
+             position INTEGER NOT NULL,
+             name TEXT NOT NULL,
+             by_value INTEGER NOT NULL DEFAULT 0,
+             default_value TEXT
+         );
+         CREATE INDEX IF NOT EXISTS idx_symbol_params_symbol ON symbol_params(symbol_id);
+         CREATE TABLE IF NOT EXISTS symbol_docs (
+             symbol_id INTEGER PRIMARY KEY,
+             summary TEXT NOT NULL DEFAULT '',
+             returns_type TEXT,
+             returns_description TEXT,
+             examples TEXT NOT NULL DEFAULT ''
+         );
+         CREATE TABLE IF NOT EXISTS symbol_doc_params (
+             symbol_id INTEGER NOT NULL,
+             position INTEGER NOT NULL,
+             name TEXT NOT NULL,
+             types TEXT NOT NULL DEFAULT '',
+             description TEXT NOT NULL DEFAULT ''
+         );
+         CREATE INDEX IF NOT EXISTS idx_symbol_doc_params_symbol ON symbol_doc_params(symbol_id);
+         CREATE TABLE IF NOT EXISTS symbol_interceptions (
+             symbol_id INTEGER NOT NULL,
+             kind TEXT NOT NULL,
+             target TEXT NOT NULL,
+             target_lower TEXT NOT NULL,
+             base_file TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_symbol_interceptions_symbol ON symbol_interceptions(symbol_id);
+         CREATE INDEX IF NOT EXISTS idx_symbol_interceptions_target ON symbol_interceptions(base_file, target_lower);
+         CREATE TABLE IF NOT EXISTS module_regions (
+             file TEXT NOT NULL,
+             name TEXT NOT NULL,
+             name_lower TEXT NOT NULL,
+             start_line INTEGER NOT NULL,
+             end_line INTEGER NOT NULL,
+             depth INTEGER NOT NULL DEFAULT 0
+         );
+         CREATE INDEX IF NOT EXISTS idx_module_regions_file ON module_regions(file);
+         CREATE TABLE IF NOT EXISTS module_vars (
+             file TEXT NOT NULL,
+             name TEXT NOT NULL,
+             name_lower TEXT NOT NULL,
+             line INTEGER NOT NULL,
+             is_export INTEGER NOT NULL DEFAULT 0,
+             directive TEXT DEFAULT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_module_vars_file ON module_vars(file);
+         CREATE TABLE IF NOT EXISTS queries (
+             id INTEGER PRIMARY KEY,
+             file TEXT NOT NULL,
+             symbol_id INTEGER,
+             start_line INTEGER NOT NULL,
+             end_line INTEGER NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_queries_file ON queries(file);
+         CREATE TABLE IF NOT EXISTS query_tables (
+             query_id INTEGER NOT NULL,
+             file TEXT NOT NULL,
+             name TEXT NOT NULL,
+             object TEXT NOT NULL,
+             object_lower TEXT NOT NULL,
+             part TEXT,
+             part_lower TEXT,
+             fields TEXT NOT NULL DEFAULT '',
+             fields_lower TEXT NOT NULL DEFAULT ''
+         );
+         CREATE INDEX IF NOT EXISTS idx_query_tables_object ON query_tables(object_lower);
+         CREATE INDEX IF NOT EXISTS idx_query_tables_file ON query_tables(file);
+         CREATE TABLE IF NOT EXISTS string_refs (
+             file TEXT NOT NULL,
+             name_lower TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_string_refs_name ON string_refs(name_lower);
+         CREATE INDEX IF NOT EXISTS idx_string_refs_file ON string_refs(file);
          CREATE TABLE IF NOT EXISTS meta (
              key TEXT PRIMARY KEY,
              value TEXT NOT NULL
@@ -211,7 +871,15 @@ fn init_db(db_path: &Path) -> Result<Connection, rusqlite::Error> {
              id INTEGER PRIMARY KEY,
              obj_type TEXT NOT NULL,
              name TEXT NOT NULL,
-             name_lower TEXT NOT NULL
+             name_lower TEXT NOT NULL,
+             global INTEGER,
+             client_managed INTEGER,
+             client_ordinary INTEGER,
+             server INTEGER,
+             external_connection INTEGER,
+             server_call INTEGER,
+             privileged INTEGER,
+             return_values_reuse TEXT
          );
          CREATE INDEX IF NOT EXISTS idx_obj_name ON objects(name_lower);
          CREATE INDEX IF NOT EXISTS idx_obj_type ON objects(obj_type);
@@ -220,57 +888,166 @@ fn init_db(db_path: &Path) -> Result<Connection, rusqlite::Error> {
              object_id INTEGER NOT NULL,
              item_type TEXT NOT NULL,
              item_name TEXT NOT NULL,
-             parent_section TEXT
+             parent_section TEXT,
+             value_type TEXT,
+             synonym TEXT,
+             comment TEXT,
+             indexing TEXT,
+             fill_checking TEXT
          );
          CREATE INDEX IF NOT EXISTS idx_items_obj ON object_items(object_id);
+         CREATE INDEX IF NOT EXISTS idx_items_type_name ON object_items(item_type, item_name);
+         CREATE TABLE IF NOT EXISTS object_properties (
+             object_id INTEGER NOT NULL,
+             name TEXT NOT NULL,
+             value TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_object_properties_obj ON object_properties(object_id);
+         CREATE TABLE IF NOT EXISTS metadata_references (
+             object_id INTEGER NOT NULL,
+             item TEXT,
+             kind TEXT NOT NULL,
+             target TEXT NOT NULL,
+             target_lower TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_md_refs_obj ON metadata_references(object_id);
+         CREATE INDEX IF NOT EXISTS idx_md_refs_target ON metadata_references(target_lower);
+         CREATE TABLE IF NOT EXISTS predefined_values (
+             object_id INTEGER NOT NULL,
+             name TEXT NOT NULL,
+             name_lower TEXT NOT NULL,
+             synonym TEXT,
+             code TEXT,
+             is_folder INTEGER NOT NULL DEFAULT 0,
+             parent TEXT
+         );
+         CREATE INDEX IF NOT EXISTS idx_predefined_obj ON predefined_values(object_id);
+         CREATE INDEX IF NOT EXISTS idx_predefined_name ON predefined_values(name_lower);
+         CREATE TABLE IF NOT EXISTS subsystems (
+             id INTEGER PRIMARY KEY,
+             parent_id INTEGER,
+             name TEXT NOT NULL,
+             name_lower TEXT NOT NULL,
+             path TEXT NOT NULL,
+             path_lower TEXT NOT NULL,
+             synonym TEXT
+         );
+         CREATE INDEX IF NOT EXISTS idx_subsystems_name ON subsystems(name_lower);
+         CREATE INDEX IF NOT EXISTS idx_subsystems_path ON subsystems(path_lower);
+         CREATE TABLE IF NOT EXISTS subsystem_content (
+             subsystem_id INTEGER NOT NULL,
+             obj_type TEXT NOT NULL,
+             obj_name TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_subsystem_content_sub ON subsystem_content(subsystem_id);
+         CREATE INDEX IF NOT EXISTS idx_subsystem_content_obj ON subsystem_content(obj_type, obj_name);
+         CREATE TABLE IF NOT EXISTS role_rights (
+             role_id INTEGER NOT NULL,
+             object TEXT NOT NULL,
+             object_lower TEXT NOT NULL,
+             item TEXT,
+             right_name TEXT NOT NULL,
+             granted INTEGER NOT NULL,
+             condition TEXT
+         );
+         CREATE INDEX IF NOT EXISTS idx_role_rights_role ON role_rights(role_id);
+         CREATE INDEX IF NOT EXISTS idx_role_rights_object ON role_rights(object_lower);
+         CREATE TABLE IF NOT EXISTS role_templates (
+             role_id INTEGER NOT NULL,
+             name TEXT NOT NULL,
+             condition TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_role_templates_role ON role_templates(role_id);
+         CREATE TABLE IF NOT EXISTS forms (
+             id INTEGER PRIMARY KEY,
+             owner TEXT NOT NULL,
+             owner_lower TEXT NOT NULL,
+             name TEXT NOT NULL,
+             name_lower TEXT NOT NULL,
+             module_file TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_forms_owner ON forms(owner_lower);
+         CREATE INDEX IF NOT EXISTS idx_forms_module ON forms(module_file);
+         CREATE TABLE IF NOT EXISTS form_attributes (
+             form_id INTEGER NOT NULL,
+             name TEXT NOT NULL,
+             types TEXT NOT NULL DEFAULT '',
+             is_main INTEGER NOT NULL DEFAULT 0
+         );
+         CREATE INDEX IF NOT EXISTS idx_form_attributes_form ON form_attributes(form_id);
+         CREATE TABLE IF NOT EXISTS form_commands (
+             form_id INTEGER NOT NULL,
+             name TEXT NOT NULL,
+             action TEXT,
+             action_lower TEXT
+         );
+         CREATE INDEX IF NOT EXISTS idx_form_commands_form ON form_commands(form_id);
+         CREATE TABLE IF NOT EXISTS form_elements (
+             form_id INTEGER NOT NULL,
+             position INTEGER NOT NULL,
+             name TEXT NOT NULL,
+             kind TEXT NOT NULL,
+             data_path TEXT,
+             parent TEXT
+         );
+         CREATE INDEX IF NOT EXISTS idx_form_elements_form ON form_elements(form_id);
+         CREATE TABLE IF NOT EXISTS form_events (
+             form_id INTEGER NOT NULL,
+             element TEXT,
+             event TEXT NOT NULL,
+             handler TEXT NOT NULL,
+             handler_lower TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_form_events_form ON form_events(form_id);
+         CREATE INDEX IF NOT EXISTS idx_form_events_handler ON form_events(handler_lower);
          CREATE TABLE IF NOT EXISTS indexed_files (
              filepath TEXT PRIMARY KEY,
-             modified_at INTEGER NOT NULL
+             modified_at INTEGER NOT NULL,
+             path_lower TEXT,
+             file_name TEXT,
+             file_name_lower TEXT,
+             extension TEXT,
+             object_type TEXT,
+             object_name TEXT,
+             module_kind TEXT,
+             source_kind TEXT,
+             content_hash INTEGER
          );
+         CREATE INDEX IF NOT EXISTS idx_indexed_files_path_lower ON indexed_files(path_lower);
+         CREATE INDEX IF NOT EXISTS idx_indexed_files_name_lower ON indexed_files(file_name_lower);
+         CREATE INDEX IF NOT EXISTS idx_indexed_files_object ON indexed_files(object_type, object_name);
+         CREATE INDEX IF NOT EXISTS idx_indexed_files_extension ON indexed_files(extension);
          CREATE TABLE IF NOT EXISTS calls (
              id INTEGER PRIMARY KEY,
              caller_file TEXT NOT NULL,
              caller_name TEXT NOT NULL,
              caller_name_lower TEXT NOT NULL,
              callee_name TEXT NOT NULL,
-             callee_name_lower TEXT NOT NULL
+             callee_name_lower TEXT NOT NULL,
+             callee_receiver TEXT,
+             callee_receiver_lower TEXT,
+             callee_file TEXT,
+             edge_kind TEXT NOT NULL DEFAULT 'call'
          );
          CREATE INDEX IF NOT EXISTS idx_calls_caller ON calls(caller_name_lower);
-         CREATE INDEX IF NOT EXISTS idx_calls_callee ON calls(callee_name_lower);",
+         CREATE INDEX IF NOT EXISTS idx_calls_callee ON calls(callee_name_lower);
+         CREATE INDEX IF NOT EXISTS idx_calls_callee_file ON calls(callee_file);
+         CREATE INDEX IF NOT EXISTS idx_calls_caller_file ON calls(caller_file);
+         CREATE TABLE IF NOT EXISTS code_files (
+             id INTEGER PRIMARY KEY,
+             file TEXT NOT NULL UNIQUE
+         );
+         CREATE VIRTUAL TABLE IF NOT EXISTS code_trigrams USING fts5(
+             content,
+             tokenize = 'trigram',
+             detail = 'none',
+             content = '',
+             contentless_delete = 1
+         );",
     )?;
-    // Phase 2: file catalog columns (additive migration — safe to call on existing DBs)
-    migrate_file_catalog_schema(&conn);
-    // Phase 3: semantic search tables (FTS5 symbol_terms, symbol_weights, domain_aliases)
-    crate::semantic::ensure_semantic_schema(&conn);
-    Ok(conn)
-}
-
-/// Add file catalog columns to indexed_files if they don't exist yet.
-/// Additive only — never removes data from existing rows.
-fn migrate_file_catalog_schema(conn: &Connection) {
-    let columns = [
-        ("path_lower",      "TEXT"),
-        ("file_name",       "TEXT"),
-        ("file_name_lower", "TEXT"),
-        ("extension",       "TEXT"),
-        ("object_type",     "TEXT"),
-        ("object_name",     "TEXT"),
-        ("module_kind",     "TEXT"),
-        ("source_kind",     "TEXT"),
-    ];
-    for (col, ty) in &columns {
-        let _ = conn.execute_batch(&format!(
-            "ALTER TABLE indexed_files ADD COLUMN {} {} DEFAULT NULL;",
-            col, ty
-        ));
-    }
-    // Indexes for fast file search
-    let _ = conn.execute_batch(
-        "CREATE INDEX IF NOT EXISTS idx_indexed_files_path_lower  ON indexed_files(path_lower);
-         CREATE INDEX IF NOT EXISTS idx_indexed_files_name_lower  ON indexed_files(file_name_lower);
-         CREATE INDEX IF NOT EXISTS idx_indexed_files_object      ON indexed_files(object_type, object_name);
-         CREATE INDEX IF NOT EXISTS idx_indexed_files_extension   ON indexed_files(extension);"
-    );
+    // Semantic search tables (FTS5 symbol_terms, symbol_weights, domain_aliases)
+    crate::semantic::ensure_semantic_schema(conn);
+    Ok(())
 }
 
 /// File catalog item returned by search_files_in_catalog.
@@ -283,14 +1060,23 @@ pub struct FileCatalogItem {
     pub module_kind: Option<String>,
 }
 
+/// Narrowing conditions of `search_files_in_catalog`.
+#[derive(Default)]
+pub struct FileCatalogFilter<'a> {
+    /// Relative directory prefix (`CommonModules/ОбщегоНазначения`).
+    pub scope_prefix: Option<&'a str>,
+    pub object_type: Option<&'a str>,
+    pub extension: Option<&'a str>,
+    /// Subsystem (see `normalize_subsystem`), nested subsystems included.
+    pub subsystem: Option<&'a str>,
+}
+
 /// Fast file search backed by the indexed_files file catalog.
 /// Returns Err if catalog columns are absent (fallback to FS walk in caller).
 pub fn search_files_in_catalog(
     db_path: &Path,
     query: &str,           // substring of path/name, lowercased
-    scope_prefix: Option<&str>,
-    object_type: Option<&str>,
-    extension: Option<&str>,
+    filter: &FileCatalogFilter,
     glob_pattern: &str,
     limit: usize,
 ) -> Result<Vec<FileCatalogItem>, String> {
@@ -324,18 +1110,23 @@ pub fn search_files_in_catalog(
         params_values.push(like.clone());
         params_values.push(like);
     }
-    if let Some(sp) = scope_prefix {
+    if let Some(sp) = filter.scope_prefix {
         conditions.push("path_lower LIKE ?".to_string());
         params_values.push(format!("{}%", sp.to_lowercase()));
     }
-    if let Some(ot) = object_type {
+    if let Some(ot) = filter.object_type {
         conditions.push("object_type = ?".to_string());
         params_values.push(ot.to_string());
     }
-    if let Some(ext) = extension {
+    if let Some(ext) = filter.extension {
         conditions.push("extension = ?".to_string());
         params_values.push(ext.to_lowercase());
     }
+    if let Some(sub) = filter.subsystem {
+        conditions.push(subsystem_member_sql("indexed_files.object_type", "indexed_files.object_name", "?"));
+        params_values.push(sub.to_string());
+        params_values.push(sub.to_string());
+    }
 
     let where_clause = if conditions.is_empty() {
         String::new()
@@ -387,6 +1178,16 @@ pub fn search_files_in_catalog(
     Ok(items)
 }
 
+/// `indexed_files.source_kind` of a file extension: `bsl` for modules, `xml` for metadata
+/// descriptions in either layout (Designer `.xml`; EDT `.mdo`, `.form`, `.rights`), `other` otherwise.
+fn source_kind(extension: &str) -> &'static str {
+    match extension {
+        "bsl" => "bsl",
+        "xml" | "mdo" | "form" | "rights" => "xml",
+        _ => "other",
+    }
+}
+
 /// Fill file catalog columns for a single file path (called during build/sync).
 #[allow(dead_code)]
 pub fn upsert_file_catalog(
@@ -402,8 +1203,8 @@ pub fn upsert_file_catalog(
         .unwrap_or("")
         .to_lowercase();
 
-    let (obj_type, obj_name, module_kind) = infer_object_from_path_index(rel_path);
-    let source_kind = if extension == "bsl" { "bsl" } else if extension == "xml" { "xml" } else { "other" };
+    let (obj_type, obj_name, module_kind) = infer_object_from_path(rel_path);
+    let source_kind = source_kind(&extension);
 
     let _ = conn.execute(
         "INSERT INTO indexed_files(filepath, modified_at, path_lower, file_name, file_name_lower, extension, object_type, object_name, module_kind, source_kind)
@@ -426,12 +1227,23 @@ pub fn upsert_file_catalog(
     );
 }
 
-fn infer_object_from_path_index(rel: &str) -> (Option<String>, Option<String>, Option<String>) {
+/// Infer (object_type, object_name, module_kind) from an index path in either layout (see
+/// `SourceLayout`): `CommonModules/УчетНДС/Ext/Module.bsl` and `CommonModules/УчетНДС/Module.bsl`
+/// both give (`CommonModule`, `УчетНДС`, `Module`).
+pub fn infer_object_from_path(rel: &str) -> (Option<String>, Option<String>, Option<String>) {
+    // Extension files mirror the base layout below their `@<extension>/` prefix
+    let rel = split_origin(rel).1;
     let parts: Vec<&str> = rel.splitn(3, '/').collect();
     if parts.len() < 2 { return (None, None, None); }
     let folder = parts[0];
-    let obj_name = parts[1];
-    let file_part = parts.get(2).copied().unwrap_or("");
+    // The description of an object sits beside its folder in a Designer dump
+    // (`Catalogs/Валюты.xml`) and inside it in EDT (`Catalogs/Валюты/Валюты.mdo`)
+    let (obj_name, file_part) = match parts.get(2) {
+        Some(file) => (parts[1], *file),
+        None => (parts[1].strip_suffix(".xml").unwrap_or(parts[1]), parts[1]),
+    };
+    // Designer keeps modules of an object under `Ext/`, EDT beside its `.mdo`
+    let file_part = file_part.strip_prefix("Ext/").unwrap_or(file_part);
 
     let obj_type = match folder {
         "CommonModules"               => Some("CommonModule"),
@@ -466,7 +1278,9 @@ fn infer_object_from_path_index(rel: &str) -> (Option<String>, Option<String>, O
     };
 
     let lf = file_part.to_lowercase();
-    let module_kind = if lf == "module.bsl" {
+    let module_kind = if obj_type == Some("CommonForm") && (lf == "module.bsl" || lf == "form/module.bsl") {
+        Some("FormModule")
+    } else if lf == "module.bsl" {
         Some("Module")
     } else if lf == "managermodule.bsl" {
         Some("ManagerModule")
@@ -474,7 +1288,7 @@ fn infer_object_from_path_index(rel: &str) -> (Option<String>, Option<String>, O
         Some("ObjectModule")
     } else if lf.starts_with("forms/") {
         Some("FormModule")
-    } else if lf.ends_with(".xml") {
+    } else if lf.ends_with(".xml") || lf.ends_with(".mdo") {
         Some("XML")
     } else {
         None
@@ -487,74 +1301,10 @@ fn infer_object_from_path_index(rel: &str) -> (Option<String>, Option<String>, O
     )
 }
 
-/// If the calls table is empty but symbols exist, this DB was indexed before call extraction
-/// was added. Reset indexed_files so the next sync re-parses all files.
-pub fn migrate_if_needed(db_path: &Path) {
-    let conn = match Connection::open(db_path) {
-        Ok(c) => c,
-        Err(_) => return,
-    };
-    let sym_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM symbols", [], |r| r.get(0))
-        .unwrap_or(0);
-    let calls_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM calls", [], |r| r.get(0))
-        .unwrap_or(0);
-    if sym_count > 0 && calls_count == 0 {
-        eprintln!("[1c-search] Migrating: resetting indexed_files to rebuild call graph...");
-        let _ = conn.execute("DELETE FROM indexed_files", []);
-    }
-}
-
-/// If symbol_terms FTS table is empty or uses old contentless schema, rebuild it.
-/// Handles upgrade from versions without semantic search or with wrong schema.
-pub fn migrate_semantic_fts_if_needed(db_path: &Path) {
-    let conn = match Connection::open(db_path) {
-        Ok(c) => c,
-        Err(_) => return,
-    };
-    let sym_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM symbols", [], |r| r.get(0))
-        .unwrap_or(0);
-    if sym_count == 0 {
-        return;
-    }
-
-    // Detect old contentless FTS5 schema: column values come back as NULL
-    let needs_rebuild = {
-        let sample = conn.query_row(
-            "SELECT symbol_id FROM symbol_terms LIMIT 1",
-            [],
-            |r| r.get::<_, Option<String>>(0),
-        );
-        match sample {
-            Ok(Some(_)) => false,           // content stored, OK
-            Ok(None) => true,               // NULL → contentless schema → rebuild
-            Err(_) => {
-                // Table empty or doesn't exist
-                let fts_count: i64 = conn
-                    .query_row("SELECT COUNT(*) FROM symbol_terms", [], |r| r.get(0))
-                    .unwrap_or(0);
-                fts_count == 0              // rebuild only if empty
-            }
-        }
-    };
-
-    if needs_rebuild {
-        eprintln!("[1c-search] Migrating: dropping old FTS schema and rebuilding ({} symbols)...", sym_count);
-        // Drop and recreate with correct schema (no content='')
-        let _ = conn.execute_batch("DROP TABLE IF EXISTS symbol_terms;");
-        crate::semantic::ensure_semantic_schema(&conn);
-        build_semantic_fts(&conn);
-        crate::semantic::rebuild_symbol_weights(&conn);
-        eprintln!("[1c-search] Semantic FTS migration complete");
-    }
-}
-
-/// Check if index exists and has data.
-pub fn index_exists(db_path: &Path) -> bool {
-    if !db_path.exists() {
-        return false;
+/// Check if index exists and has data.
+pub fn index_exists(db_path: &Path) -> bool {
+    if !db_path.exists() {
+        return false;
     }
     if let Ok(conn) = Connection::open(db_path) {
         if let Ok(count) = conn.query_row(
@@ -594,6 +1344,150 @@ pub fn symbol_count(db_path: &Path) -> usize {
     0
 }
 
+// ─── Portable index ──────────────────────────────────────────────────────────
+
+/// Summary of an index archive written by `export_index` or read by `import_index`.
+pub struct IndexArchive {
+    /// `dump_fingerprint` of the exported index.
+    pub fingerprint: String,
+    pub files: usize,
+    pub symbols: usize,
+}
+
+/// Content fingerprint of the indexed dump: `fnv_hash` over the sorted (path, content hash)
+/// pairs of `indexed_files`. Equal fingerprints mean the same modules with the same text.
+fn dump_fingerprint(conn: &Connection) -> rusqlite::Result<String> {
+    let mut stmt = conn.prepare("SELECT filepath, content_hash FROM indexed_files ORDER BY filepath")?;
+    let lines: Vec<String> = stmt
+        .query_map([], |r| Ok(format!("{}\t{:016x}", r.get::<_, String>(0)?, r.get::<_, Option<i64>>(1)?.unwrap_or(0))))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(format!("{:016x}", fnv_hash(&lines.join("\n"))))
+}
+
+/// `dump_fingerprint` of the index at `db_path`.
+pub fn index_fingerprint(db_path: &Path) -> Option<String> {
+    dump_fingerprint(&Connection::open(db_path).ok()?).ok()
+}
+
+fn archive_summary(conn: &Connection, fingerprint: String) -> rusqlite::Result<IndexArchive> {
+    let count = |table: &str| {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get::<_, i64>(0))
+            .map(|n| n as usize)
+    };
+    Ok(IndexArchive { fingerprint, files: count("indexed_files")?, symbols: count("symbols")? })
+}
+
+/// Write the index at `db_path` to `archive`: a standalone SQLite file with the same tables.
+/// Index paths are relative to the configuration root (`@<extension>/` for extensions), so the
+/// archive does not depend on where the dump lies; `meta.dump_fingerprint` identifies its modules.
+pub fn export_index(db_path: &Path, archive: &Path) -> Result<IndexArchive, String> {
+    let conn = init_db(db_path).map_err(|e| format!("Ошибка БД: {}", e))?;
+    let summary = archive_summary(&conn, dump_fingerprint(&conn).map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    if summary.symbols == 0 {
+        return Err("Индекс пуст — дождитесь завершения индексации".to_string());
+    }
+    if archive.exists() {
+        fs::remove_file(archive).map_err(|e| format!("Не удалось заменить {}: {}", archive.display(), e))?;
+    }
+    conn.execute("VACUUM INTO ?1", params![archive.to_string_lossy()])
+        .map_err(|e| format!("Запись архива: {}", e))?;
+    let out = Connection::open(archive).map_err(|e| e.to_string())?;
+    out.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('dump_fingerprint', ?1)",
+        params![summary.fingerprint],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(summary)
+}
+
+/// Lock file beside the index: a running server holds it shared (`hold_index`), `import_index`
+/// takes it exclusively to replace the database file.
+fn index_lock_path(db_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.lock", db_path.display()))
+}
+
+fn open_index_lock(db_path: &Path) -> std::io::Result<fs::File> {
+    if let Some(parent) = db_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    fs::OpenOptions::new().create(true).truncate(false).write(true).open(index_lock_path(db_path))
+}
+
+/// Keep `import_index` from replacing the index while the returned file is alive (the server
+/// holds it until exit). Waits for an import in progress; `None` if the lock is unavailable.
+pub fn hold_index(db_path: &Path) -> Option<fs::File> {
+    let file = open_index_lock(db_path).ok()?;
+    file.lock_shared().ok()?;
+    Some(file)
+}
+
+/// Replace the index at `db_path` with an archive from `export_index` of the same
+/// `SCHEMA_VERSION`. Module mtimes in the archive are those of the exporting machine, so the
+/// next `sync_index` hashes every module and re-parses only those whose content differs.
+/// Metadata XML is not fingerprinted: the imported metadata is marked stale for `build_metadata`.
+///
+/// Refused while a server holds the index (`hold_index`). The archive is prepared in a copy
+/// beside the index, which then replaces the database file with a rename.
+pub fn import_index(archive: &Path, db_path: &Path) -> Result<IndexArchive, String> {
+    let source = Connection::open_with_flags(archive, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Открытие архива {}: {}", archive.display(), e))?;
+    let meta = |key: &str| {
+        source
+            .query_row("SELECT value FROM meta WHERE key = ?1", params![key], |r| r.get::<_, String>(0))
+            .ok()
+    };
+    let fingerprint = meta("dump_fingerprint")
+        .ok_or_else(|| format!("{} не является архивом индекса (нет dump_fingerprint)", archive.display()))?;
+    let version = meta("schema_version").and_then(|v| v.parse::<i64>().ok()).unwrap_or(0);
+    if version != SCHEMA_VERSION {
+        return Err(format!(
+            "Архив создан другой версией сервера: схема v{}, ожидается v{}. Экспортируйте индекс заново.",
+            version, SCHEMA_VERSION
+        ));
+    }
+    let summary = archive_summary(&source, fingerprint).map_err(|e| e.to_string())?;
+    drop(source);
+
+    let lock = open_index_lock(db_path).map_err(|e| format!("Блокировка индекса: {}", e))?;
+    match lock.try_lock() {
+        Ok(()) => {}
+        Err(fs::TryLockError::WouldBlock) => {
+            return Err("Индекс открыт сервером поиска. Остановите сервер (или закройте приложение) и повторите загрузку.".to_string())
+        }
+        Err(fs::TryLockError::Error(e)) => return Err(format!("Блокировка индекса: {}", e)),
+    }
+
+    let staged = PathBuf::from(format!("{}.import", db_path.display()));
+    let remove_db_files = |db: &Path, suffixes: &[&str]| -> Result<(), String> {
+        for suffix in suffixes {
+            let file = PathBuf::from(format!("{}{}", db.display(), suffix));
+            if file.exists() {
+                fs::remove_file(&file).map_err(|e| format!("Не удалось заменить {}: {}", file.display(), e))?;
+            }
+        }
+        Ok(())
+    };
+    remove_db_files(&staged, &["", "-wal", "-shm"])?;
+    let prepared = fs::copy(archive, &staged)
+        .map_err(|e| format!("Копирование архива: {}", e))
+        .and_then(|_| {
+            // Closing the connection checkpoints its WAL into the copy
+            let conn = init_db(&staged).map_err(|e| format!("Ошибка БД: {}", e))?;
+            conn.execute("DELETE FROM meta WHERE key IN ('metadata_version', 'dump_fingerprint')", [])
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = prepared {
+        let _ = remove_db_files(&staged, &["", "-wal", "-shm"]);
+        return Err(e);
+    }
+    // The WAL of the replaced database must not be applied to the new one
+    remove_db_files(db_path, &["-wal", "-shm"])?;
+    fs::rename(&staged, db_path).map_err(|e| format!("Не удалось заменить {}: {}", db_path.display(), e))?;
+    Ok(summary)
+}
+
 /// Save symbol/file/object/calls counts to meta table for fast stats retrieval.
 fn save_stats_to_meta(conn: &Connection) {
     let sym: i64 = conn.query_row("SELECT COUNT(*) FROM symbols", [], |r| r.get(0)).unwrap_or(0);
@@ -612,7 +1506,13 @@ fn save_stats_to_meta(conn: &Connection) {
 struct ParsedFile {
     rel_path: String,
     mtime: u64,
+    /// `fnv_hash` of the decoded file text.
+    content_hash: u64,
     symbols: Vec<crate::parser::bsl_ast::BslSymbol>,
+    layout: bsl_layout::BslModuleLayout,
+    queries: Vec<bsl_query::BslQuery>,
+    /// Lowercased method names in string literals (`bsl_query::extract_method_refs`).
+    method_refs: Vec<String>,
     /// true = brand new file, never indexed before (skip DELETE)
     is_new: bool,
 }
@@ -628,11 +1528,24 @@ fn file_mtime(path: &Path) -> u64 {
 }
 
 /// Load the `indexed_files` table into a HashMap<rel_path, mtime>.
-fn load_indexed_mtimes(conn: &Connection) -> std::collections::HashMap<String, u64> {
+/// Stored state of an indexed file: mtime and content hash (`None` for rows indexed
+/// before hashes were stored).
+struct IndexedFileState {
+    mtime: u64,
+    content_hash: Option<u64>,
+}
+
+fn load_indexed_files(conn: &Connection) -> std::collections::HashMap<String, IndexedFileState> {
     let mut map = std::collections::HashMap::new();
-    if let Ok(mut stmt) = conn.prepare("SELECT filepath, modified_at FROM indexed_files") {
+    if let Ok(mut stmt) = conn.prepare("SELECT filepath, modified_at, content_hash FROM indexed_files") {
         let _ = stmt.query_map([], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+            Ok((
+                row.get::<_, String>(0)?,
+                IndexedFileState {
+                    mtime: row.get::<_, i64>(1)? as u64,
+                    content_hash: row.get::<_, Option<i64>>(2)?.map(|h| h as u64),
+                },
+            ))
         }).map(|rows| {
             for row in rows.flatten() {
                 map.insert(row.0, row.1);
@@ -647,38 +1560,184 @@ pub struct SyncStats {
     pub added: usize,
     pub updated: usize,
     pub removed: usize,
+    /// Files whose mtime changed but content did not (checkout, re-export) — not re-parsed.
+    pub unchanged: usize,
     pub total_symbols: usize,
 }
 
-/// Incremental sync: only re-parse files that are new or have changed mtime.
+/// Serializes index writers: the startup sync, the `sync_index` tool and the filesystem watcher.
+static SYNC_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Outcome of reading a file whose mtime changed.
+enum SyncCandidate {
+    Parsed(ParsedFile),
+    /// Same content hash as indexed — only the stored mtime needs updating.
+    Touched { rel_path: String, mtime: u64 },
+}
+
+/// Inserts of one parsed module's rows, prepared once per `build_index` or `apply_sync`.
+struct Statements<'conn> {
+    symbol: rusqlite::Statement<'conn>,
+    param: rusqlite::Statement<'conn>,
+    doc: rusqlite::Statement<'conn>,
+    doc_param: rusqlite::Statement<'conn>,
+    interception: rusqlite::Statement<'conn>,
+    call: rusqlite::Statement<'conn>,
+    region: rusqlite::Statement<'conn>,
+    var: rusqlite::Statement<'conn>,
+    query: rusqlite::Statement<'conn>,
+    query_table: rusqlite::Statement<'conn>,
+    string_ref: rusqlite::Statement<'conn>,
+    file: rusqlite::Statement<'conn>,
+}
+
+impl<'conn> Statements<'conn> {
+    fn prepare(conn: &'conn Connection) -> Result<Self, String> {
+        let prepare = |sql: &str| conn.prepare(sql).map_err(|e| e.to_string());
+        Ok(Statements {
+            symbol: prepare(
+                "INSERT INTO symbols (name, name_lower, kind, file, start_line, end_line, is_export, directive, origin)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?,
+            param: prepare(
+                "INSERT INTO symbol_params (symbol_id, position, name, by_value, default_value)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?,
+            doc: prepare(
+                "INSERT INTO symbol_docs (symbol_id, summary, returns_type, returns_description, examples)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?,
+            doc_param: prepare(
+                "INSERT INTO symbol_doc_params (symbol_id, position, name, types, description)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?,
+            interception: prepare(
+                "INSERT INTO symbol_interceptions (symbol_id, kind, target, target_lower, base_file)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?,
+            call: prepare(
+                "INSERT INTO calls (caller_file, caller_name, caller_name_lower, callee_name, callee_name_lower, callee_receiver, callee_receiver_lower)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?,
+            region: prepare(
+                "INSERT INTO module_regions (file, name, name_lower, start_line, end_line, depth)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?,
+            var: prepare(
+                "INSERT INTO module_vars (file, name, name_lower, line, is_export, directive)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?,
+            query: prepare("INSERT INTO queries (file, symbol_id, start_line, end_line) VALUES (?1, ?2, ?3, ?4)")?,
+            query_table: prepare(
+                "INSERT INTO query_tables (query_id, file, name, object, object_lower, part, part_lower, fields, fields_lower)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?,
+            string_ref: prepare("INSERT INTO string_refs (file, name_lower) VALUES (?1, ?2)")?,
+            file: prepare(
+                "INSERT OR REPLACE INTO indexed_files
+                 (filepath, modified_at, path_lower, file_name, file_name_lower, extension, object_type, object_name, module_kind, source_kind, content_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?,
+        })
+    }
+}
+
+/// Insert a parsed module: symbols with their parameters, docs, interceptions and calls, the
+/// module layout, queries, method names in string literals and its `indexed_files` row. Rows of
+/// an earlier version of the file must be deleted first. Returns the number of symbols written.
+fn write_parsed_file(st: &mut Statements, pf: &ParsedFile) -> usize {
+    let (origin, base_file) = split_origin(&pf.rel_path);
+    let mut symbol_lines: Vec<(u32, u32, i64)> = Vec::new();
+    for sym in &pf.symbols {
+        let name_lower = sym.name.to_lowercase();
+        let Ok(symbol_id) = st.symbol.insert(params![
+            sym.name, name_lower, sym.kind,
+            pf.rel_path, sym.start_line, sym.end_line, sym.is_export as i32,
+            sym.directive, origin
+        ]) else {
+            continue;
+        };
+        symbol_lines.push((sym.start_line, sym.end_line, symbol_id));
+        for (pos, p) in sym.params.iter().enumerate() {
+            let _ = st.param.execute(params![symbol_id, pos as i64, p.name, p.by_value as i32, p.default]);
+        }
+        if let Some(i) = &sym.interception {
+            // The intercepted method lives in the same module of the base configuration
+            let _ = st.interception.execute(params![symbol_id, i.kind, i.target, i.target.to_lowercase(), base_file]);
+        }
+        if let Some(doc) = &sym.doc {
+            let _ = st.doc.execute(params![
+                symbol_id, doc.summary,
+                doc.returns.as_ref().map(|r| &r.types),
+                doc.returns.as_ref().map(|r| &r.description),
+                doc.examples
+            ]);
+            for (pos, p) in doc.params.iter().enumerate() {
+                let _ = st.doc_param.execute(params![symbol_id, pos as i64, p.name, p.types, p.description]);
+            }
+        }
+        for callee in &sym.calls {
+            let _ = st.call.execute(params![
+                pf.rel_path, sym.name, name_lower,
+                callee.name, callee.name.to_lowercase(),
+                callee.receiver, callee.receiver.as_ref().map(|r| r.to_lowercase())
+            ]);
+        }
+    }
+    for r in &pf.layout.regions {
+        let _ = st.region.execute(params![pf.rel_path, r.name, r.name.to_lowercase(), r.start_line, r.end_line, r.depth]);
+    }
+    for v in &pf.layout.variables {
+        let _ = st.var.execute(params![pf.rel_path, v.name, v.name.to_lowercase(), v.line, v.is_export as i32, v.directive]);
+    }
+    for q in &pf.queries {
+        let symbol_id = symbol_lines
+            .iter()
+            .find(|(start, end, _)| (*start..=*end).contains(&q.start_line))
+            .map(|(_, _, id)| *id);
+        let Ok(query_id) = st.query.insert(params![pf.rel_path, symbol_id, q.start_line, q.end_line]) else {
+            continue;
+        };
+        for t in &q.tables {
+            let fields = t.fields.join(", ");
+            let _ = st.query_table.execute(params![
+                query_id, pf.rel_path, t.name, t.object, t.object.to_lowercase(),
+                t.part, t.part.as_ref().map(|p| p.to_lowercase()), fields, fields.to_lowercase()
+            ]);
+        }
+    }
+    for name in &pf.method_refs {
+        let _ = st.string_ref.execute(params![pf.rel_path, name]);
+    }
+    let (obj_type, obj_name, module_kind) = infer_object_from_path(&pf.rel_path);
+    let file_name = pf.rel_path.rsplit('/').next().unwrap_or(&pf.rel_path);
+    let extension = file_name.rsplit('.').next().filter(|e| *e != file_name).unwrap_or("").to_lowercase();
+    let _ = st.file.execute(params![
+        pf.rel_path, pf.mtime as i64,
+        pf.rel_path.to_lowercase(), file_name, file_name.to_lowercase(), extension,
+        obj_type.as_deref(), obj_name.as_deref(), module_kind.as_deref(), source_kind(&extension),
+        pf.content_hash as i64
+    ]);
+    symbol_lines.len()
+}
+
+/// Incremental sync: files with a changed mtime are hashed, and only those whose content
+/// hash differs from the indexed one (or new files) are re-parsed.
 /// Also removes symbols for deleted files.
 /// Returns statistics of what changed.
 pub fn sync_index(root: &Path, db_path: &Path) -> Result<SyncStats, String> {
+    let _guard = SYNC_LOCK.lock().unwrap_or_else(|e| e.into_inner());
     eprintln!("SEARCH_STATUS:syncing:0:Сравнение файлов...");
 
     let conn = init_db(db_path).map_err(|e| format!("Ошибка БД: {}", e))?;
-    let indexed_mtimes = load_indexed_mtimes(&conn);
-
-    // Scan filesystem — collect all current .bsl files with their mtime
-    let root_owned = root.to_path_buf();
-    let all_disk_files: Vec<(String, u64, PathBuf)> = WalkBuilder::new(root)
-        .standard_filters(true)
-        .follow_links(false)
-        .build()
+    let indexed = load_indexed_files(&conn);
+
+    // Scan filesystem — collect all current .bsl files (base + extensions) with their mtime
+    let all_disk_files: Vec<(String, u64, PathBuf)> = collect_bsl_files(root)
         .into_iter()
-        .flatten()
-        .filter(|e| {
-            e.path().is_file()
-                && e.path().extension().and_then(|x| x.to_str()) == Some("bsl")
-        })
-        .filter_map(|e| {
-            let path = e.into_path();
-            let rel = path
-                .strip_prefix(&root_owned)
-                .map(|p| p.to_string_lossy().replace('\\', "/"))
-                .unwrap_or_else(|_| path.to_string_lossy().replace('\\', "/"));
+        .map(|(rel, path)| {
             let mtime = file_mtime(&path);
-            Some((rel, mtime, path))
+            (rel, mtime, path)
         })
         .collect();
 
@@ -686,32 +1745,93 @@ pub fn sync_index(root: &Path, db_path: &Path) -> Result<SyncStats, String> {
         all_disk_files.iter().map(|(r, _, _)| r.clone()).collect();
 
     // Detect deleted files
-    let deleted: Vec<String> = indexed_mtimes
+    let deleted: Vec<String> = indexed
         .keys()
         .filter(|k| !disk_set.contains(*k))
         .cloned()
         .collect();
 
-    // Detect new / changed files
-    let to_parse: Vec<(String, u64, PathBuf)> = all_disk_files
+    // Detect new / touched files. Any mtime difference counts (a checkout may move it back);
+    // the content hash decides below whether the file is actually re-parsed.
+    let to_check: Vec<(String, u64, PathBuf)> = all_disk_files
         .into_iter()
         .filter(|(rel, mtime, _)| {
-            match indexed_mtimes.get(rel) {
-                None => true,               // new file
-                Some(&old) => *mtime > old, // changed file
+            match indexed.get(rel) {
+                None => true,                     // new file
+                Some(old) => *mtime != old.mtime, // touched file
+            }
+        })
+        .collect();
+
+    apply_sync(conn, root, db_path, &indexed, deleted, to_check)
+}
+
+/// Incremental sync of specific paths reported by the filesystem watcher.
+///
+/// Each path may be a `.bsl` file (added, changed or removed) or a directory: an existing
+/// directory is scanned for `.bsl` files, a removed one drops every indexed file under it.
+/// Other paths are ignored. Content hashes decide what is actually re-parsed, as in `sync_index`.
+pub fn sync_paths(root: &Path, db_path: &Path, paths: &[PathBuf]) -> Result<SyncStats, String> {
+    let _guard = SYNC_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let conn = init_db(db_path).map_err(|e| format!("Ошибка БД: {}", e))?;
+    let indexed = load_indexed_files(&conn);
+    let rel_of = |path: &Path| index_rel_path(root, path);
+    let is_bsl = |path: &Path| path.extension().and_then(|x| x.to_str()) == Some("bsl");
+
+    let mut deleted: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
+    let mut on_disk: std::collections::BTreeMap<String, PathBuf> = std::collections::BTreeMap::new();
+    for path in paths {
+        if path.is_file() {
+            if is_bsl(path) {
+                on_disk.insert(rel_of(path), path.clone());
+            }
+        } else if path.is_dir() {
+            for entry in WalkBuilder::new(path).standard_filters(true).follow_links(false).build().flatten() {
+                if entry.path().is_file() && is_bsl(entry.path()) {
+                    on_disk.insert(rel_of(entry.path()), entry.into_path());
+                }
             }
+        } else {
+            // Removed: a file, or a directory with everything indexed below it
+            let rel = rel_of(path);
+            let dir_prefix = format!("{}/", rel);
+            deleted.extend(
+                indexed.keys().filter(|k| **k == rel || k.starts_with(&dir_prefix)).cloned(),
+            );
+        }
+    }
+
+    let to_check: Vec<(String, u64, PathBuf)> = on_disk
+        .into_iter()
+        .filter(|(rel, _)| !deleted.contains(rel))
+        .map(|(rel, path)| {
+            let mtime = file_mtime(&path);
+            (rel, mtime, path)
         })
         .collect();
 
-    let added = to_parse.iter().filter(|(r, _, _)| !indexed_mtimes.contains_key(r)).count();
-    let updated = to_parse.len() - added;
+    apply_sync(conn, root, db_path, &indexed, deleted.into_iter().collect(), to_check)
+}
+
+/// Shared tail of `sync_index` / `sync_paths`: hash and re-parse `to_check`, drop `deleted`,
+/// then refresh call resolution, the semantic FTS, the code index and cached stats.
+fn apply_sync(
+    conn: Connection,
+    root: &Path,
+    db_path: &Path,
+    indexed: &std::collections::HashMap<String, IndexedFileState>,
+    deleted: Vec<String>,
+    to_check: Vec<(String, u64, PathBuf)>,
+) -> Result<SyncStats, String> {
+    let new_files = to_check.iter().filter(|(r, _, _)| !indexed.contains_key(r)).count();
 
     eprintln!(
         "SEARCH_STATUS:syncing:10:+{}новых  ~{}изм  -{}удал",
-        added, updated, deleted.len()
+        new_files, to_check.len() - new_files, deleted.len()
     );
 
-    if deleted.is_empty() && to_parse.is_empty() {
+    if deleted.is_empty() && to_check.is_empty() {
         // Update built_at timestamp so UI knows we explicitly checked just now
         let now_unix = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
         if let Ok(conn) = Connection::open(db_path) {
@@ -725,28 +1845,81 @@ pub fn sync_index(root: &Path, db_path: &Path) -> Result<SyncStats, String> {
         // Nothing to do — index is up-to-date
         let total_symbols = symbol_count(db_path);
         eprintln!("SEARCH_STATUS:syncing:100:Индекс актуален");
-        return Ok(SyncStats { added: 0, updated: 0, removed: 0, total_symbols });
+        return Ok(SyncStats { added: 0, updated: 0, removed: 0, unchanged: 0, total_symbols });
     }
 
-    // ── Parallel parse of new/changed files ──────────────────────────────────
-    let total_to_parse = to_parse.len();
+    // ── Parallel hash + parse of new/touched files ───────────────────────────
+    let total_to_check = to_check.len();
     let processed = Arc::new(AtomicUsize::new(0));
 
-    let parsed: Vec<ParsedFile> = to_parse
+    let checked: Vec<SyncCandidate> = to_check
         .par_iter()
         .filter_map(|(rel_path, mtime, path)| {
             let buf = read_file_to_string_lossy(path).ok()?;
-            let symbols = bsl_ast::extract_symbols(&buf);
-            let is_new = !indexed_mtimes.contains_key(rel_path);
+            let content_hash = fnv_hash(&buf);
+            let previous = indexed.get(rel_path);
             let done = processed.fetch_add(1, Ordering::Relaxed) + 1;
-            if total_to_parse > 0 && done % (total_to_parse / 10).max(1) == 0 {
-                let pct = done * 80 / total_to_parse + 10;
-                eprintln!("SEARCH_STATUS:syncing:{}:Парсинг {}/{}", pct, done, total_to_parse);
+            if total_to_check > 0 && done % (total_to_check / 10).max(1) == 0 {
+                let pct = done * 80 / total_to_check + 10;
+                eprintln!("SEARCH_STATUS:syncing:{}:Парсинг {}/{}", pct, done, total_to_check);
             }
-            Some(ParsedFile { rel_path: rel_path.clone(), mtime: *mtime, symbols, is_new })
+            if previous.and_then(|p| p.content_hash) == Some(content_hash) {
+                return Some(SyncCandidate::Touched { rel_path: rel_path.clone(), mtime: *mtime });
+            }
+            let symbols = bsl_ast::extract_symbols(&buf);
+            let layout = bsl_layout::extract_module_layout(&buf);
+            let queries = bsl_query::extract_queries(&buf);
+            let method_refs = bsl_query::extract_method_refs(&buf);
+            Some(SyncCandidate::Parsed(ParsedFile {
+                rel_path: rel_path.clone(),
+                mtime: *mtime,
+                content_hash,
+                symbols,
+                layout,
+                queries,
+                method_refs,
+                is_new: previous.is_none(),
+            }))
         })
         .collect();
 
+    let mut parsed: Vec<ParsedFile> = Vec::new();
+    let mut touched: Vec<(String, u64)> = Vec::new();
+    for candidate in checked {
+        match candidate {
+            SyncCandidate::Parsed(pf) => parsed.push(pf),
+            SyncCandidate::Touched { rel_path, mtime } => touched.push((rel_path, mtime)),
+        }
+    }
+    // Counted from what was read: a file that failed to read is neither added nor updated
+    let added = parsed.iter().filter(|pf| pf.is_new).count();
+    let updated = parsed.len() - added;
+    let unchanged = touched.len();
+
+    if deleted.is_empty() && parsed.is_empty() {
+        // Only mtimes moved — remember them so the next sync does not hash these files again
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        {
+            let mut upd_mtime = tx
+                .prepare("UPDATE indexed_files SET modified_at = ?2 WHERE filepath = ?1")
+                .map_err(|e| e.to_string())?;
+            for (rel, mtime) in &touched {
+                let _ = upd_mtime.execute(params![rel, *mtime as i64]);
+            }
+        }
+        let now_unix = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+        let _ = tx.execute(
+            "INSERT INTO meta (key, value) VALUES ('built_at', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = ?1",
+            [now_unix.to_string()],
+        );
+        tx.commit().map_err(|e| e.to_string())?;
+
+        let total_symbols = symbol_count(db_path);
+        eprintln!("SEARCH_STATUS:syncing:100:Индекс актуален ({} файлов без изменений)", unchanged);
+        return Ok(SyncStats { added, updated, removed: 0, unchanged, total_symbols });
+    }
+
     // ── Serial phase: apply changes to SQLite ─────────────────────────────────
     eprintln!("SEARCH_STATUS:syncing:90:Запись изменений...");
 
@@ -771,10 +1944,14 @@ pub fn sync_index(root: &Path, db_path: &Path) -> Result<SyncStats, String> {
     {
         // Remove deleted files
         if !deleted.is_empty() {
+            let mut del_children = prepare_child_deletes(&tx)?;
             let mut del_sym  = tx.prepare("DELETE FROM symbols WHERE file = ?1").map_err(|e| e.to_string())?;
             let mut del_call = tx.prepare("DELETE FROM calls WHERE caller_file = ?1").map_err(|e| e.to_string())?;
             let mut del_file = tx.prepare("DELETE FROM indexed_files WHERE filepath = ?1").map_err(|e| e.to_string())?;
             for rel in &deleted {
+                for del in del_children.iter_mut() {
+                    let _ = del.execute([rel]);
+                }
                 let _ = del_sym.execute([rel]);
                 let _ = del_call.execute([rel]);
                 let _ = del_file.execute([rel]);
@@ -782,21 +1959,17 @@ pub fn sync_index(root: &Path, db_path: &Path) -> Result<SyncStats, String> {
         }
 
         // Prepare all INSERT/DELETE statements ONCE and reuse — avoids SQL re-compilation per row
+        let mut del_children = prepare_child_deletes(&tx)?;
         let mut del_sym  = tx.prepare("DELETE FROM symbols WHERE file = ?1").map_err(|e| e.to_string())?;
         let mut del_call = tx.prepare("DELETE FROM calls WHERE caller_file = ?1").map_err(|e| e.to_string())?;
-        let mut ins_sym  = tx.prepare(
-            "INSERT INTO symbols (name, name_lower, kind, file, start_line, end_line, is_export)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
-        ).map_err(|e| e.to_string())?;
-        let mut ins_call = tx.prepare(
-            "INSERT INTO calls (caller_file, caller_name, caller_name_lower, callee_name, callee_name_lower)
-             VALUES (?1, ?2, ?3, ?4, ?5)"
-        ).map_err(|e| e.to_string())?;
-        let mut ins_file = tx.prepare(
-            "INSERT OR REPLACE INTO indexed_files
-             (filepath, modified_at, path_lower, file_name, file_name_lower, extension, object_type, object_name, module_kind, source_kind)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
-        ).map_err(|e| e.to_string())?;
+        let mut statements = Statements::prepare(&tx)?;
+
+        let mut upd_mtime = tx
+            .prepare("UPDATE indexed_files SET modified_at = ?2 WHERE filepath = ?1")
+            .map_err(|e| e.to_string())?;
+        for (rel, mtime) in &touched {
+            let _ = upd_mtime.execute(params![rel, *mtime as i64]);
+        }
 
         for (i, pf) in parsed.iter().enumerate() {
             if i > 0 && i % 5000 == 0 {
@@ -805,33 +1978,13 @@ pub fn sync_index(root: &Path, db_path: &Path) -> Result<SyncStats, String> {
             }
             // Only DELETE existing data for changed files — skip for brand new files
             if !pf.is_new {
+                for del in del_children.iter_mut() {
+                    let _ = del.execute([&pf.rel_path]);
+                }
                 let _ = del_sym.execute([&pf.rel_path]);
                 let _ = del_call.execute([&pf.rel_path]);
             }
-            for sym in &pf.symbols {
-                let name_lower = sym.name.to_lowercase();
-                let _ = ins_sym.execute(params![
-                    sym.name, name_lower, sym.kind,
-                    pf.rel_path, sym.start_line, sym.end_line, sym.is_export as i32
-                ]);
-                for callee in &sym.calls {
-                    let _ = ins_call.execute(params![
-                        pf.rel_path, sym.name, name_lower,
-                        callee, callee.to_lowercase()
-                    ]);
-                }
-            }
-            let (obj_type, obj_name, module_kind) = infer_object_from_path_index(&pf.rel_path);
-            let path_lower = pf.rel_path.to_lowercase();
-            let file_name = pf.rel_path.rsplit('/').next().unwrap_or(&pf.rel_path).to_string();
-            let file_name_lower = file_name.to_lowercase();
-            let extension = file_name.rsplit('.').next().filter(|e| *e != file_name.as_str()).unwrap_or("").to_lowercase();
-            let source_kind = if extension == "bsl" { "bsl" } else if extension == "xml" { "xml" } else { "other" };
-            let _ = ins_file.execute(params![
-                pf.rel_path, pf.mtime as i64,
-                path_lower, file_name, file_name_lower, extension,
-                obj_type.as_deref(), obj_name.as_deref(), module_kind.as_deref(), source_kind
-            ]);
+            write_parsed_file(&mut statements, pf);
         }
         // statements dropped here — tx borrow ends
     }
@@ -848,10 +2001,18 @@ pub fn sync_index(root: &Path, db_path: &Path) -> Result<SyncStats, String> {
          PRAGMA synchronous=NORMAL;"
     );
 
+    // Link calls to callee modules (targets may have moved with the changed files)
+    resolve_calls(&conn);
+
     // Sync FTS5 semantic index for changed/new/deleted files
     sync_semantic_fts(&conn, &deleted, &parsed);
     crate::semantic::rebuild_symbol_weights(&conn);
 
+    // Replace module texts in the trigram code index
+    let reindexed: Vec<String> = parsed.iter().map(|pf| pf.rel_path.clone()).collect();
+    remove_code_contents(&conn, &deleted);
+    index_code_contents(&conn, root, &reindexed);
+
     // Update built_at timestamp
     let ts = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -870,151 +2031,114 @@ pub fn sync_index(root: &Path, db_path: &Path) -> Result<SyncStats, String> {
         added,
         updated,
         removed: deleted.len(),
+        unchanged,
         total_symbols,
     })
 }
 
-/// Full (re)build: clear all symbols and re-index everything in parallel.
-/// Also fills `indexed_files` with mtime for each file.
+/// Parsed modules waiting for the writer in `build_index`. Bounds memory: parser threads block
+/// while the queue is full, so at most this many files (text and parse results) are held at once.
+const PARSE_QUEUE_CAPACITY: usize = 256;
+
+/// Files per write transaction in `build_index`.
+const WRITE_BATCH_FILES: usize = 1000;
+
+/// Full (re)build: clear all symbols and re-index everything.
+///
+/// A streaming pipeline: rayon threads read and parse modules and send them through a bounded
+/// channel to a single writer, which inserts with prepared statements and commits every
+/// `WRITE_BATCH_FILES` files. Memory stays flat whatever the configuration size. Module texts go
+/// into the trigram code index on the way. Also fills `indexed_files` with mtime for each file.
 /// Use `sync_index` for incremental updates after initial build.
-pub fn build_index(root: &Path, db_path: &Path) -> Result<usize, String> {
-    eprintln!("SEARCH_STATUS:indexing:0:Сканирование файлов...");
-
-    // Collect all .bsl file paths first to know total count
-    let bsl_paths: Vec<(PathBuf, u64)> = WalkBuilder::new(root)
-        .standard_filters(true)
-        .follow_links(false)
-        .build()
-        .into_iter()
-        .flatten()
-        .filter(|e| {
-            e.path().is_file()
-                && e.path()
-                    .extension()
-                    .and_then(|x| x.to_str())
-                    == Some("bsl")
-        })
-        .map(|e| {
-            let path = e.into_path();
-            let mtime = file_mtime(&path);
-            (path, mtime)
-        })
-        .collect();
+///
+/// `on_progress(percent, message, symbols)` is called for every progress step.
+pub fn build_index(root: &Path, db_path: &Path, on_progress: &dyn Fn(u32, &str, usize)) -> Result<usize, String> {
+    let _guard = SYNC_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    on_progress(0, "Сканирование файлов...", 0);
+
+    // Collect all .bsl file paths (base + extensions) first to know total count
+    let bsl_paths: Vec<(String, PathBuf)> = collect_bsl_files(root);
 
     let total_files = bsl_paths.len();
     if total_files == 0 {
         return Err("В директории не найдено BSL файлов".to_string());
     }
 
-    eprintln!("SEARCH_STATUS:indexing:5:Парсинг {} файлов...", total_files);
-
-    // ── Parallel phase: read + parse (CPU-bound, rayon thread pool) ──────────
-    let processed = Arc::new(AtomicUsize::new(0));
-    let root_owned = root.to_path_buf();
-
-    let parsed_files: Vec<ParsedFile> = bsl_paths
-        .par_iter()
-        .filter_map(|(path, mtime)| {
-            let buf = read_file_to_string_lossy(path).ok()?;
-
-            let rel_path = path
-                .strip_prefix(&root_owned)
-                .map(|p| p.to_string_lossy().replace('\\', "/"))
-                .unwrap_or_else(|_| path.to_string_lossy().replace('\\', "/"));
-
-            let symbols = bsl_ast::extract_symbols(&buf);
-
-            // Progress reporting ~every 10%
-            let done = processed.fetch_add(1, Ordering::Relaxed) + 1;
-            let pct = done * 90 / total_files + 5; // range 5..95
-            if done % (total_files / 10).max(1) == 0 {
-                eprintln!(
-                    "SEARCH_STATUS:indexing:{}:Парсинг {}/{} файлов",
-                    pct, done, total_files
-                );
-            }
-
-            Some(ParsedFile { rel_path, mtime: *mtime, symbols, is_new: true })
-        })
-        .collect();
-
-    // ── Serial phase: batch INSERT into SQLite ────────────────────────────────
-    eprintln!("SEARCH_STATUS:indexing:95:Запись в индекс...");
-
     let conn = init_db(db_path).map_err(|e| format!("Ошибка БД: {}", e))?;
 
     // Maximum write speed: memory journal, no sync, large cache, no indexes during insert
-    let _ = conn.execute_batch(
+    let _ = conn.execute_batch(&format!(
         "PRAGMA journal_mode=MEMORY;
          PRAGMA synchronous=OFF;
          PRAGMA cache_size=-262144;
          PRAGMA temp_store=MEMORY;
          DELETE FROM symbols;
+         {}
          DELETE FROM indexed_files;
-         DELETE FROM calls;
+         DELETE FROM calls WHERE edge_kind = 'call';
+         INSERT INTO code_trigrams(code_trigrams) VALUES('delete-all');
+         DELETE FROM code_files;
          DROP INDEX IF EXISTS idx_name_lower;
          DROP INDEX IF EXISTS idx_file;
          DROP INDEX IF EXISTS idx_calls_caller;
-         DROP INDEX IF EXISTS idx_calls_callee;"
-    );
+         DROP INDEX IF EXISTS idx_calls_callee;",
+        clear_child_tables_sql()
+    ));
+
+    let mut progress = BuildProgress::new(total_files, on_progress);
+    progress.stage(0, &format!("Индексация {} файлов...", total_files));
 
     let mut total_symbols = 0usize;
-    let total_parsed = parsed_files.len();
 
-    // Single transaction + prepared statements reused for every row.
-    // Statements dropped in inner block so tx can be committed.
-    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    // Prepared statements live on the connection and are reused across the write batches
     {
-        let mut ins_sym = tx.prepare(
-            "INSERT INTO symbols (name, name_lower, kind, file, start_line, end_line, is_export)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
-        ).map_err(|e| e.to_string())?;
-        let mut ins_call = tx.prepare(
-            "INSERT INTO calls (caller_file, caller_name, caller_name_lower, callee_name, callee_name_lower)
-             VALUES (?1, ?2, ?3, ?4, ?5)"
-        ).map_err(|e| e.to_string())?;
-        let mut ins_file = tx.prepare(
-            "INSERT OR REPLACE INTO indexed_files
-             (filepath, modified_at, path_lower, file_name, file_name_lower, extension, object_type, object_name, module_kind, source_kind)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
-        ).map_err(|e| e.to_string())?;
-
-        for (i, pf) in parsed_files.iter().enumerate() {
-            if i > 0 && i % 5000 == 0 {
-                eprintln!("SEARCH_STATUS:indexing:95:Запись {}/{}...", i, total_parsed);
-            }
-            for sym in &pf.symbols {
-                let name_lower = sym.name.to_lowercase();
-                let _ = ins_sym.execute(params![
-                    sym.name, name_lower, sym.kind,
-                    pf.rel_path, sym.start_line, sym.end_line, sym.is_export as i32
-                ]);
-                for callee in &sym.calls {
-                    let _ = ins_call.execute(params![
-                        pf.rel_path, sym.name, name_lower,
-                        callee, callee.to_lowercase()
-                    ]);
+        let mut statements = Statements::prepare(&conn)?;
+        let mut ins_code_file = conn.prepare("INSERT INTO code_files (file) VALUES (?1)").map_err(|e| e.to_string())?;
+        let mut ins_code_text = conn
+            .prepare("INSERT INTO code_trigrams (rowid, content) VALUES (?1, ?2)")
+            .map_err(|e| e.to_string())?;
+
+        let (sender, receiver) = std::sync::mpsc::sync_channel::<(ParsedFile, String)>(PARSE_QUEUE_CAPACITY);
+        std::thread::scope(|scope| -> Result<(), String> {
+            // Producer: read + parse (CPU-bound, rayon thread pool). Stops early once the writer is gone.
+            scope.spawn(|| {
+                let _ = bsl_paths.par_iter().try_for_each_with(sender, |sender, (rel_path, path)| {
+                    let Ok(buf) = read_file_to_string_lossy(path) else { return Ok(()) };
+                    let parsed = ParsedFile {
+                        rel_path: rel_path.clone(),
+                        mtime: file_mtime(path),
+                        content_hash: fnv_hash(&buf),
+                        symbols: bsl_ast::extract_symbols(&buf),
+                        layout: bsl_layout::extract_module_layout(&buf),
+                        queries: bsl_query::extract_queries(&buf),
+                        method_refs: bsl_query::extract_method_refs(&buf),
+                        is_new: true,
+                    };
+                    sender.send((parsed, buf)).map_err(|_| ())
+                });
+            });
+
+            // Consumer: the single writer, in the order files finish parsing
+            conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+            for (written, (pf, text)) in receiver.into_iter().enumerate() {
+                total_symbols += write_parsed_file(&mut statements, &pf);
+                if ins_code_file.execute(params![pf.rel_path]).is_ok() {
+                    let _ = ins_code_text.execute(params![conn.last_insert_rowid(), text]);
+                }
+
+                let written = written + 1;
+                if written % WRITE_BATCH_FILES == 0 {
+                    conn.execute_batch("COMMIT; BEGIN").map_err(|e| e.to_string())?;
                 }
-                total_symbols += 1;
+                progress.file_written(written, total_symbols);
             }
-            let (obj_type, obj_name, module_kind) = infer_object_from_path_index(&pf.rel_path);
-            let path_lower = pf.rel_path.to_lowercase();
-            let file_name = pf.rel_path.rsplit('/').next().unwrap_or(&pf.rel_path).to_string();
-            let file_name_lower = file_name.to_lowercase();
-            let extension = file_name.rsplit('.').next().filter(|e| *e != file_name.as_str()).unwrap_or("").to_lowercase();
-            let source_kind = if extension == "bsl" { "bsl" } else if extension == "xml" { "xml" } else { "other" };
-            let _ = ins_file.execute(params![
-                pf.rel_path, pf.mtime as i64,
-                path_lower, file_name, file_name_lower, extension,
-                obj_type.as_deref(), obj_name.as_deref(), module_kind.as_deref(), source_kind
-            ]);
-        }
+            conn.execute_batch("COMMIT").map_err(|e| e.to_string())
+        })?;
         // statements dropped here
     }
-    tx.commit().map_err(|e| e.to_string())?;
 
     // Recreate indexes and switch back to WAL
-    eprintln!("SEARCH_STATUS:indexing:98:Создание индексов...");
+    progress.stage(96, "Создание индексов...");
     let _ = conn.execute_batch(
         "CREATE INDEX IF NOT EXISTS idx_name_lower ON symbols(name_lower);
          CREATE INDEX IF NOT EXISTS idx_file ON symbols(file);
@@ -1024,8 +2148,11 @@ pub fn build_index(root: &Path, db_path: &Path) -> Result<usize, String> {
          PRAGMA synchronous=NORMAL;"
     );
 
+    progress.stage(97, "Разрешение вызовов...");
+    resolve_calls(&conn);
+
     // Build FTS5 semantic index (symbol_terms)
-    eprintln!("SEARCH_STATUS:indexing:99:Семантическая индексация...");
+    progress.stage(98, "Семантическая индексация...");
     build_semantic_fts(&conn);
     crate::semantic::rebuild_symbol_weights(&conn);
 
@@ -1040,40 +2167,164 @@ pub fn build_index(root: &Path, db_path: &Path) -> Result<usize, String> {
     );
 
     // Cache counts so stats tool is O(1) instead of COUNT(*)
+    progress.stage(99, "Сохранение статистики...");
     save_stats_to_meta(&conn);
 
     Ok(total_symbols)
 }
 
-/// (Re)build symbol_terms FTS5 table from current symbols.
-/// Runs in a single transaction for speed. Used after full build_index.
-fn build_semantic_fts(conn: &Connection) {
-    let _ = conn.execute_batch("DELETE FROM symbol_terms;");
-    let pairs: Vec<(i64, String)> = {
-        match conn.prepare("SELECT id, name FROM symbols") {
-            Ok(mut stmt) => stmt
-                .query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?)))
-                .map(|rows| rows.flatten().collect())
-                .unwrap_or_default(),
-            Err(_) => return,
+/// Minimum interval between progress lines of `build_index`.
+const PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Progress of `build_index`, passed to its `on_progress` callback. Writing files takes 0–95%,
+/// the final stages the rest.
+struct BuildProgress<'a> {
+    total: usize,
+    symbols: usize,
+    started: std::time::Instant,
+    last_report: Option<std::time::Instant>,
+    on_progress: &'a dyn Fn(u32, &str, usize),
+}
+
+impl<'a> BuildProgress<'a> {
+    fn new(total: usize, on_progress: &'a dyn Fn(u32, &str, usize)) -> Self {
+        BuildProgress { total, symbols: 0, started: std::time::Instant::now(), last_report: None, on_progress }
+    }
+
+    /// `done` files written so far. Throttled to `PROGRESS_INTERVAL`; the last file is always
+    /// reported. The ETA extrapolates the average time per file since the build started.
+    fn file_written(&mut self, done: usize, symbols: usize) {
+        self.symbols = symbols;
+        let now = std::time::Instant::now();
+        if done < self.total && self.last_report.is_some_and(|t| now - t < PROGRESS_INTERVAL) {
+            return;
         }
-    };
-    if pairs.is_empty() {
-        return;
+        self.last_report = Some(now);
+
+        let percent = (done * 95 / self.total) as u32;
+        let mut message = format!("Индексация {}/{} файлов", done, self.total);
+        let elapsed = now - self.started;
+        if done < self.total && elapsed.as_secs() >= 1 {
+            let eta = (elapsed.as_secs_f64() / done as f64 * (self.total - done) as f64).round() as u64;
+            message.push_str(&format!(" • осталось {}", format_eta(eta)));
+        }
+        (self.on_progress)(percent, &message, symbols);
+    }
+
+    /// A step after all files are written (or before the first one).
+    fn stage(&self, percent: u32, message: &str) {
+        (self.on_progress)(percent, message, self.symbols);
     }
+}
+
+/// `~40 с`, `~3 мин`, `~1 ч 05 мин`.
+fn format_eta(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("~{} с", secs.max(1)),
+        60..=3599 => format!("~{} мин", secs.div_ceil(60)),
+        _ => format!("~{} ч {:02} мин", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Text sources of one symbol_terms row.
+struct TermSource {
+    id: i64,
+    name: String,
+    /// Space-separated parameter names from the signature.
+    param_names: String,
+    summary: String,
+    /// Parameter names, types and descriptions from the doc comment.
+    param_docs: String,
+    /// Return type and description from the doc comment.
+    return_docs: String,
+}
+
+/// SELECT producing `TermSource` columns for `symbols` rows; append a WHERE clause if needed.
+const TERM_SOURCE_SELECT: &str =
+    "SELECT symbols.id, symbols.name,
+            COALESCE((SELECT group_concat(p.name, ' ') FROM symbol_params p WHERE p.symbol_id = symbols.id), ''),
+            COALESCE(d.summary, ''),
+            COALESCE((SELECT group_concat(dp.name || ' ' || dp.types || ' ' || dp.description, ' ')
+                      FROM symbol_doc_params dp WHERE dp.symbol_id = symbols.id), ''),
+            COALESCE(d.returns_type, '') || ' ' || COALESCE(d.returns_description, '')
+     FROM symbols
+     LEFT JOIN symbol_docs d ON d.symbol_id = symbols.id";
+
+fn load_term_sources(conn: &Connection, where_clause: &str, args: &[&dyn rusqlite::types::ToSql]) -> Vec<TermSource> {
+    let sql = format!("{} {}", TERM_SOURCE_SELECT, where_clause);
+    conn.prepare(&sql)
+        .ok()
+        .map(|mut stmt| {
+            stmt.query_map(args, |r| {
+                Ok(TermSource {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                    param_names: r.get(2)?,
+                    summary: r.get(3)?,
+                    param_docs: r.get(4)?,
+                    return_docs: r.get(5)?,
+                })
+            })
+            .map(|rows| rows.flatten().collect())
+            .unwrap_or_default()
+        })
+        .unwrap_or_default()
+}
+
+/// Insert FTS rows: identifiers are split on CamelCase, descriptions tokenized as free text.
+fn insert_symbol_terms(conn: &Connection, sources: &[TermSource]) {
+    use crate::semantic::{tokenize_identifier, tokenize_query};
+    let identifiers = |text: &str| {
+        text.split_whitespace()
+            .flat_map(tokenize_identifier)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let free_text = |text: &str| tokenize_query(text).join(" ");
+
     if let Ok(tx) = conn.unchecked_transaction() {
         if let Ok(mut ins) = tx.prepare(
-            "INSERT INTO symbol_terms (symbol_id, name_tokens, comment_head, param_tokens)
-             VALUES (?1, ?2, '', '')"
+            "INSERT INTO symbol_terms (symbol_id, name_tokens, comment_head, param_tokens, param_docs, return_docs)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
         ) {
-            for (id, name) in &pairs {
-                let tokens = crate::semantic::tokenize_identifier(name).join(" ");
-                let _ = ins.execute(params![id.to_string(), tokens]);
+            for src in sources {
+                let _ = ins.execute(params![
+                    src.id.to_string(),
+                    identifiers(&src.name),
+                    free_text(&src.summary),
+                    identifiers(&src.param_names),
+                    free_text(&src.param_docs),
+                    free_text(&src.return_docs)
+                ]);
             }
         }
         let _ = tx.commit();
     }
-    eprintln!("[1c-search] semantic FTS built: {} symbols", pairs.len());
+}
+
+/// Symbols loaded per `symbol_terms` transaction in `build_semantic_fts`.
+const TERM_SOURCE_BATCH: i64 = 20_000;
+
+/// (Re)build symbol_terms FTS5 table from current symbols, in batches of `TERM_SOURCE_BATCH`
+/// by id so memory doesn't grow with the configuration. Used after full build_index.
+fn build_semantic_fts(conn: &Connection) {
+    let _ = conn.execute_batch("DELETE FROM symbol_terms;");
+    let mut last_id = 0i64;
+    let mut total = 0usize;
+    loop {
+        let sources = load_term_sources(
+            conn,
+            "WHERE symbols.id > ?1 ORDER BY symbols.id LIMIT ?2",
+            &[&last_id, &TERM_SOURCE_BATCH],
+        );
+        let Some(last) = sources.last() else { break };
+        last_id = last.id;
+        insert_symbol_terms(conn, &sources);
+        total += sources.len();
+    }
+    if total > 0 {
+        eprintln!("[1c-search] semantic FTS built: {} symbols", total);
+    }
 }
 
 /// Incrementally sync symbol_terms FTS after sync_index.
@@ -1086,60 +2337,165 @@ fn sync_semantic_fts(conn: &Connection, deleted: &[String], parsed: &[ParsedFile
     );
 
     // Upsert FTS for each newly parsed file
-    let file_pairs: Vec<(i64, String)> = parsed.iter().flat_map(|pf| {
-        conn.prepare("SELECT id, name FROM symbols WHERE file = ?1")
-            .ok()
-            .and_then(|mut stmt| {
-                stmt.query_map([&pf.rel_path], |r| {
-                    Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?))
-                })
-                .ok()
-                .map(|rows| rows.flatten().collect::<Vec<_>>())
-            })
-            .unwrap_or_default()
-    }).collect();
+    let sources: Vec<TermSource> = parsed
+        .iter()
+        .flat_map(|pf| load_term_sources(conn, "WHERE symbols.file = ?1", &[&pf.rel_path]))
+        .collect();
 
-    if file_pairs.is_empty() && deleted.is_empty() {
+    if sources.is_empty() && deleted.is_empty() {
         return;
     }
 
-    if let Ok(tx) = conn.unchecked_transaction() {
-        if let Ok(mut ins) = tx.prepare(
-            "INSERT OR REPLACE INTO symbol_terms (symbol_id, name_tokens, comment_head, param_tokens)
-             VALUES (?1, ?2, '', '')"
-        ) {
-            for (id, name) in &file_pairs {
-                let tokens = crate::semantic::tokenize_identifier(name).join(" ");
-                let _ = ins.execute(params![id.to_string(), tokens]);
+    insert_symbol_terms(conn, &sources);
+    eprintln!("[1c-search] semantic FTS synced: {} symbols updated", sources.len());
+}
+
+// ─── Code content index ──────────────────────────────────────────────────────
+
+/// Modules read into memory at once while filling `code_trigrams`.
+const CODE_INDEX_CHUNK: usize = 256;
+
+/// Trigrams of a candidate query; more only slow the FTS lookup down without narrowing much.
+const MAX_QUERY_TRIGRAMS: usize = 24;
+
+/// Add the texts of `rel_paths` to the trigram code index, replacing what was stored for them.
+/// Files are read in parallel, a chunk at a time, so a full build never holds every module.
+fn index_code_contents(conn: &Connection, root: &Path, rel_paths: &[String]) {
+    if rel_paths.is_empty() {
+        return;
+    }
+    remove_code_contents(conn, rel_paths);
+    let Ok(tx) = conn.unchecked_transaction() else { return };
+    {
+        let (Ok(mut ins_file), Ok(mut ins_text)) = (
+            tx.prepare("INSERT INTO code_files (file) VALUES (?1)"),
+            tx.prepare("INSERT INTO code_trigrams (rowid, content) VALUES (?1, ?2)"),
+        ) else {
+            return;
+        };
+        for chunk in rel_paths.chunks(CODE_INDEX_CHUNK) {
+            let texts: Vec<(&String, String)> = chunk
+                .par_iter()
+                .filter_map(|rel| read_file_to_string_lossy(&source_path(root, rel)).ok().map(|text| (rel, text)))
+                .collect();
+            for (rel, text) in texts {
+                if ins_file.execute(params![rel]).is_ok() {
+                    let _ = ins_text.execute(params![tx.last_insert_rowid(), text]);
+                }
             }
         }
-        let _ = tx.commit();
     }
-    eprintln!("[1c-search] semantic FTS synced: {} symbols updated", file_pairs.len());
+    let _ = tx.commit();
+    eprintln!("[1c-search] code index: {} modules", rel_paths.len());
 }
 
-/// Query the index for symbols matching the query.
-pub fn find_symbols(
-    db_path: &Path,
-    query: &str,
-    exact: bool,
-    limit: usize,
-) -> Result<Vec<SymbolMatch>, String> {
-    let conn = Connection::open(db_path).map_err(|e| format!("Ошибка БД: {}", e))?;
-    let query_lower = query.to_lowercase();
-
-    if exact {
-        let mut stmt = conn
-            .prepare(
-                "SELECT name, kind, file, start_line, end_line, is_export \
-                 FROM symbols WHERE name_lower = ?1 LIMIT ?2",
-            )
-            .map_err(|e| e.to_string())?;
-        return collect_symbol_rows(&mut stmt, &query_lower, limit);
+/// Drop the texts of `rel_paths` from the trigram code index.
+fn remove_code_contents(conn: &Connection, rel_paths: &[String]) {
+    if rel_paths.is_empty() {
+        return;
     }
-
-    // Two-phase substring search to avoid slow full-table scans:
-    //
+    let Ok(tx) = conn.unchecked_transaction() else { return };
+    {
+        let (Ok(mut find), Ok(mut del_text), Ok(mut del_file)) = (
+            tx.prepare("SELECT id FROM code_files WHERE file = ?1"),
+            tx.prepare("DELETE FROM code_trigrams WHERE rowid = ?1"),
+            tx.prepare("DELETE FROM code_files WHERE id = ?1"),
+        ) else {
+            return;
+        };
+        for rel in rel_paths {
+            let Ok(id) = find.query_row(params![rel], |r| r.get::<_, i64>(0)) else { continue };
+            let _ = del_text.execute(params![id]);
+            let _ = del_file.execute(params![id]);
+        }
+    }
+    let _ = tx.commit();
+}
+
+/// Modules that may contain every string of `literals` (case-insensitive): all their trigrams
+/// must occur in the module text. Literals shorter than three characters don't narrow the
+/// search; with none left, every indexed module is a candidate. `scope` keeps that module or
+/// the modules under that relative directory. Sorted by path.
+///
+/// `None` if the code index is not built yet (a new or migrated index before `build_index`).
+pub fn code_candidate_files(db_path: &Path, literals: &[String], scope: Option<&str>) -> Option<Vec<String>> {
+    let conn = Connection::open(db_path).ok()?;
+    let ready = conn
+        .query_row("SELECT EXISTS (SELECT 1 FROM code_files)", [], |r| r.get::<_, bool>(0))
+        .ok()?;
+    if !ready {
+        return None;
+    }
+
+    let mut trigrams: Vec<String> = Vec::new();
+    for literal in literals {
+        let chars: Vec<char> = literal.to_lowercase().chars().collect();
+        for window in chars.windows(3) {
+            let trigram: String = window.iter().collect();
+            if !trigrams.contains(&trigram) {
+                trigrams.push(trigram);
+            }
+        }
+    }
+    trigrams.truncate(MAX_QUERY_TRIGRAMS);
+
+    let scope = scope
+        .map(|s| s.replace('\\', "/").trim_matches('/').to_string())
+        .filter(|s| !s.is_empty());
+    let mut sql = if trigrams.is_empty() {
+        "SELECT file FROM code_files f WHERE 1".to_string()
+    } else {
+        "SELECT f.file FROM code_trigrams t JOIN code_files f ON f.id = t.rowid WHERE code_trigrams MATCH ?1".to_string()
+    };
+    let mut args: Vec<String> = Vec::new();
+    if !trigrams.is_empty() {
+        args.push(
+            trigrams
+                .iter()
+                .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+                .collect::<Vec<_>>()
+                .join(" AND "),
+        );
+    }
+    if let Some(scope) = scope {
+        sql.push_str(&format!(" AND (f.file = ?{n} OR f.file LIKE ?{n} || '/%')", n = args.len() + 1));
+        args.push(scope);
+    }
+    sql.push_str(" ORDER BY f.file");
+
+    let mut stmt = conn.prepare(&sql).ok()?;
+    let files = stmt
+        .query_map(rusqlite::params_from_iter(args.iter()), |r| r.get::<_, String>(0))
+        .ok()?
+        .flatten()
+        .collect();
+    Some(files)
+}
+
+/// Query the index for symbols matching the query.
+pub fn find_symbols(
+    db_path: &Path,
+    query: &str,
+    exact: bool,
+    limit: usize,
+    filter: &SymbolFilter,
+) -> Result<Vec<SymbolMatch>, String> {
+    let conn = Connection::open(db_path).map_err(|e| format!("Ошибка БД: {}", e))?;
+    let query_lower = query.to_lowercase();
+    let (filter_sql, filter_values) = filter.sql_conditions();
+
+    if exact {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM symbols WHERE name_lower = ?{} LIMIT ?",
+                symbol_columns(), filter_sql
+            ))
+            .map_err(|e| e.to_string())?;
+        return collect_symbol_rows(&mut stmt, &[query_lower], &filter_values, limit);
+    }
+
+    // Two-phase substring search to avoid slow full-table scans:
+    //
     // Phase 1: prefix match  — `name_lower LIKE 'query%'`
     //   Uses the idx_name_lower B-tree index → O(log n + k), fast on cold HDD.
     //   Covers the common case: user types the start of a function name.
@@ -1151,12 +2507,12 @@ pub fn find_symbols(
 
     let prefix_pattern = format!("{}%", query_lower);
     let mut stmt = conn
-        .prepare(
-            "SELECT name, kind, file, start_line, end_line, is_export \
-             FROM symbols WHERE name_lower LIKE ?1 LIMIT ?2",
-        )
+        .prepare(&format!(
+            "SELECT {} FROM symbols WHERE name_lower LIKE ?{} LIMIT ?",
+            symbol_columns(), filter_sql
+        ))
         .map_err(|e| e.to_string())?;
-    let mut results = collect_symbol_rows(&mut stmt, &prefix_pattern, limit)?;
+    let mut results = collect_symbol_rows(&mut stmt, std::slice::from_ref(&prefix_pattern), &filter_values, limit)?;
 
     if results.len() < limit {
         // Phase 2: find mid-string matches not already returned by prefix search
@@ -1165,18 +2521,13 @@ pub fn find_symbols(
         let prefix_set: std::collections::HashSet<String> =
             results.iter().map(|r| r.name.to_lowercase()).collect();
         let mut stmt2 = conn
-            .prepare(
-                "SELECT name, kind, file, start_line, end_line, is_export \
-                 FROM symbols WHERE name_lower LIKE ?1 AND name_lower NOT LIKE ?2 LIMIT ?3",
-            )
+            .prepare(&format!(
+                "SELECT {} FROM symbols WHERE name_lower LIKE ? AND name_lower NOT LIKE ?{} LIMIT ?",
+                symbol_columns(), filter_sql
+            ))
             .map_err(|e| e.to_string())?;
-        let extra = stmt2
-            .query_map(
-                params![mid_pattern, prefix_pattern, remaining as i64],
-                symbol_row_mapper,
-            )
-            .map_err(|e| e.to_string())?;
-        for row in extra.flatten() {
+        let extra = collect_symbol_rows(&mut stmt2, &[mid_pattern, prefix_pattern], &filter_values, remaining)?;
+        for row in extra {
             if !prefix_set.contains(&row.name.to_lowercase()) {
                 results.push(row);
             }
@@ -1194,16 +2545,27 @@ fn symbol_row_mapper(row: &rusqlite::Row<'_>) -> rusqlite::Result<SymbolMatch> {
         start_line: row.get::<_, u32>(3)?,
         end_line: row.get::<_, u32>(4)?,
         is_export: row.get::<_, i32>(5)? != 0,
+        directive: row.get(6)?,
+        params: row.get(7)?,
+        origin: row.get(8)?,
     })
 }
 
+/// Run a symbol query whose placeholders are: `leading` values, then filter values, then LIMIT.
 fn collect_symbol_rows(
     stmt: &mut rusqlite::Statement<'_>,
-    pattern: &str,
+    leading: &[String],
+    filter_values: &[String],
     limit: usize,
 ) -> Result<Vec<SymbolMatch>, String> {
+    let limit = limit as i64;
+    let mut values: Vec<&dyn rusqlite::types::ToSql> = Vec::new();
+    for v in leading.iter().chain(filter_values) {
+        values.push(v as &dyn rusqlite::types::ToSql);
+    }
+    values.push(&limit);
     let rows = stmt
-        .query_map(params![pattern, limit as i64], symbol_row_mapper)
+        .query_map(values.as_slice(), symbol_row_mapper)
         .map_err(|e| e.to_string())?;
     Ok(rows.flatten().collect())
 }
@@ -1237,20 +2599,12 @@ pub fn find_symbol_at_line(
 ) -> Option<SymbolMatch> {
     let conn = Connection::open(db_path).ok()?;
     conn.query_row(
-        "SELECT name, kind, file, start_line, end_line, is_export \
-         FROM symbols WHERE file = ?1 AND start_line <= ?2 AND end_line >= ?2 \
-         LIMIT 1",
+        &format!(
+            "SELECT {} FROM symbols WHERE file = ?1 AND start_line <= ?2 AND end_line >= ?2 LIMIT 1",
+            symbol_columns()
+        ),
         params![file, line],
-        |row| {
-            Ok(SymbolMatch {
-                name: row.get(0)?,
-                kind: row.get(1)?,
-                file: row.get(2)?,
-                start_line: row.get::<_, u32>(3)?,
-                end_line: row.get::<_, u32>(4)?,
-                is_export: row.get::<_, i32>(5)? != 0,
-            })
-        },
+        symbol_row_mapper,
     )
     .ok()
 }
@@ -1262,14 +2616,80 @@ pub struct ObjectInfo {
     pub name: String,
 }
 
+/// Attribute of an object or a tabular section column. Properties are `None` when the
+/// metadata came from `ConfigDumpInfo.xml`, which lists names only.
+pub struct ObjectAttribute {
+    pub name: String,
+    /// `CatalogRef.Номенклатура`, `Number(15,2)`, `String(50)`; composite types comma-separated.
+    pub value_type: Option<String>,
+    pub synonym: Option<String>,
+    pub comment: Option<String>,
+    pub indexing: Option<String>,
+    pub fill_checking: Option<String>,
+}
+
+pub struct TabularSectionInfo {
+    pub name: String,
+    pub synonym: Option<String>,
+    pub attributes: Vec<ObjectAttribute>,
+}
+
+/// One `metadata_references` edge: `object.item` (kind) → `target`.
+pub struct MetadataReference {
+    /// Referencing object: `Document.РеализацияТоваров`.
+    pub object: String,
+    /// Attribute path within it (`Товары.Номенклатура`); `None` for object-level edges.
+    pub item: Option<String>,
+    /// `Attribute`, `Dimension`, `Resource`, `RegisterRecord`, `Property` or `Type`.
+    pub kind: String,
+    /// Referenced object: `Catalog.Номенклатура`.
+    pub target: String,
+    /// Defined type the reference goes through, when the attribute is typed with it.
+    pub via: Option<String>,
+}
+
+pub struct MetadataDependencies {
+    pub object: String,
+    /// Objects this one references.
+    pub uses: Vec<MetadataReference>,
+    /// Objects referencing this one, directly or through a defined type.
+    pub used_by: Vec<MetadataReference>,
+}
+
+/// Enum value or predefined catalog/chart item.
+pub struct PredefinedValue {
+    /// Owner object: `Enum.СтавкиНДС`, `Catalog.Валюты`.
+    pub object: String,
+    pub name: String,
+    /// Enum value synonym or predefined item description.
+    pub synonym: Option<String>,
+    /// Item code; account code for charts of accounts.
+    pub code: Option<String>,
+    pub is_folder: bool,
+    pub parent: Option<String>,
+}
+
 pub struct ObjectDetails {
     pub obj_type: String,
     pub name: String,
-    pub attributes: Vec<String>,
-    pub tabular_sections: Vec<(String, Vec<String>)>, // (section_name, [attr_names])
+    pub attributes: Vec<ObjectAttribute>,
+    pub tabular_sections: Vec<TabularSectionInfo>,
     pub forms: Vec<String>,
     pub commands: Vec<String>,
     pub modules: Vec<String>,
+    /// Register dimensions and resources (empty for other object types).
+    pub dimensions: Vec<ObjectAttribute>,
+    pub resources: Vec<ObjectAttribute>,
+    /// Register settings as stored in the dump: (`RegisterType`, `Balance`), (`InformationRegisterPeriodicity`, `Month`).
+    pub properties: Vec<(String, String)>,
+    /// Registers this document writes records to (`AccumulationRegister.ТоварыНаСкладах`).
+    pub register_records: Vec<String>,
+    /// Documents that write records to this register (`Document.ПоступлениеТоваров`).
+    pub recorders: Vec<String>,
+    /// Enum values or predefined items, in declaration order.
+    pub predefined: Vec<PredefinedValue>,
+    /// Flags of a common module (`None` for other object types).
+    pub module_flags: Option<CommonModuleFlags>,
 }
 
 /// Check if metadata (objects table) has been built.
@@ -1306,33 +2726,36 @@ pub fn list_objects(
     db_path: &Path,
     obj_type_filter: Option<&str>,
     name_filter: Option<&str>,
+    subsystem: Option<&str>,
     limit: usize,
 ) -> Result<Vec<ObjectInfo>, String> {
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
 
     // Build query dynamically based on filters
-    let name_pattern = name_filter.map(|n| format!("%{}%", n.to_lowercase()));
-
-    let (sql, boxed_params): (&str, Vec<Box<dyn rusqlite::ToSql>>) = match (obj_type_filter, name_pattern.as_deref()) {
-        (Some(t), Some(n)) => (
-            "SELECT obj_type, name FROM objects WHERE obj_type = ?1 AND name_lower LIKE ?2 ORDER BY name LIMIT ?3",
-            vec![Box::new(t.to_string()), Box::new(n.to_string()), Box::new(limit as i64)],
-        ),
-        (Some(t), None) => (
-            "SELECT obj_type, name FROM objects WHERE obj_type = ?1 ORDER BY name LIMIT ?2",
-            vec![Box::new(t.to_string()), Box::new(limit as i64)],
-        ),
-        (None, Some(n)) => (
-            "SELECT obj_type, name FROM objects WHERE name_lower LIKE ?1 ORDER BY obj_type, name LIMIT ?2",
-            vec![Box::new(n.to_string()), Box::new(limit as i64)],
-        ),
-        (None, None) => (
-            "SELECT obj_type, name FROM objects ORDER BY obj_type, name LIMIT ?1",
-            vec![Box::new(limit as i64)],
-        ),
+    let mut conditions: Vec<String> = Vec::new();
+    let mut boxed_params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    if let Some(t) = obj_type_filter {
+        conditions.push("obj_type = ?".to_string());
+        boxed_params.push(Box::new(t.to_string()));
+    }
+    if let Some(n) = name_filter {
+        conditions.push("name_lower LIKE ?".to_string());
+        boxed_params.push(Box::new(format!("%{}%", n.to_lowercase())));
+    }
+    if let Some(sub) = subsystem {
+        conditions.push(subsystem_member_sql("objects.obj_type", "objects.name", "?"));
+        boxed_params.push(Box::new(sub.to_string()));
+        boxed_params.push(Box::new(sub.to_string()));
+    }
+    boxed_params.push(Box::new(limit as i64));
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
     };
+    let sql = format!("SELECT obj_type, name FROM objects {} ORDER BY obj_type, name LIMIT ?", where_clause);
 
-    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
     let params_refs: Vec<&dyn rusqlite::ToSql> = boxed_params.iter().map(|b| b.as_ref()).collect();
     let rows = stmt
         .query_map(params_refs.as_slice(), |row| {
@@ -1349,144 +2772,1261 @@ pub fn list_objects(
     Ok(result)
 }
 
+// ─── Call resolution ───────────────────────────────────────────────────────
+
+/// Map a manager collection (`Справочники`, `Catalogs`, ...) to the object type used in indexed_files.
+fn manager_collection_type(collection_lower: &str) -> Option<&'static str> {
+    match collection_lower {
+        "справочники" | "catalogs"                              => Some("Catalog"),
+        "документы" | "documents"                               => Some("Document"),
+        "перечисления" | "enums"                                => Some("Enum"),
+        "регистрысведений" | "informationregisters"             => Some("InformationRegister"),
+        "регистрынакопления" | "accumulationregisters"          => Some("AccumulationRegister"),
+        "регистрыбухгалтерии" | "accountingregisters"           => Some("AccountingRegister"),
+        "регистрырасчета" | "calculationregisters"              => Some("CalculationRegister"),
+        "планыобмена" | "exchangeplans"                         => Some("ExchangePlan"),
+        "бизнеспроцессы" | "businessprocesses"                  => Some("BusinessProcess"),
+        "задачи" | "tasks"                                      => Some("Task"),
+        "планывидовхарактеристик" | "chartsofcharacteristictypes" => Some("ChartOfCharacteristicTypes"),
+        "планысчетов" | "chartsofaccounts"                      => Some("ChartOfAccounts"),
+        "планывидоврасчета" | "chartsofcalculationtypes"        => Some("ChartOfCalculationTypes"),
+        "обработки" | "dataprocessors"                          => Some("DataProcessor"),
+        "отчеты" | "reports"                                    => Some("Report"),
+        "журналыдокументов" | "documentjournals"                => Some("DocumentJournal"),
+        _ => None,
+    }
+}
+
+/// Short human-readable module label: `CommonModule.ОбщегоНазначения`,
+/// `Catalog.Валюты.ManagerModule`, `Document.Заказ.Form.ФормаДокумента`.
+/// Modules of extensions get the extension name appended: `CommonModule.Общий [Расш1]`.
+pub fn module_label(file: &str) -> String {
+    let label = base_module_label(file);
+    match split_origin(file).0 {
+        Some(origin) => format!("{} [{}]", label, origin),
+        None => label,
+    }
+}
+
+fn base_module_label(file: &str) -> String {
+    let (obj_type, obj_name, module_kind) = infer_object_from_path(file);
+    let (obj_type, obj_name) = match (obj_type, obj_name) {
+        (Some(t), Some(n)) => (t, n),
+        _ => return file.to_string(),
+    };
+    match module_kind.as_deref() {
+        Some("Module") if obj_type == "CommonModule" => format!("{}.{}", obj_type, obj_name),
+        Some("FormModule") => {
+            let form = file.split('/').skip_while(|p| *p != "Forms").nth(1).unwrap_or("");
+            format!("{}.{}.Form.{}", obj_type, obj_name, form)
+        }
+        Some(kind) => format!("{}.{}.{}", obj_type, obj_name, kind),
+        None => format!("{}.{}", obj_type, obj_name),
+    }
+}
+
+/// Does `file` belong to the module written as `receiver` in code?
+/// `ОбщегоНазначения` matches the common module, `Справочники.Валюты` the catalog manager module.
+fn receiver_matches_file(receiver_lower: &str, file: &str) -> bool {
+    let (obj_type, obj_name, module_kind) = infer_object_from_path(file);
+    let (obj_type, obj_name) = match (obj_type, obj_name) {
+        (Some(t), Some(n)) => (t, n.to_lowercase()),
+        _ => return false,
+    };
+    let parts: Vec<&str> = receiver_lower.split('.').collect();
+    match parts.as_slice() {
+        [module] => obj_type == "CommonModule" && obj_name == *module,
+        [collection, name] => {
+            module_kind.as_deref() == Some("ManagerModule")
+                && obj_name == *name
+                && manager_collection_type(collection) == Some(obj_type.as_str())
+        }
+        _ => false,
+    }
+}
+
+/// Resolve every call to the file of the module that defines the callee.
+///
+/// - `Метод()` → a method with that name in the caller's own module;
+/// - `ОбщийМодуль.Метод()` → the exported method of that common module;
+/// - `Справочники.Имя.Метод()` → the exported method of the object's manager module.
+///
+/// Calls on variables (`Запрос.Выполнить()`) and platform methods stay unresolved (NULL).
+/// Calls are resolved one caller file at a time, so memory does not grow with the index.
+/// Only rows whose target changed are written. Returns the number of resolved calls.
+pub fn resolve_calls(conn: &Connection) -> usize {
+    use std::collections::{HashMap, HashSet};
+
+    // Module lookup keyed the way receivers are written in code (lowercased in Rust:
+    // SQLite lower() only folds ASCII, object names are mostly Cyrillic).
+    let mut common_modules: HashMap<String, String> = HashMap::new();
+    let mut manager_modules: HashMap<(String, String), String> = HashMap::new();
+    if let Ok(mut stmt) = conn.prepare("SELECT DISTINCT file FROM symbols") {
+        if let Ok(rows) = stmt.query_map([], |r| r.get::<_, String>(0)) {
+            for file in rows.flatten() {
+                let (obj_type, obj_name, module_kind) = infer_object_from_path(&file);
+                let (obj_type, obj_name) = match (obj_type, obj_name) {
+                    (Some(t), Some(n)) => (t, n.to_lowercase()),
+                    _ => continue,
+                };
+                // An adopted module exists both in the base configuration and in the extension;
+                // calls resolve to the base one, extension-only modules are used as is.
+                let prefer = |current: &String| split_origin(current).0.is_some() && split_origin(&file).0.is_none();
+                let entry = match (obj_type.as_str(), module_kind.as_deref()) {
+                    ("CommonModule", Some("Module")) => common_modules.entry(obj_name).or_insert_with(|| file.clone()),
+                    (_, Some("ManagerModule")) => {
+                        manager_modules.entry((obj_type, obj_name)).or_insert_with(|| file.clone())
+                    }
+                    _ => continue,
+                };
+                if prefer(entry) {
+                    *entry = file.clone();
+                }
+            }
+        }
+    }
+
+    let caller_files: Vec<String> = conn
+        .prepare("SELECT DISTINCT caller_file FROM calls WHERE edge_kind = 'call'")
+        .and_then(|mut stmt| stmt.query_map([], |r| r.get(0))?.collect())
+        .unwrap_or_default();
+
+    let Ok(tx) = conn.unchecked_transaction() else { return 0 };
+    let (Ok(mut names_of), Ok(mut calls_of), Ok(mut exported), Ok(mut upd)) = (
+        tx.prepare("SELECT name_lower FROM symbols WHERE file = ?1"),
+        tx.prepare(
+            "SELECT id, callee_name_lower, callee_receiver_lower, callee_file FROM calls \
+             WHERE caller_file = ?1 AND edge_kind = 'call'"
+        ),
+        tx.prepare("SELECT 1 FROM symbols WHERE file = ?1 AND name_lower = ?2 AND is_export = 1 LIMIT 1"),
+        tx.prepare("UPDATE calls SET callee_file = ?2 WHERE id = ?1"),
+    ) else {
+        return 0;
+    };
+
+    let mut resolved = 0usize;
+    let mut updated = 0usize;
+    for caller_file in &caller_files {
+        let local_names: HashSet<String> = names_of
+            .query_map([caller_file], |r| r.get(0))
+            .map(|rows| rows.flatten().collect())
+            .unwrap_or_default();
+        let calls: Vec<(i64, String, Option<String>, Option<String>)> = calls_of
+            .query_map([caller_file], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))
+            .map(|rows| rows.flatten().collect())
+            .unwrap_or_default();
+
+        for (id, callee, receiver, current) in calls {
+            let target: Option<String> = match receiver.as_deref() {
+                None | Some("этотобъект") | Some("thisobject") => {
+                    local_names.contains(&callee).then(|| caller_file.clone())
+                }
+                Some(r) => {
+                    let parts: Vec<&str> = r.split('.').collect();
+                    let module = match parts.as_slice() {
+                        [module] => common_modules.get(*module),
+                        [collection, name] => manager_collection_type(collection)
+                            .and_then(|t| manager_modules.get(&(t.to_string(), name.to_string()))),
+                        _ => None,
+                    };
+                    module.filter(|f| exported.exists(params![f, callee]).unwrap_or(false)).cloned()
+                }
+            };
+            if target.is_some() {
+                resolved += 1;
+            }
+            if target != current && upd.execute(params![id, target]).is_ok() {
+                updated += 1;
+            }
+        }
+    }
+    drop((names_of, calls_of, exported, upd));
+    let _ = tx.commit();
+    eprintln!("[1c-search] Calls resolved: {} ({} updated)", resolved, updated);
+    resolved
+}
+
 // ─── Call graph queries ────────────────────────────────────────────────────
 
 pub struct CallerInfo {
     pub name: String,
     pub file: String,
     pub start_line: u32,
+    /// false — the call site was not linked to a module (e.g. call through a variable),
+    /// it only matches by method name.
+    pub resolved: bool,
+    /// `call` for a call in code; `event_subscription`, `scheduled_job`, `http_service`,
+    /// `web_service` or `common_command` when the platform invokes the method for the metadata
+    /// object named by `name`.
+    pub edge_kind: String,
+}
+
+pub struct CalleeInfo {
+    pub name: String,
+    pub receiver: Option<String>,
+    /// Module file that defines the callee, if resolved.
+    pub file: Option<String>,
+}
+
+/// Extension method attached to a base method with `&Перед`/`&После`/`&Вместо`/`&ИзменениеИКонтроль`.
+pub struct InterceptionInfo {
+    /// Extension name.
+    pub origin: String,
+    /// Annotation, canonical form: `Перед`, `После`, `Вместо`, `ИзменениеИКонтроль`.
+    pub kind: String,
+    /// Extension method that carries the annotation.
+    pub method: String,
+    pub file: String,
+    pub start_line: u32,
+    /// Intercepted method name as written in the annotation.
+    pub target: String,
 }
 
 pub struct FunctionContext {
     pub function: SymbolMatch,
-    pub calls: Vec<String>,
+    pub calls: Vec<CalleeInfo>,
     pub called_by: Vec<CallerInfo>,
+    /// Extension methods that intercept this (base) method.
+    pub overridden_by: Vec<InterceptionInfo>,
+    /// Base method this extension method intercepts, if it carries an annotation.
+    pub intercepts: Option<InterceptionInfo>,
+    /// Form events and commands this method handles (form modules only).
+    pub form_bindings: Vec<FormBinding>,
+}
+
+/// Why `lookup_method` resolved no method.
+#[derive(Debug)]
+pub enum MethodLookupError {
+    NotFound,
+    /// No method has exactly this name (in the given module); methods whose names start with it.
+    Ambiguous(Vec<SymbolMatch>),
+}
+
+/// Names listed in a `MethodLookupError::Ambiguous`.
+const LOOKUP_SUGGESTIONS: usize = 10;
+
+impl MethodLookupError {
+    /// Tool response text: `Функция 'X' не найдена в индексе`, with the close names if any.
+    pub fn message(&self, function_name: &str) -> String {
+        let mut text = format!("Функция '{}' не найдена в индексе", function_name);
+        if let MethodLookupError::Ambiguous(similar) = self {
+            let names: Vec<String> = similar
+                .iter()
+                .map(|s| format!("{}.{}", module_label(&s.file), s.name))
+                .collect();
+            text.push_str(&format!(". Похожие методы: {}", names.join(", ")));
+        }
+        text
+    }
+}
+
+/// Resolve a method by name: `Имя` or qualified `ОбщийМодуль.Имя` / `Справочники.Валюты.Имя`,
+/// or with a module label (`Document.Заказ.ObjectModule.ОбработкаПроведения`, see `module_label`).
+/// Only an exact name resolves; exported methods come first. Without one, methods whose names
+/// start with the given one are returned as `Ambiguous` for the caller to choose from.
+fn lookup_method(conn: &Connection, function_name: &str) -> Result<SymbolMatch, MethodLookupError> {
+    let (receiver_lower, name_lower) = match function_name.rsplit_once('.') {
+        Some((recv, name)) => (Some(recv.trim().to_lowercase()), name.trim().to_lowercase()),
+        None => (None, function_name.trim().to_lowercase()),
+    };
+
+    let candidates = |pattern: &str, sql: &str| -> Vec<SymbolMatch> {
+        conn.prepare(sql)
+            .ok()
+            .map(|mut stmt| {
+                stmt.query_map(params![pattern], symbol_row_mapper)
+                    .map(|rows| rows.flatten().collect())
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default()
+    };
+    let in_module = |s: &SymbolMatch| match &receiver_lower {
+        Some(recv) => receiver_matches_file(recv, &s.file) || module_label(&s.file).to_lowercase() == *recv,
+        None => true,
+    };
+
+    let exact = candidates(
+        &name_lower,
+        &format!(
+            "SELECT {} FROM symbols WHERE name_lower = ?1 ORDER BY origin IS NOT NULL, is_export DESC, file",
+            symbol_columns()
+        ),
+    );
+    if let Some(found) = exact.into_iter().find(|s| in_module(s)) {
+        return Ok(found);
+    }
+
+    // Suggestions: same-prefix names in the module asked for, or anywhere if it has none
+    let similar = candidates(
+        &format!("{}%", name_lower),
+        &format!(
+            "SELECT {} FROM symbols WHERE name_lower LIKE ?1 ORDER BY origin IS NOT NULL, is_export DESC, file LIMIT 200",
+            symbol_columns()
+        ),
+    );
+    let in_scope: Vec<SymbolMatch> = similar.iter().filter(|s| in_module(s)).cloned().collect();
+    let mut suggestions = if in_scope.is_empty() { similar } else { in_scope };
+    suggestions.truncate(LOOKUP_SUGGESTIONS);
+    if suggestions.is_empty() {
+        Err(MethodLookupError::NotFound)
+    } else {
+        Err(MethodLookupError::Ambiguous(suggestions))
+    }
+}
+
+/// A method with its doc comment, as returned by `get_symbol_doc`.
+pub struct SymbolDoc {
+    pub symbol: SymbolMatch,
+    /// Parameter names of the signature, in order (from `symbol_params`).
+    pub param_names: Vec<String>,
+    /// `None` when the method has no description block.
+    pub doc: Option<BslDoc>,
+}
+
+/// Structured doc comment of a method (`Имя` or `Модуль.Имя`), with the symbol itself.
+pub fn get_symbol_doc(db_path: &Path, function_name: &str) -> Result<SymbolDoc, MethodLookupError> {
+    let conn = Connection::open(db_path).map_err(|_| MethodLookupError::NotFound)?;
+    let symbol = lookup_method(&conn, function_name)?;
+    let symbol_id: i64 = conn
+        .query_row(
+            "SELECT id FROM symbols WHERE file = ?1 AND name_lower = ?2 AND start_line = ?3",
+            params![symbol.file, symbol.name.to_lowercase(), symbol.start_line],
+            |row| row.get(0),
+        )
+        .map_err(|_| MethodLookupError::NotFound)?;
+
+    let param_names: Vec<String> = conn
+        .prepare("SELECT name FROM symbol_params WHERE symbol_id = ?1 ORDER BY position")
+        .and_then(|mut stmt| {
+            stmt.query_map(params![symbol_id], |row| row.get(0))
+                .map(|rows| rows.flatten().collect())
+        })
+        .unwrap_or_default();
+
+    let doc = conn
+        .query_row(
+            "SELECT summary, returns_type, returns_description, examples FROM symbol_docs WHERE symbol_id = ?1",
+            params![symbol_id],
+            |row| {
+                let returns_type: Option<String> = row.get(1)?;
+                let returns_description: Option<String> = row.get(2)?;
+                Ok(BslDoc {
+                    summary: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+                    params: Vec::new(),
+                    returns: returns_type.map(|types| BslDocReturn {
+                        types,
+                        description: returns_description.unwrap_or_default(),
+                    }),
+                    examples: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                })
+            },
+        )
+        .ok()
+        .map(|mut doc| {
+            if let Ok(mut stmt) = conn.prepare(
+                "SELECT name, types, description FROM symbol_doc_params WHERE symbol_id = ?1 ORDER BY position",
+            ) {
+                doc.params = stmt
+                    .query_map(params![symbol_id], |row| {
+                        Ok(BslDocParam {
+                            name: row.get(0)?,
+                            types: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                            description: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                        })
+                    })
+                    .map(|rows| rows.flatten().collect())
+                    .unwrap_or_default();
+            }
+            doc
+        });
+
+    Ok(SymbolDoc { symbol, param_names, doc })
 }
 
 /// Get call graph context for a function: what it calls and who calls it.
-pub fn get_function_context(db_path: &Path, function_name: &str) -> Option<FunctionContext> {
-    let conn = Connection::open(db_path).ok()?;
-    let name_lower = function_name.to_lowercase();
-
-    // Find the function symbol (exact first, then prefix)
-    let function = conn.query_row(
-        "SELECT name, kind, file, start_line, end_line, is_export \
-         FROM symbols WHERE name_lower = ?1 LIMIT 1",
-        params![name_lower],
-        |row| Ok(SymbolMatch {
-            name: row.get(0)?,
-            kind: row.get(1)?,
-            file: row.get(2)?,
-            start_line: row.get::<_, u32>(3)?,
-            end_line: row.get::<_, u32>(4)?,
-            is_export: row.get::<_, i32>(5)? != 0,
-        }),
-    ).or_else(|_| conn.query_row(
-        "SELECT name, kind, file, start_line, end_line, is_export \
-         FROM symbols WHERE name_lower LIKE ?1 LIMIT 1",
-        params![format!("{}%", name_lower)],
-        |row| Ok(SymbolMatch {
-            name: row.get(0)?,
-            kind: row.get(1)?,
-            file: row.get(2)?,
-            start_line: row.get::<_, u32>(3)?,
-            end_line: row.get::<_, u32>(4)?,
-            is_export: row.get::<_, i32>(5)? != 0,
-        }),
-    )).ok()?;
+///
+/// `function_name` may be qualified — `ОбщийМодуль.Метод` or `Справочники.Валюты.Метод` —
+/// to pick the definition from a particular module when several modules share the name.
+/// Callers are taken from resolved calls to this exact module; call sites that could not be
+/// resolved but use a receiver and the same name are returned with `resolved = false`.
+pub fn get_function_context(db_path: &Path, function_name: &str) -> Result<FunctionContext, MethodLookupError> {
+    let conn = Connection::open(db_path).map_err(|_| MethodLookupError::NotFound)?;
+    let function = lookup_method(&conn, function_name)?;
+    function_context(&conn, function).ok_or(MethodLookupError::NotFound)
+}
 
+fn function_context(conn: &Connection, function: SymbolMatch) -> Option<FunctionContext> {
     let resolved_name_lower = function.name.to_lowercase();
 
-    // What does this function call?
+    // What does this function call? (only from its own module — same-name functions elsewhere are different)
     let mut calls_stmt = conn.prepare(
-        "SELECT DISTINCT callee_name FROM calls WHERE caller_name_lower = ?1 ORDER BY callee_name"
+        "SELECT DISTINCT callee_name, callee_receiver, callee_file FROM calls \
+         WHERE caller_file = ?1 AND caller_name_lower = ?2 \
+         ORDER BY callee_file IS NULL, callee_file, callee_name"
     ).ok()?;
-    let calls: Vec<String> = calls_stmt
-        .query_map(params![resolved_name_lower], |row| row.get(0))
+    let calls: Vec<CalleeInfo> = calls_stmt
+        .query_map(params![function.file, resolved_name_lower], |row| {
+            Ok(CalleeInfo {
+                name: row.get(0)?,
+                receiver: row.get(1)?,
+                file: row.get(2)?,
+            })
+        })
         .ok()?
         .flatten()
         .collect();
 
-    // Who calls this function? (limit 50 to avoid huge responses)
+    // Who calls this function? Resolved call sites first, then unresolved qualified ones
+    // (limit 50 to avoid huge responses)
     let mut callers_stmt = conn.prepare(
-        "SELECT DISTINCT c.caller_name, c.caller_file, s.start_line \
+        "SELECT DISTINCT c.caller_name, c.caller_file, s.start_line, c.callee_file IS NOT NULL, c.edge_kind \
          FROM calls c \
          LEFT JOIN symbols s ON s.name_lower = c.caller_name_lower AND s.file = c.caller_file \
          WHERE c.callee_name_lower = ?1 \
-         ORDER BY c.caller_file, c.caller_name \
+           AND (c.callee_file = ?2 OR (?3 AND c.callee_file IS NULL AND c.callee_receiver IS NOT NULL)) \
+         ORDER BY c.callee_file IS NULL, c.edge_kind = 'call', c.caller_file, c.caller_name \
          LIMIT 50"
     ).ok()?;
     let called_by: Vec<CallerInfo> = callers_stmt
-        .query_map(params![resolved_name_lower], |row| {
+        .query_map(params![resolved_name_lower, function.file, function.is_export], |row| {
             Ok(CallerInfo {
                 name: row.get(0)?,
                 file: row.get(1)?,
                 start_line: row.get::<_, Option<u32>>(2)?.unwrap_or(0),
+                resolved: row.get::<_, i32>(3)? != 0,
+                edge_kind: row.get(4)?,
             })
         })
         .ok()?
         .flatten()
         .collect();
 
-    Some(FunctionContext { function, calls, called_by })
+    let interception_of = |row: &rusqlite::Row| -> rusqlite::Result<InterceptionInfo> {
+        Ok(InterceptionInfo {
+            origin: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+            kind: row.get(1)?,
+            method: row.get(2)?,
+            file: row.get(3)?,
+            start_line: row.get(4)?,
+            target: row.get(5)?,
+        })
+    };
+    let overridden_by: Vec<InterceptionInfo> = conn
+        .prepare(
+            "SELECT s.origin, i.kind, s.name, s.file, s.start_line, i.target \
+             FROM symbol_interceptions i JOIN symbols s ON s.id = i.symbol_id \
+             WHERE i.base_file = ?1 AND i.target_lower = ?2 \
+             ORDER BY s.origin, s.file",
+        )
+        .ok()
+        .map(|mut stmt| {
+            stmt.query_map(params![function.file, resolved_name_lower], interception_of)
+                .map(|rows| rows.flatten().collect())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default();
+    let intercepts = if function.origin.is_some() {
+        conn.query_row(
+            "SELECT s.origin, i.kind, s.name, s.file, s.start_line, i.target \
+             FROM symbol_interceptions i JOIN symbols s ON s.id = i.symbol_id \
+             WHERE s.file = ?1 AND s.name_lower = ?2 AND s.start_line = ?3",
+            params![function.file, resolved_name_lower, function.start_line],
+            interception_of,
+        )
+        .ok()
+    } else {
+        None
+    };
+
+    let form_bindings = form_bindings(conn, &function.file, &resolved_name_lower);
+
+    Some(FunctionContext { function, calls, called_by, overridden_by, intercepts, form_bindings })
 }
 
-/// List all functions in a module matching the given path substring.
-pub fn get_module_functions(db_path: &Path, module_path: &str, limit: usize) -> Vec<SymbolMatch> {
-    let conn = match Connection::open(db_path) {
-        Ok(c) => c,
-        Err(_) => return vec![],
-    };
-    let pattern = format!("%{}%", module_path.replace('\\', "/"));
-    let mut stmt = match conn.prepare(
-        "SELECT name, kind, file, start_line, end_line, is_export \
-         FROM symbols WHERE file LIKE ?1 \
-         ORDER BY start_line LIMIT ?2"
-    ) {
-        Ok(s) => s,
-        Err(_) => return vec![],
+// ─── Transitive call graph ──────────────────────────────────────────────────
+
+/// Direction of a call tree: what a method calls, or what calls it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallDirection {
+    Callers,
+    Callees,
+}
+
+/// Method in a call tree or call chain. For a metadata caller (event subscription, scheduled
+/// job, common command) `name` is the object and `file` its description.
+#[derive(Clone)]
+pub struct CallNode {
+    pub name: String,
+    pub file: String,
+    pub start_line: u32,
+}
+
+pub struct CallTreeNode {
+    pub method: CallNode,
+    /// Edge from the parent, as in `CallerInfo::edge_kind`. Metadata callers are leaves.
+    pub edge_kind: String,
+    /// The method is already on the way from the root: the calls loop back to it.
+    pub cycle: bool,
+    /// The method is expanded elsewhere in the tree; its subtree is not repeated.
+    pub repeated: bool,
+    /// Neighbours not shown: over the per-method cap or the tree's node budget.
+    pub omitted: usize,
+    pub children: Vec<CallTreeNode>,
+    /// Callers direction: form events and commands the method handles (called by the platform).
+    pub form_bindings: Vec<FormBinding>,
+}
+
+/// Shortest call chains between two methods.
+pub struct CallPaths {
+    pub from: SymbolMatch,
+    pub to: SymbolMatch,
+    /// Each chain from `from` to `to` inclusive; all of the same, shortest length.
+    pub paths: Vec<Vec<CallNode>>,
+    /// Methods reached by the search.
+    pub explored: usize,
+    /// Some calls were not followed: a method calls more than `max_fan_out` others, or the
+    /// search reached `CALL_PATH_MAX_VISITED` methods.
+    pub capped: bool,
+}
+
+/// Nodes in one call tree, whatever the depth and per-method cap.
+const CALL_TREE_MAX_NODES: usize = 400;
+
+/// Methods a call path search may reach before it gives up.
+const CALL_PATH_MAX_VISITED: usize = 50_000;
+
+/// `(file, name_lower)` — a method as a graph node.
+type MethodKey = (String, String);
+
+fn method_key(node: &CallNode) -> MethodKey {
+    (node.file.clone(), node.name.to_lowercase())
+}
+
+/// Resolved neighbours of a method, with the edge kind: the methods it calls, or the methods
+/// and metadata objects calling it. Calls that `resolve_calls` could not link to a module
+/// (through variables, platform methods) are not part of the graph.
+fn call_neighbours(conn: &Connection, key: &MethodKey, direction: CallDirection) -> Vec<(CallNode, String)> {
+    let sql = match direction {
+        CallDirection::Callees => {
+            "SELECT DISTINCT s.name, s.file, s.start_line, c.edge_kind FROM calls c \
+             JOIN symbols s ON s.file = c.callee_file AND s.name_lower = c.callee_name_lower \
+             WHERE c.caller_file = ?1 AND c.caller_name_lower = ?2 \
+             ORDER BY s.file, s.start_line"
+        }
+        CallDirection::Callers => {
+            "SELECT DISTINCT c.caller_name, c.caller_file, COALESCE(s.start_line, 0), c.edge_kind FROM calls c \
+             LEFT JOIN symbols s ON s.file = c.caller_file AND s.name_lower = c.caller_name_lower \
+             WHERE c.callee_file = ?1 AND c.callee_name_lower = ?2 \
+             ORDER BY c.edge_kind = 'call', c.caller_file, c.caller_name"
+        }
     };
-    stmt.query_map(params![pattern, limit as i64], symbol_row_mapper)
+    conn.prepare_cached(sql)
         .ok()
-        .map(|rows| rows.flatten().collect())
+        .map(|mut stmt| {
+            stmt.query_map(params![key.0, key.1], |r| {
+                Ok((CallNode { name: r.get(0)?, file: r.get(1)?, start_line: r.get(2)? }, r.get::<_, String>(3)?))
+            })
+            .map(|rows| rows.flatten().collect())
+            .unwrap_or_default()
+        })
         .unwrap_or_default()
 }
 
-// ─── Stats ────────────────────────────────────────────────────────────────
-
-pub struct IndexStats {
-    pub symbol_count: usize,
-    pub file_count: usize,
-    pub object_count: usize,
-    pub calls_count: usize,
-    pub built_at: Option<u64>,
-    pub db_size_mb: f64,
+/// Depth-first walk building a `CallTreeNode`; every method is expanded at most once.
+struct CallTreeWalk<'c> {
+    conn: &'c Connection,
+    direction: CallDirection,
+    max_children: usize,
+    expanded: std::collections::HashSet<MethodKey>,
+    budget: usize,
 }
 
-pub fn get_index_stats(db_path: &Path) -> IndexStats {
-    let db_size_mb = std::fs::metadata(db_path)
-        .map(|m| m.len() as f64 / 1024.0 / 1024.0)
-        .unwrap_or(0.0);
+impl CallTreeWalk<'_> {
+    fn node(&mut self, method: CallNode, edge_kind: String, depth: usize, path: &mut Vec<MethodKey>) -> CallTreeNode {
+        let key = method_key(&method);
+        let from_code = edge_kind == "call";
+        let mut node = CallTreeNode {
+            form_bindings: if from_code && self.direction == CallDirection::Callers {
+                form_bindings(self.conn, &key.0, &key.1)
+            } else {
+                Vec::new()
+            },
+            method,
+            edge_kind,
+            cycle: false,
+            repeated: false,
+            omitted: 0,
+            children: Vec::new(),
+        };
+        if !from_code {
+            return node;
+        }
+        if path.contains(&key) {
+            node.cycle = true;
+            return node;
+        }
+        if depth == 0 {
+            return node;
+        }
+        if !self.expanded.insert(key.clone()) {
+            node.repeated = true;
+            return node;
+        }
 
-    let conn = match Connection::open(db_path) {
-        Ok(c) => c,
-        Err(_) => return IndexStats { symbol_count: 0, file_count: 0, object_count: 0, calls_count: 0, built_at: None, db_size_mb },
-    };
+        let neighbours = call_neighbours(self.conn, &key, self.direction);
+        path.push(key);
+        for (i, (child, kind)) in neighbours.into_iter().enumerate() {
+            if i >= self.max_children || self.budget == 0 {
+                node.omitted += 1;
+                continue;
+            }
+            self.budget -= 1;
+            node.children.push(self.node(child, kind, depth - 1, path));
+        }
+        path.pop();
+        node
+    }
+}
 
-    // Try reading cached counts from meta table (written at build/sync time) — O(1)
-    let read_meta_int = |key: &str| -> Option<usize> {
-        conn.query_row(
-            "SELECT value FROM meta WHERE key = ?1",
-            [key],
-            |r| r.get::<_, String>(0),
-        ).ok().and_then(|s| s.parse::<usize>().ok())
-    };
-    // If any cached value is missing, compute all 4 via COUNT and save to meta (one-time migration).
-    let needs_migration = read_meta_int("stat_symbols").is_none();
-    let symbol_count = if needs_migration {
-        conn.query_row("SELECT COUNT(*) FROM symbols", [], |r| r.get::<_, i64>(0)).unwrap_or(0) as usize
-    } else {
-        read_meta_int("stat_symbols").unwrap_or(0)
+/// Transitive callers or callees of a method, `depth` levels deep (1 = direct ones, as in
+/// `get_function_context`). At most `max_children` neighbours per method and
+/// `CALL_TREE_MAX_NODES` in total; cycles and methods already expanded are marked, not repeated.
+pub fn get_call_tree(
+    db_path: &Path,
+    function_name: &str,
+    direction: CallDirection,
+    depth: usize,
+    max_children: usize,
+) -> Result<CallTreeNode, MethodLookupError> {
+    let conn = Connection::open(db_path).map_err(|_| MethodLookupError::NotFound)?;
+    let root = lookup_method(&conn, function_name)?;
+    let mut walk = CallTreeWalk {
+        conn: &conn,
+        direction,
+        max_children,
+        expanded: std::collections::HashSet::new(),
+        budget: CALL_TREE_MAX_NODES,
+    };
+    let root = CallNode { name: root.name, file: root.file, start_line: root.start_line };
+    Ok(walk.node(root, "call".to_string(), depth, &mut Vec::new()))
+}
+
+/// Shortest call chains from method `from` to method `to`, at most `max_depth` calls long.
+///
+/// Breadth-first over resolved calls, level by level: a method reached again on the same level
+/// adds another parent (another chain of the same length), one reached on a later level is a
+/// longer route or a cycle and is ignored. Only the first `max_fan_out` callees of a method are
+/// followed. Up to `max_paths` chains are returned.
+pub fn find_call_paths(
+    db_path: &Path,
+    from: &str,
+    to: &str,
+    max_depth: usize,
+    max_paths: usize,
+    max_fan_out: usize,
+) -> Result<CallPaths, String> {
+    let conn = Connection::open(db_path).map_err(|e| format!("Ошибка БД: {}", e))?;
+    let from = lookup_method(&conn, from).map_err(|e| e.message(from))?;
+    let to = lookup_method(&conn, to).map_err(|e| e.message(to))?;
+    let as_node = |s: &SymbolMatch| CallNode { name: s.name.clone(), file: s.file.clone(), start_line: s.start_line };
+    let start = method_key(&as_node(&from));
+    let target = method_key(&as_node(&to));
+    if start == target {
+        return Err("Начальный и конечный методы совпадают".to_string());
+    }
+
+    let mut level: std::collections::HashMap<MethodKey, usize> = std::collections::HashMap::new();
+    let mut parents: std::collections::HashMap<MethodKey, Vec<MethodKey>> = std::collections::HashMap::new();
+    let mut nodes: std::collections::HashMap<MethodKey, CallNode> = std::collections::HashMap::new();
+    level.insert(start.clone(), 0);
+    nodes.insert(start.clone(), as_node(&from));
+
+    let mut capped = false;
+    let mut frontier = vec![start.clone()];
+    for depth in 1..=max_depth {
+        let mut next = Vec::new();
+        for key in &frontier {
+            let callees = call_neighbours(&conn, key, CallDirection::Callees);
+            if callees.len() > max_fan_out {
+                capped = true;
+            }
+            for (callee, _) in callees.into_iter().take(max_fan_out) {
+                let callee_key = method_key(&callee);
+                match level.get(&callee_key) {
+                    None => {
+                        level.insert(callee_key.clone(), depth);
+                        parents.insert(callee_key.clone(), vec![key.clone()]);
+                        nodes.insert(callee_key.clone(), callee);
+                        next.push(callee_key);
+                    }
+                    Some(&d) if d == depth => {
+                        let list = parents.entry(callee_key).or_default();
+                        if !list.contains(key) {
+                            list.push(key.clone());
+                        }
+                    }
+                    Some(_) => {}
+                }
+            }
+            if level.len() >= CALL_PATH_MAX_VISITED {
+                capped = true;
+                break;
+            }
+        }
+        if level.contains_key(&target) || next.is_empty() || level.len() >= CALL_PATH_MAX_VISITED {
+            break;
+        }
+        frontier = next;
+    }
+
+    // Walk the parent links back from the target
+    let mut paths: Vec<Vec<CallNode>> = Vec::new();
+    if level.contains_key(&target) {
+        let mut stack: Vec<Vec<MethodKey>> = vec![vec![target.clone()]];
+        while let Some(chain) = stack.pop() {
+            if paths.len() >= max_paths {
+                break;
+            }
+            let last = chain.last().expect("chains are never empty");
+            if *last == start {
+                paths.push(chain.iter().rev().filter_map(|k| nodes.get(k).cloned()).collect());
+                continue;
+            }
+            // Reversed so that the first parent is expanded first
+            for parent in parents.get(last).into_iter().flatten().rev() {
+                let mut longer = chain.clone();
+                longer.push(parent.clone());
+                stack.push(longer);
+            }
+        }
+    }
+
+    Ok(CallPaths { from, to, paths, explored: level.len(), capped })
+}
+
+// ─── Unused methods ─────────────────────────────────────────────────────────
+
+/// Handlers of object, record set, manager, form, command and application module events
+/// (Russian and English names, lowercased): the platform calls them by name. Common modules
+/// have no events.
+const PLATFORM_EVENT_HANDLERS: &[&str] = &[
+    // Object, record set and constant value manager modules
+    "передзаписью", "призаписи", "передудалением", "прикопировании", "обработказаполнения",
+    "обработкапроверкизаполнения", "обработкапроведения", "обработкаудаленияпроведения",
+    "приустановкеновогономера", "приустановкеновогокода", "передстартом", "пристарте",
+    "призавершении", "передсозданиемзадач", "присозданиизадач", "передвыполнением",
+    "привыполнении", "передвыполнениеминтерактивно", "приполученииданныхотглавного",
+    "приполученииданныхотподчиненного", "приотправкеданныхглавному",
+    "приотправкеданныхподчиненному", "прикомпоновкерезультата",
+    "beforewrite", "onwrite", "beforedelete", "oncopy", "filling", "fillcheckprocessing",
+    "posting", "undoposting", "onsetnewnumber", "onsetnewcode", "beforestart", "onstart",
+    "oncomplete", "beforecreatetasks", "oncreatetasks", "beforeexecute", "onexecute",
+    "beforeinteractiveexecute", "oncomposeresult",
+    // Manager modules
+    "обработкаполученияданныхвыбора", "обработкаполученияформы",
+    "обработкаполученияпредставления", "обработкаполученияполейпредставления",
+    "choicedatagetprocessing", "formgetprocessing", "presentationgetprocessing",
+    "presentationfieldsgetprocessing",
+    // Form modules (handlers bound in the form description are found in `form_events`)
+    "присозданиинасервере", "приоткрытии", "передзакрытием", "призакрытии",
+    "обработкаоповещения", "обработкавыбора", "причтениинасервере", "передзаписьюнасервере",
+    "призаписинасервере", "послезаписи", "послезаписинасервере",
+    "обработкапроверкизаполнениянасервере",
+    "oncreateatserver", "onopen", "beforeclose", "onclose", "notificationprocessing",
+    "choiceprocessing", "onreadatserver", "beforewriteatserver", "onwriteatserver",
+    "afterwrite", "afterwriteatserver", "fillcheckprocessingatserver",
+    // Command modules
+    "обработкакоманды", "commandprocessing",
+    // Application, session and external connection modules
+    "передначаломработысистемы", "приначалеработысистемы", "передзавершениемработысистемы",
+    "призавершенииработысистемы", "обработкавнешнегособытия", "обработкаотображенияошибки",
+    "установкапараметровсеанса",
+    "beforeexit", "onexit", "externaleventprocessing",
+    "errordisplayprocessing", "sessionparameterssetting",
+];
+
+/// Methods nothing calls, by object.
+pub struct UnusedSymbols {
+    /// (`Document.Заказ`, its methods by module and line), objects in name order.
+    pub objects: Vec<(String, Vec<SymbolMatch>)>,
+    /// Methods checked.
+    pub checked: usize,
+    /// Unused methods found; more than listed in `objects` when over the limit.
+    pub total: usize,
+    /// Objects with unused methods; more than `objects.len()` when over the limit.
+    pub total_objects: usize,
+}
+
+/// Object a module belongs to (`Document.Заказ` for its object, manager and form modules),
+/// with the extension as in `module_label`. Modules outside objects keep their path.
+pub fn module_object_label(file: &str) -> String {
+    let label = match infer_object_from_path(file) {
+        (Some(obj_type), Some(obj_name), _) => format!("{}.{}", obj_type, obj_name),
+        _ => split_origin(file).1.to_string(),
+    };
+    match split_origin(file).0 {
+        Some(origin) => format!("{} [{}]", label, origin),
+        None => label,
+    }
+}
+
+/// Methods of modules matching `module_path` (a path substring, as in `get_module_functions`)
+/// that nothing calls: a non-exported method not called in its own module, an exported one
+/// not called anywhere. A call the resolver could not link to a module (through a variable,
+/// `ЭтотОбъект.Метод()`) counts for every method it may reach by name, and so does a name in a
+/// string literal (`Выполнить`, `ОписаниеОповещения`), so doubtful cases are left out.
+///
+/// Entry points are never unused: handlers of event subscriptions, scheduled jobs, HTTP and web
+/// services and common commands (`calls` rows added by `build_metadata`), form event and
+/// command handlers, extension interceptors and module event handlers (`PLATFORM_EVENT_HANDLERS`).
+/// Objects are listed whole, in name order, while their methods fit in `limit` (the first
+/// object always).
+pub fn find_unused_symbols(
+    db_path: &Path,
+    module_path: &str,
+    filter: &SymbolFilter,
+    include_exported: bool,
+    limit: usize,
+) -> Option<UnusedSymbols> {
+    let conn = Connection::open(db_path).ok()?;
+    let pattern = format!("%{}%", module_path.replace('\\', "/"));
+    let (filter_sql, filter_values) = filter.sql_conditions();
+    let mut scope = "file LIKE ?".to_string() + &filter_sql;
+    if !include_exported {
+        scope.push_str(" AND is_export = 0");
+    }
+    let values: Vec<&String> = std::iter::once(&pattern).chain(&filter_values).collect();
+
+    let checked: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM symbols WHERE {}", scope),
+            rusqlite::params_from_iter(values.iter()),
+            |r| r.get(0),
+        )
+        .ok()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM symbols WHERE {} \
+             AND NOT EXISTS (SELECT 1 FROM calls c WHERE c.callee_name_lower = symbols.name_lower \
+                 AND (c.callee_file = symbols.file \
+                      OR (c.callee_file IS NULL AND (symbols.is_export = 1 OR c.caller_file = symbols.file)))) \
+             AND NOT EXISTS (SELECT 1 FROM string_refs r WHERE r.name_lower = symbols.name_lower \
+                 AND (symbols.is_export = 1 OR r.file = symbols.file)) \
+             AND NOT EXISTS (SELECT 1 FROM form_events e JOIN forms f ON f.id = e.form_id \
+                 WHERE f.module_file = symbols.file AND e.handler_lower = symbols.name_lower) \
+             AND NOT EXISTS (SELECT 1 FROM form_commands fc JOIN forms f ON f.id = fc.form_id \
+                 WHERE f.module_file = symbols.file AND fc.action_lower = symbols.name_lower) \
+             AND NOT EXISTS (SELECT 1 FROM symbol_interceptions i WHERE i.symbol_id = symbols.id) \
+             ORDER BY file, start_line",
+            symbol_columns(),
+            scope
+        ))
+        .ok()?;
+    let unused: Vec<SymbolMatch> = stmt
+        .query_map(rusqlite::params_from_iter(values.iter()), symbol_row_mapper)
+        .ok()?
+        .flatten()
+        .filter(|s| {
+            infer_object_from_path(&s.file).0.as_deref() == Some("CommonModule")
+                || !PLATFORM_EVENT_HANDLERS.contains(&s.name.to_lowercase().as_str())
+        })
+        .collect();
+
+    let total = unused.len();
+    let mut by_object: std::collections::BTreeMap<String, Vec<SymbolMatch>> = std::collections::BTreeMap::new();
+    for symbol in unused {
+        by_object.entry(module_object_label(&symbol.file)).or_default().push(symbol);
+    }
+    let total_objects = by_object.len();
+    let mut objects = Vec::new();
+    let mut listed = 0usize;
+    for (object, methods) in by_object {
+        if !objects.is_empty() && listed + methods.len() > limit {
+            break;
+        }
+        listed += methods.len();
+        objects.push((object, methods));
+    }
+    Some(UnusedSymbols { objects, checked: checked as usize, total, total_objects })
+}
+
+/// List all functions in a module matching the given path substring.
+pub fn get_module_functions(
+    db_path: &Path,
+    module_path: &str,
+    limit: usize,
+    filter: &SymbolFilter,
+) -> Vec<SymbolMatch> {
+    let conn = match Connection::open(db_path) {
+        Ok(c) => c,
+        Err(_) => return vec![],
+    };
+    let pattern = format!("%{}%", module_path.replace('\\', "/"));
+    let (filter_sql, filter_values) = filter.sql_conditions();
+    let mut stmt = match conn.prepare(&format!(
+        "SELECT {} FROM symbols WHERE file LIKE ?{} ORDER BY start_line LIMIT ?",
+        symbol_columns(), filter_sql
+    )) {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+    collect_symbol_rows(&mut stmt, &[pattern], &filter_values, limit).unwrap_or_default()
+}
+
+// ─── Module outline ─────────────────────────────────────────────────────────
+
+pub struct RegionInfo {
+    pub name: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub depth: u32,
+}
+
+pub struct ModuleVarInfo {
+    pub name: String,
+    pub line: u32,
+    pub is_export: bool,
+    pub directive: Option<String>,
+}
+
+/// Regions, module variables and methods of one module, each ordered by line.
+pub struct ModuleOutline {
+    pub file: String,
+    pub regions: Vec<RegionInfo>,
+    pub variables: Vec<ModuleVarInfo>,
+    pub methods: Vec<SymbolMatch>,
+}
+
+/// Outlines of the BSL modules whose path contains `module_path` (at most `max_files`),
+/// plus the total number of matching modules.
+pub fn get_module_outline(db_path: &Path, module_path: &str, max_files: usize) -> (Vec<ModuleOutline>, usize) {
+    let conn = match Connection::open(db_path) {
+        Ok(c) => c,
+        Err(_) => return (vec![], 0),
+    };
+    let pattern = format!("%{}%", module_path.replace('\\', "/").to_lowercase());
+    let files: Vec<String> = conn
+        .prepare("SELECT filepath FROM indexed_files WHERE path_lower LIKE ?1 AND extension = 'bsl' ORDER BY filepath")
+        .and_then(|mut stmt| {
+            stmt.query_map(params![pattern], |row| row.get(0))
+                .map(|rows| rows.flatten().collect())
+        })
+        .unwrap_or_default();
+    let total = files.len();
+
+    let outlines = files
+        .into_iter()
+        .take(max_files)
+        .map(|file| {
+            let regions = conn
+                .prepare("SELECT name, start_line, end_line, depth FROM module_regions WHERE file = ?1 ORDER BY start_line")
+                .and_then(|mut stmt| {
+                    stmt.query_map(params![file], |row| {
+                        Ok(RegionInfo {
+                            name: row.get(0)?,
+                            start_line: row.get(1)?,
+                            end_line: row.get(2)?,
+                            depth: row.get(3)?,
+                        })
+                    })
+                    .map(|rows| rows.flatten().collect())
+                })
+                .unwrap_or_default();
+            let variables = conn
+                .prepare("SELECT name, line, is_export, directive FROM module_vars WHERE file = ?1 ORDER BY line")
+                .and_then(|mut stmt| {
+                    stmt.query_map(params![file], |row| {
+                        Ok(ModuleVarInfo {
+                            name: row.get(0)?,
+                            line: row.get(1)?,
+                            is_export: row.get::<_, i32>(2)? != 0,
+                            directive: row.get(3)?,
+                        })
+                    })
+                    .map(|rows| rows.flatten().collect())
+                })
+                .unwrap_or_default();
+            let methods = conn
+                .prepare(&format!("SELECT {} FROM symbols WHERE file = ?1 ORDER BY start_line", symbol_columns()))
+                .and_then(|mut stmt| {
+                    stmt.query_map(params![file], symbol_row_mapper)
+                        .map(|rows| rows.flatten().collect())
+                })
+                .unwrap_or_default();
+            ModuleOutline { file, regions, variables, methods }
+        })
+        .collect();
+    (outlines, total)
+}
+
+// ─── Embedded queries ───────────────────────────────────────────────────────
+
+/// One metadata table read by a query text found in code.
+pub struct QueryUsage {
+    pub file: String,
+    /// Enclosing procedure or function; `None` for module-level code.
+    pub symbol: Option<String>,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// Table as written in the query: `РегистрНакопления.ТоварыНаСкладах.Остатки`.
+    pub table: String,
+    /// Fields read through the table alias.
+    pub fields: Vec<String>,
+}
+
+/// Queries reading `object` (`AccumulationRegister.ТоварыНаСкладах`, or a bare name of any type),
+/// optionally only through virtual table or tabular section `part` and only those reading `field`.
+/// Returns the first `limit` in file order and the number of all matching ones.
+pub fn find_queries_using(
+    db_path: &Path,
+    object: &str,
+    part: Option<&str>,
+    field: Option<&str>,
+    limit: usize,
+) -> (Vec<QueryUsage>, usize) {
+    let Ok(conn) = Connection::open(db_path) else { return (Vec::new(), 0) };
+    let object_cond = if object.contains('.') {
+        "t.object_lower = ?1"
+    } else {
+        "substr(t.object_lower, instr(t.object_lower, '.') + 1) = ?1"
+    };
+    // `fields_lower` is a `, `-separated list: match whole names only
+    let filter = format!(
+        "{} AND (?2 IS NULL OR t.part_lower = ?2) \
+         AND (?3 IS NULL OR instr(', ' || t.fields_lower || ', ', ', ' || ?3 || ', ') > 0)",
+        object_cond
+    );
+    let (object_lower, part_lower, field_lower) =
+        (object.to_lowercase(), part.map(str::to_lowercase), field.map(str::to_lowercase));
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM query_tables t WHERE {}", filter),
+            params![object_lower, part_lower, field_lower],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+    let sql = format!(
+        "SELECT q.file, s.name, q.start_line, q.end_line, t.name, t.fields FROM query_tables t \
+         JOIN queries q ON q.id = t.query_id LEFT JOIN symbols s ON s.id = q.symbol_id \
+         WHERE {} ORDER BY q.file, q.start_line LIMIT ?4",
+        filter
+    );
+    let Ok(mut stmt) = conn.prepare(&sql) else { return (Vec::new(), 0) };
+    let usages = stmt
+        .query_map(params![object_lower, part_lower, field_lower, limit as i64], |r| {
+            Ok(QueryUsage {
+                file: r.get(0)?,
+                symbol: r.get(1)?,
+                start_line: r.get(2)?,
+                end_line: r.get(3)?,
+                table: r.get(4)?,
+                fields: r
+                    .get::<_, String>(5)?
+                    .split(", ")
+                    .filter(|f| !f.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .map(|rows| rows.flatten().collect())
+        .unwrap_or_default();
+    (usages, total as usize)
+}
+
+// ─── Execution context checks ───────────────────────────────────────────────
+
+/// Where a method runs, derived from its compilation directive or, without one, from its module.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ExecContext {
+    Client,
+    Server,
+    ServerNoContext,
+    ClientServerNoContext,
+    /// Server common module with the «Вызов сервера» flag — callable from the client.
+    ServerCall,
+    /// Available in both contexts or not determinable.
+    Any,
+}
+
+impl ExecContext {
+    fn from_directive(directive: &str) -> ExecContext {
+        match directive {
+            "НаКлиенте"                      => ExecContext::Client,
+            "НаСервере"                      => ExecContext::Server,
+            "НаСервереБезКонтекста"          => ExecContext::ServerNoContext,
+            "НаКлиентеНаСервереБезКонтекста" => ExecContext::ClientServerNoContext,
+            _                                => ExecContext::Any,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ExecContext::Client                => "клиент",
+            ExecContext::Server                => "сервер",
+            ExecContext::ServerNoContext       => "сервер без контекста",
+            ExecContext::ClientServerNoContext => "клиент и сервер без контекста",
+            ExecContext::ServerCall            => "сервер (вызов сервера)",
+            ExecContext::Any                   => "любой",
+        }
+    }
+}
+
+/// Context of methods without a directive, by module kind.
+/// Common module flags come from the metadata index (`objects`).
+fn module_exec_context(conn: &Connection, file: &str) -> ExecContext {
+    let (obj_type, obj_name, module_kind) = infer_object_from_path(file);
+    match (obj_type.as_deref(), module_kind.as_deref()) {
+        (Some("CommonModule"), Some("Module")) => {
+            let Some(flags) = read_common_module_flags(conn, &obj_name.unwrap_or_default()) else {
+                return ExecContext::Any;
+            };
+            let client = flags.client_managed || flags.client_ordinary;
+            match (client, flags.server, flags.server_call) {
+                (true, false, _)     => ExecContext::Client,
+                (false, true, true)  => ExecContext::ServerCall,
+                (false, true, false) => ExecContext::Server,
+                _                    => ExecContext::Any,
+            }
+        }
+        // Form methods without a directive compile on the server
+        (_, Some("FormModule")) => ExecContext::Server,
+        (_, Some("ManagerModule")) | (_, Some("ObjectModule")) => ExecContext::Server,
+        _ => ExecContext::Any,
+    }
+}
+
+/// Why a call from `caller` to `callee` cannot work as written, if it can't.
+fn context_violation(caller: ExecContext, callee: ExecContext, same_module: bool) -> Option<&'static str> {
+    use ExecContext::*;
+    match (caller, callee) {
+        (Client, Server) | (Client, ServerNoContext) if !same_module =>
+            Some("клиентский метод напрямую вызывает серверный модуль (нужен общий модуль с флагом «Вызов сервера»)"),
+        (Server, Client) | (ServerNoContext, Client) | (ServerCall, Client) =>
+            Some("серверный метод вызывает клиентский метод"),
+        (ServerNoContext, Server) if same_module =>
+            Some("метод &НаСервереБезКонтекста вызывает контекстный серверный метод формы"),
+        (ClientServerNoContext, Client) =>
+            Some("метод &НаКлиентеНаСервереБезКонтекста вызывает клиентский метод (недоступен на сервере)"),
+        (ClientServerNoContext, Server) =>
+            Some("метод &НаКлиентеНаСервереБезКонтекста вызывает серверный метод (недоступен на клиенте)"),
+        _ => None,
+    }
+}
+
+pub struct ContextViolation {
+    pub caller: String,
+    pub caller_file: String,
+    pub caller_line: u32,
+    pub caller_context: &'static str,
+    pub callee: String,
+    pub callee_file: String,
+    pub callee_context: &'static str,
+    pub reason: &'static str,
+}
+
+/// Check resolved call edges for calls that are invalid between execution contexts:
+/// a `&НаКлиенте` method calling a server-only module directly, server code calling client code, etc.
+/// `scope_prefix` restricts callers to files under a relative path.
+pub fn find_context_violations(
+    db_path: &Path,
+    scope_prefix: Option<&str>,
+    limit: usize,
+) -> Result<Vec<ContextViolation>, String> {
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    let scope = format!("{}%", scope_prefix.unwrap_or("").replace('\\', "/"));
+    let mut stmt = conn.prepare(
+        "SELECT c.caller_name, c.caller_file, cs.start_line, cs.directive, \
+                c.callee_name, c.callee_file, ts.directive \
+         FROM calls c \
+         JOIN symbols cs ON cs.file = c.caller_file AND cs.name_lower = c.caller_name_lower \
+         JOIN symbols ts ON ts.file = c.callee_file AND ts.name_lower = c.callee_name_lower \
+         WHERE c.callee_file IS NOT NULL AND c.caller_file LIKE ?1 \
+         ORDER BY c.caller_file, cs.start_line"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![scope], |r| {
+        Ok((
+            r.get::<_, String>(0)?,
+            r.get::<_, String>(1)?,
+            r.get::<_, u32>(2)?,
+            r.get::<_, Option<String>>(3)?,
+            r.get::<_, String>(4)?,
+            r.get::<_, String>(5)?,
+            r.get::<_, Option<String>>(6)?,
+        ))
+    }).map_err(|e| e.to_string())?;
+
+    let mut module_cache: std::collections::HashMap<String, ExecContext> = std::collections::HashMap::new();
+    let mut context_of = |directive: &Option<String>, file: &str| -> ExecContext {
+        match directive {
+            Some(d) => ExecContext::from_directive(d),
+            None => *module_cache
+                .entry(file.to_string())
+                .or_insert_with(|| module_exec_context(&conn, file)),
+        }
+    };
+
+    let mut result = Vec::new();
+    for (caller, caller_file, caller_line, caller_dir, callee, callee_file, callee_dir) in rows.flatten() {
+        let caller_ctx = context_of(&caller_dir, &caller_file);
+        if caller_ctx == ExecContext::Any {
+            continue;
+        }
+        let callee_ctx = context_of(&callee_dir, &callee_file);
+        if let Some(reason) = context_violation(caller_ctx, callee_ctx, caller_file == callee_file) {
+            result.push(ContextViolation {
+                caller,
+                caller_file,
+                caller_line,
+                caller_context: caller_ctx.label(),
+                callee,
+                callee_file,
+                callee_context: callee_ctx.label(),
+                reason,
+            });
+            if result.len() >= limit {
+                break;
+            }
+        }
+    }
+    Ok(result)
+}
+
+// ─── Stats ────────────────────────────────────────────────────────────────
+
+pub struct IndexStats {
+    pub symbol_count: usize,
+    pub file_count: usize,
+    pub object_count: usize,
+    pub calls_count: usize,
+    pub built_at: Option<u64>,
+    pub db_size_mb: f64,
+}
+
+pub fn get_index_stats(db_path: &Path) -> IndexStats {
+    let db_size_mb = std::fs::metadata(db_path)
+        .map(|m| m.len() as f64 / 1024.0 / 1024.0)
+        .unwrap_or(0.0);
+
+    let conn = match Connection::open(db_path) {
+        Ok(c) => c,
+        Err(_) => return IndexStats { symbol_count: 0, file_count: 0, object_count: 0, calls_count: 0, built_at: None, db_size_mb },
+    };
+
+    // Try reading cached counts from meta table (written at build/sync time) — O(1)
+    let read_meta_int = |key: &str| -> Option<usize> {
+        conn.query_row(
+            "SELECT value FROM meta WHERE key = ?1",
+            [key],
+            |r| r.get::<_, String>(0),
+        ).ok().and_then(|s| s.parse::<usize>().ok())
+    };
+    // If any cached value is missing, compute all 4 via COUNT and save to meta (one-time migration).
+    let needs_migration = read_meta_int("stat_symbols").is_none();
+    let symbol_count = if needs_migration {
+        conn.query_row("SELECT COUNT(*) FROM symbols", [], |r| r.get::<_, i64>(0)).unwrap_or(0) as usize
+    } else {
+        read_meta_int("stat_symbols").unwrap_or(0)
     };
     let file_count = if needs_migration {
         conn.query_row("SELECT COUNT(*) FROM indexed_files", [], |r| r.get::<_, i64>(0)).unwrap_or(0) as usize
@@ -1521,22 +4061,17 @@ pub fn get_index_stats(db_path: &Path) -> IndexStats {
     IndexStats { symbol_count, file_count, object_count, calls_count, built_at, db_size_mb }
 }
 
-/// Get full structure of an object by name (case-insensitive).
-pub fn get_object_details(db_path: &Path, name_query: &str) -> Option<ObjectDetails> {
-    let conn = Connection::open(db_path).ok()?;
-    
-    // Handle qualified names like "Catalog.Agent" or "CommonModule.РаботаСФайлами"
-    let (obj_type_filter, name_filter) = if let Some(dot_pos) = name_query.find('.') {
-        let t = &name_query[..dot_pos];
-        let n = &name_query[dot_pos + 1..];
-        (Some(t.to_string()), n.to_string())
-    } else {
-        (None, name_query.to_string())
-    };
-
-    let name_lower = name_filter.to_lowercase();
+/// Split `Catalog.Agent` / `CommonModule.РаботаСФайлами` into type filter and lowercased name.
+fn split_object_query(name_query: &str) -> (Option<String>, String) {
+    match name_query.find('.') {
+        Some(dot_pos) => (Some(name_query[..dot_pos].to_string()), name_query[dot_pos + 1..].to_lowercase()),
+        None => (None, name_query.to_lowercase()),
+    }
+}
 
-    // Find the object — try exact match first
+/// `find_object` without the substring fallback, for tools where a near name would mislead.
+fn find_object_exact(conn: &Connection, name_query: &str) -> Option<(String, String, i64)> {
+    let (obj_type_filter, name_lower) = split_object_query(name_query);
     let query = if let Some(ref _t) = obj_type_filter {
         "SELECT obj_type, name, id FROM objects WHERE name_lower = ?1 AND obj_type LIKE ?2 LIMIT 1"
     } else {
@@ -1550,8 +4085,13 @@ pub fn get_object_details(db_path: &Path, name_query: &str) -> Option<ObjectDeta
         conn.query_row(query, params![name_lower], 
             |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, i64>(2)?)))
     };
+    res.ok()
+}
 
-    let (obj_type, obj_name, obj_id) = res.or_else(|_| {
+/// Resolve `Catalog.Агенты` or a bare `Агенты` to (obj_type, name, id): exact name first, then substring.
+fn find_object(conn: &Connection, name_query: &str) -> Option<(String, String, i64)> {
+    find_object_exact(conn, name_query).or_else(|| {
+        let (obj_type_filter, name_lower) = split_object_query(name_query);
         let like_pattern = format!("%{}%", name_lower);
         if let Some(ref t) = obj_type_filter {
             conn.query_row(
@@ -1566,56 +4106,112 @@ pub fn get_object_details(db_path: &Path, name_query: &str) -> Option<ObjectDeta
                 |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, i64>(2)?))
             )
         }
-    }).ok()?;
+        .ok()
+    })
+}
+
+/// Get full structure of an object by name (case-insensitive).
+pub fn get_object_details(db_path: &Path, name_query: &str) -> Option<ObjectDetails> {
+    let conn = Connection::open(db_path).ok()?;
+    let (obj_type, obj_name, obj_id) = find_object(&conn, name_query)?;
 
     // Fetch all children
     let mut stmt = conn.prepare(
-        "SELECT item_type, item_name, parent_section FROM object_items WHERE object_id = ?1 ORDER BY item_type, parent_section, item_name"
+        "SELECT item_type, item_name, parent_section, value_type, synonym, comment, indexing, fill_checking \
+         FROM object_items WHERE object_id = ?1 ORDER BY item_type, parent_section, item_name"
     ).ok()?;
-    let children: Vec<(String, String, Option<String>)> = stmt
+    let children: Vec<(String, Option<String>, ObjectAttribute)> = stmt
         .query_map(params![obj_id], |r| {
-            Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, Option<String>>(2)?))
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, Option<String>>(2)?,
+                ObjectAttribute {
+                    name: r.get(1)?,
+                    value_type: r.get(3)?,
+                    synonym: r.get(4)?,
+                    comment: r.get(5)?,
+                    indexing: r.get(6)?,
+                    fill_checking: r.get(7)?,
+                },
+            ))
         })
         .ok()?
         .flatten()
         .collect();
 
     let mut attributes = Vec::new();
+    let mut dimensions = Vec::new();
+    let mut resources = Vec::new();
+    let mut register_records = Vec::new();
     let mut forms = Vec::new();
     let mut commands = Vec::new();
     let mut modules = Vec::new();
-    let mut tab_section_attrs: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
-    let mut tab_section_names: Vec<String> = Vec::new();
+    let mut tab_section_attrs: std::collections::HashMap<String, Vec<ObjectAttribute>> = std::collections::HashMap::new();
+    let mut tab_sections: Vec<(String, Option<String>)> = Vec::new();
 
-    for (item_type, item_name, parent) in children {
+    for (item_type, parent, item) in children {
         match item_type.as_str() {
             "Attribute" => {
                 if let Some(sec) = parent {
-                    tab_section_attrs.entry(sec).or_default().push(item_name);
+                    tab_section_attrs.entry(sec).or_default().push(item);
                 } else {
-                    attributes.push(item_name);
+                    attributes.push(item);
                 }
             }
             "TabularSection" => {
-                if !tab_section_names.contains(&item_name) {
-                    tab_section_names.push(item_name);
+                if !tab_sections.iter().any(|(name, _)| *name == item.name) {
+                    tab_sections.push((item.name, item.synonym));
                 }
             }
-            "Form" => forms.push(item_name),
-            "Command" => commands.push(item_name),
-            t if t.ends_with("Module") => modules.push(item_name),
+            "Dimension" => dimensions.push(item),
+            "Resource" => resources.push(item),
+            "RegisterRecord" => register_records.push(item.name),
+            "Form" => forms.push(item.name),
+            "Command" => commands.push(item.name),
+            t if t.ends_with("Module") => modules.push(item.name),
             _ => {}
         }
     }
 
-    let tabular_sections = tab_section_names
+    let tabular_sections = tab_sections
         .into_iter()
-        .map(|sec| {
-            let attrs = tab_section_attrs.remove(&sec).unwrap_or_default();
-            (sec, attrs)
+        .map(|(name, synonym)| {
+            let attributes = tab_section_attrs.remove(&name).unwrap_or_default();
+            TabularSectionInfo { name, synonym, attributes }
         })
         .collect();
 
+    let properties: Vec<(String, String)> = conn
+        .prepare("SELECT name, value FROM object_properties WHERE object_id = ?1 ORDER BY rowid")
+        .ok()
+        .map(|mut stmt| {
+            stmt.query_map(params![obj_id], |r| Ok((r.get(0)?, r.get(1)?)))
+                .map(|rows| rows.flatten().collect())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default();
+
+    let recorders: Vec<String> = conn
+        .prepare(
+            "SELECT o.obj_type, o.name FROM object_items i JOIN objects o ON o.id = i.object_id \
+             WHERE i.item_type = 'RegisterRecord' AND i.item_name = ?1 ORDER BY o.obj_type, o.name",
+        )
+        .ok()
+        .map(|mut stmt| {
+            stmt.query_map(params![format!("{}.{}", obj_type, obj_name)], |r| {
+                Ok(format!("{}.{}", r.get::<_, String>(0)?, r.get::<_, String>(1)?))
+            })
+            .map(|rows| rows.flatten().collect())
+            .unwrap_or_default()
+        })
+        .unwrap_or_default();
+
+    let predefined = predefined_values_of(&conn, obj_id, &format!("{}.{}", obj_type, obj_name));
+    let module_flags = match obj_type.as_str() {
+        "CommonModule" => read_common_module_flags(&conn, &obj_name),
+        _ => None,
+    };
+
     Some(ObjectDetails {
         obj_type,
         name: obj_name,
@@ -1624,5 +4220,1295 @@ pub fn get_object_details(db_path: &Path, name_query: &str) -> Option<ObjectDeta
         forms,
         commands,
         modules,
+        dimensions,
+        resources,
+        properties,
+        register_records,
+        recorders,
+        predefined,
+        module_flags,
+    })
+}
+
+fn predefined_values_of(conn: &Connection, obj_id: i64, object: &str) -> Vec<PredefinedValue> {
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT name, synonym, code, is_folder, parent FROM predefined_values WHERE object_id = ?1 ORDER BY rowid",
+    ) else {
+        return Vec::new();
+    };
+    stmt.query_map(params![obj_id], |r| {
+        Ok(PredefinedValue {
+            object: object.to_string(),
+            name: r.get(0)?,
+            synonym: r.get(1)?,
+            code: r.get(2)?,
+            is_folder: r.get::<_, i64>(3)? != 0,
+            parent: r.get(4)?,
+        })
+    })
+    .map(|rows| rows.flatten().collect())
+    .unwrap_or_default()
+}
+
+/// Enum values or predefined items of exactly `obj_type.name` (case-insensitive).
+/// `None` when there is no such object; the qualified object name is returned with its values.
+pub fn get_predefined_values(db_path: &Path, obj_type: &str, name: &str) -> Option<(String, Vec<PredefinedValue>)> {
+    let conn = Connection::open(db_path).ok()?;
+    let (obj_type, obj_name, obj_id): (String, String, i64) = conn
+        .query_row(
+            "SELECT obj_type, name, id FROM objects WHERE obj_type = ?1 AND name_lower = ?2 LIMIT 1",
+            params![obj_type, name.to_lowercase()],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .ok()?;
+    let object = format!("{}.{}", obj_type, obj_name);
+    let values = predefined_values_of(&conn, obj_id, &object);
+    Some((object, values))
+}
+
+/// Enum values and predefined items named `name` in any object, or only in objects named
+/// `object` (of any type) when given.
+pub fn find_predefined_by_name(db_path: &Path, name: &str, object: Option<&str>) -> Vec<PredefinedValue> {
+    let Ok(conn) = Connection::open(db_path) else { return Vec::new() };
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT o.obj_type || '.' || o.name, p.name, p.synonym, p.code, p.is_folder, p.parent \
+         FROM predefined_values p JOIN objects o ON o.id = p.object_id \
+         WHERE p.name_lower = ?1 AND (?2 IS NULL OR o.name_lower = ?2) \
+         ORDER BY o.obj_type, o.name LIMIT 50",
+    ) else {
+        return Vec::new();
+    };
+    stmt.query_map(params![name.to_lowercase(), object.map(str::to_lowercase)], |r| {
+        Ok(PredefinedValue {
+            object: r.get(0)?,
+            name: r.get(1)?,
+            synonym: r.get(2)?,
+            code: r.get(3)?,
+            is_folder: r.get::<_, i64>(4)? != 0,
+            parent: r.get(5)?,
+        })
+    })
+    .map(|rows| rows.flatten().collect())
+    .unwrap_or_default()
+}
+
+/// Metadata-level edges of an object: what its attributes, register records and linked charts
+/// reference, and which objects reference it. References through a defined type are followed
+/// one level, so `Контрагент: DefinedType.Контрагент` counts as a use of every catalog in it.
+pub fn get_metadata_dependencies(db_path: &Path, name_query: &str) -> Option<MetadataDependencies> {
+    let conn = Connection::open(db_path).ok()?;
+    let (obj_type, obj_name, obj_id) = find_object(&conn, name_query)?;
+    let object = format!("{}.{}", obj_type, obj_name);
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT item, kind, target FROM metadata_references WHERE object_id = ?1 \
+             ORDER BY target, item",
+        )
+        .ok()?;
+    let uses: Vec<MetadataReference> = stmt
+        .query_map(params![obj_id], |r| {
+            Ok(MetadataReference {
+                object: object.clone(),
+                item: r.get(0)?,
+                kind: r.get(1)?,
+                target: r.get(2)?,
+                via: None,
+            })
+        })
+        .ok()?
+        .flatten()
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT o.obj_type || '.' || o.name, r.item, r.kind, r.target FROM metadata_references r \
+             JOIN objects o ON o.id = r.object_id WHERE r.target_lower = ?1 \
+             ORDER BY o.obj_type, o.name, r.item",
+        )
+        .ok()?;
+    let mut referencing = |target: &str, via: Option<&str>| -> Vec<MetadataReference> {
+        stmt.query_map(params![target.to_lowercase()], |r| {
+            Ok(MetadataReference {
+                object: r.get(0)?,
+                item: r.get(1)?,
+                kind: r.get(2)?,
+                target: r.get(3)?,
+                via: via.map(str::to_string),
+            })
+        })
+        .map(|rows| rows.flatten().collect())
+        .unwrap_or_default()
+    };
+    let direct = referencing(&object, None);
+    let mut used_by = Vec::new();
+    for edge in direct {
+        let defined_type = (obj_type != "DefinedType" && edge.object.starts_with("DefinedType."))
+            .then(|| edge.object.clone());
+        used_by.push(edge);
+        if let Some(defined_type) = defined_type {
+            used_by.extend(referencing(&defined_type, Some(&defined_type)));
+        }
+    }
+
+    Some(MetadataDependencies { object, uses, used_by })
+}
+
+// ─── Role rights ───────────────────────────────────────────────────────────
+
+/// One right of a role on an object or one of its fields, as listed in the role's `Rights.xml`.
+pub struct RoleRight {
+    pub role: String,
+    /// `Document.РеализацияТоваров`.
+    pub object: String,
+    /// Field the right applies to (`Attribute.Сумма`); `None` for the object itself.
+    pub item: Option<String>,
+    /// `Read`, `Posting`, `InteractiveDelete`...
+    pub right: String,
+    pub granted: bool,
+    /// RLS condition restricting the right.
+    pub condition: Option<String>,
+    /// Not listed in the role's `Rights.xml`: granted by its "rights for new objects" flag,
+    /// `right` is empty then.
+    pub inherited: bool,
+}
+
+pub struct RoleRightsDetails {
+    pub role: String,
+    /// Rights on objects added to the configuration later are granted by default.
+    pub set_for_new_objects: bool,
+    pub rights: Vec<RoleRight>,
+    /// Restriction templates: (name with parameters, text).
+    pub templates: Vec<(String, String)>,
+}
+
+fn role_right_mapper(row: &rusqlite::Row<'_>) -> rusqlite::Result<RoleRight> {
+    Ok(RoleRight {
+        role: row.get(0)?,
+        object: row.get(1)?,
+        item: row.get(2)?,
+        right: row.get(3)?,
+        granted: row.get::<_, i64>(4)? != 0,
+        condition: row.get(5)?,
+        inherited: false,
+    })
+}
+
+/// Rights of every role on an object (`Document.Заказ` or a bare `Заказ`) and its fields,
+/// then an inherited right for each role with "rights for new objects" that doesn't list the
+/// object. `None` when there is no object with exactly this name; the qualified object name is
+/// returned with the rights.
+pub fn get_object_rights(db_path: &Path, name_query: &str) -> Option<(String, Vec<RoleRight>)> {
+    let conn = Connection::open(db_path).ok()?;
+    let (obj_type, obj_name, _) = find_object_exact(&conn, name_query)?;
+    let object = format!("{}.{}", obj_type, obj_name);
+    let mut stmt = conn
+        .prepare(
+            "SELECT o.name, r.object, r.item, r.right_name, r.granted, r.condition FROM role_rights r \
+             JOIN objects o ON o.id = r.role_id WHERE r.object_lower = ?1 \
+             ORDER BY o.name, r.item, r.rowid",
+        )
+        .ok()?;
+    let mut rights: Vec<RoleRight> = stmt
+        .query_map(params![object.to_lowercase()], role_right_mapper)
+        .ok()?
+        .flatten()
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT o.name FROM objects o \
+             JOIN object_properties p ON p.object_id = o.id AND p.name = 'SetForNewObjects' AND p.value = 'true' \
+             WHERE o.obj_type = 'Role' \
+               AND NOT EXISTS (SELECT 1 FROM role_rights r WHERE r.role_id = o.id AND r.object_lower = ?1) \
+             ORDER BY o.name",
+        )
+        .ok()?;
+    let inherited = stmt
+        .query_map(params![object.to_lowercase()], |row| {
+            Ok(RoleRight {
+                role: row.get(0)?,
+                object: object.clone(),
+                item: None,
+                right: String::new(),
+                granted: true,
+                condition: None,
+                inherited: true,
+            })
+        })
+        .ok()?
+        .flatten();
+    rights.extend(inherited);
+    Some((object, rights))
+}
+
+/// Everything a role grants or denies, optionally limited to objects of one type (`Document`),
+/// with its restriction templates. `None` when there is no such role.
+pub fn get_role_rights(db_path: &Path, role: &str, object_type: Option<&str>) -> Option<RoleRightsDetails> {
+    let conn = Connection::open(db_path).ok()?;
+    let (_, role, role_id) = find_object_exact(&conn, &format!("Role.{}", role))?;
+    let set_for_new_objects = conn
+        .query_row(
+            "SELECT value FROM object_properties WHERE object_id = ?1 AND name = 'SetForNewObjects'",
+            params![role_id],
+            |r| r.get::<_, String>(0),
+        )
+        .is_ok_and(|v| v == "true");
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT ?2, object, item, right_name, granted, condition FROM role_rights \
+             WHERE role_id = ?1 ORDER BY object, item, rowid",
+        )
+        .ok()?;
+    let mut rights: Vec<RoleRight> = stmt
+        .query_map(params![role_id, role], role_right_mapper)
+        .ok()?
+        .flatten()
+        .collect();
+    if let Some(object_type) = object_type {
+        rights.retain(|r| r.object.split_once('.').is_some_and(|(t, _)| t.eq_ignore_ascii_case(object_type)));
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT name, condition FROM role_templates WHERE role_id = ?1 ORDER BY rowid")
+        .ok()?;
+    let templates = stmt
+        .query_map(params![role_id], |r| Ok((r.get(0)?, r.get(1)?)))
+        .ok()?
+        .flatten()
+        .collect();
+
+    Some(RoleRightsDetails { role, set_for_new_objects, rights, templates })
+}
+
+// ─── Managed forms ─────────────────────────────────────────────────────────
+
+pub struct FormInfo {
+    pub id: i64,
+    /// Owning object: `Document.Заказ`, `CommonForm.Вопрос`.
+    pub owner: String,
+    pub name: String,
+    /// Form module, as stored in `symbols.file`.
+    pub module_file: String,
+}
+
+/// Forms of an object. `object` is `Type.Name` or a bare object name; `form` narrows to
+/// one form by exact name (case-insensitive).
+pub fn find_forms(db_path: &Path, object: &str, form: Option<&str>) -> Vec<FormInfo> {
+    let Ok(conn) = Connection::open(db_path) else { return Vec::new() };
+    let object_lower = object.trim().to_lowercase();
+    let owner_pattern = if object_lower.contains('.') {
+        object_lower
+    } else {
+        format!("%.{}", object_lower)
+    };
+    let form_lower = form.map(|f| f.trim().to_lowercase());
+    conn.prepare(
+        "SELECT id, owner, name, module_file FROM forms \
+         WHERE owner_lower LIKE ?1 AND (?2 IS NULL OR name_lower = ?2) \
+         ORDER BY owner, name",
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map(params![owner_pattern, form_lower], |row| {
+            Ok(FormInfo { id: row.get(0)?, owner: row.get(1)?, name: row.get(2)?, module_file: row.get(3)? })
+        })
+        .map(|rows| rows.flatten().collect())
+    })
+    .unwrap_or_default()
+}
+
+/// Items, attributes, commands and event bindings of a form found by `find_forms`.
+pub fn get_form_structure(db_path: &Path, form_id: i64) -> Option<FormStructure> {
+    let conn = Connection::open(db_path).ok()?;
+
+    let attributes = conn
+        .prepare("SELECT name, types, is_main FROM form_attributes WHERE form_id = ?1 ORDER BY rowid")
+        .ok()
+        .map(|mut stmt| {
+            stmt.query_map(params![form_id], |row| {
+                Ok(FormAttribute { name: row.get(0)?, types: row.get(1)?, is_main: row.get::<_, i32>(2)? != 0 })
+            })
+            .map(|rows| rows.flatten().collect())
+            .unwrap_or_default()
+        })
+        .unwrap_or_default();
+    let commands = conn
+        .prepare("SELECT name, action FROM form_commands WHERE form_id = ?1 ORDER BY rowid")
+        .ok()
+        .map(|mut stmt| {
+            stmt.query_map(params![form_id], |row| Ok(FormCommand { name: row.get(0)?, action: row.get(1)? }))
+                .map(|rows| rows.flatten().collect())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default();
+    let elements = conn
+        .prepare("SELECT name, kind, data_path, parent FROM form_elements WHERE form_id = ?1 ORDER BY position")
+        .ok()
+        .map(|mut stmt| {
+            stmt.query_map(params![form_id], |row| {
+                Ok(FormElement { name: row.get(0)?, kind: row.get(1)?, data_path: row.get(2)?, parent: row.get(3)? })
+            })
+            .map(|rows| rows.flatten().collect())
+            .unwrap_or_default()
+        })
+        .unwrap_or_default();
+    let events = conn
+        .prepare("SELECT element, event, handler FROM form_events WHERE form_id = ?1 ORDER BY rowid")
+        .ok()
+        .map(|mut stmt| {
+            stmt.query_map(params![form_id], |row| {
+                Ok(FormEvent { element: row.get(0)?, event: row.get(1)?, handler: row.get(2)? })
+            })
+            .map(|rows| rows.flatten().collect())
+            .unwrap_or_default()
+        })
+        .unwrap_or_default();
+
+    Some(FormStructure { attributes, commands, elements, events })
+}
+
+/// A form event or command bound to a procedure of the form module.
+pub struct FormBinding {
+    pub owner: String,
+    pub form: String,
+    /// Form item for item events; `None` for events of the form itself and for commands.
+    pub element: Option<String>,
+    /// Event name (`OnChange`), or the command name when `is_command`.
+    pub event: String,
+    pub is_command: bool,
+}
+
+/// Form events and commands handled by method `name_lower` of form module `file`.
+/// Such methods are called by the platform, not from code — they are entry points of the call graph.
+fn form_bindings(conn: &Connection, file: &str, name_lower: &str) -> Vec<FormBinding> {
+    conn.prepare(
+        "SELECT f.owner, f.name, e.element, e.event, 0 FROM form_events e JOIN forms f ON f.id = e.form_id \
+         WHERE f.module_file = ?1 AND e.handler_lower = ?2 \
+         UNION ALL \
+         SELECT f.owner, f.name, NULL, c.name, 1 FROM form_commands c JOIN forms f ON f.id = c.form_id \
+         WHERE f.module_file = ?1 AND c.action_lower = ?2",
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map(params![file, name_lower], |row| {
+            Ok(FormBinding {
+                owner: row.get(0)?,
+                form: row.get(1)?,
+                element: row.get(2)?,
+                event: row.get(3)?,
+                is_command: row.get::<_, i32>(4)? != 0,
+            })
+        })
+        .map(|rows| rows.flatten().collect())
     })
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Index in a shared-cache in-memory database: query functions open it by `path`, the
+    /// fixture's own connection keeps it alive and fills the tables. Also used by the tool tests.
+    pub(crate) struct TestIndex {
+        pub(crate) path: PathBuf,
+        pub(crate) conn: Connection,
+    }
+
+    impl TestIndex {
+        pub(crate) fn new() -> Self {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            let path = PathBuf::from(format!(
+                "file:index-test-{}?mode=memory&cache=shared",
+                NEXT.fetch_add(1, Ordering::Relaxed)
+            ));
+            let conn = init_db(&path).expect("in-memory index");
+            TestIndex { path, conn }
+        }
+
+        pub(crate) fn exec(&self, sql: &str) {
+            self.conn.execute_batch(sql).expect(sql);
+        }
+
+        /// Procedure `name` (not exported) at lines `line..line + 5` of `file`, with `file`
+        /// in the file catalog. Returns the symbol id.
+        pub(crate) fn method(&self, file: &str, name: &str, line: u32) -> i64 {
+            upsert_file_catalog(&self.conn, file, 0);
+            self.conn
+                .execute(
+                    "INSERT INTO symbols (name, name_lower, kind, file, start_line, end_line)
+                     VALUES (?1, ?2, 'procedure', ?3, ?4, ?4 + 5)",
+                    params![name, name.to_lowercase(), file, line],
+                )
+                .unwrap();
+            self.conn.last_insert_rowid()
+        }
+
+        pub(crate) fn export(&self, symbol_id: i64) {
+            self.exec(&format!("UPDATE symbols SET is_export = 1 WHERE id = {}", symbol_id));
+        }
+
+        /// Metadata object `obj_type.name` (`Catalog`, `Контрагенты`). Returns the object id.
+        pub(crate) fn object(&self, obj_type: &str, name: &str) -> i64 {
+            self.conn
+                .execute(
+                    "INSERT INTO objects (obj_type, name, name_lower) VALUES (?1, ?2, ?3)",
+                    params![obj_type, name, name.to_lowercase()],
+                )
+                .unwrap();
+            self.conn.last_insert_rowid()
+        }
+
+        /// Reference of attribute `item` of object `object_id` to `target` (`Catalog.Договоры`).
+        pub(crate) fn reference(&self, object_id: i64, item: &str, kind: &str, target: &str) {
+            self.conn
+                .execute(
+                    "INSERT INTO metadata_references (object_id, item, kind, target, target_lower) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![object_id, item, kind, target, target.to_lowercase()],
+                )
+                .unwrap();
+        }
+
+        /// Role `name` with its "rights for new objects" flag. Returns the role's object id.
+        pub(crate) fn role(&self, name: &str, set_for_new_objects: bool) -> i64 {
+            let id = self.object("Role", name);
+            self.conn
+                .execute(
+                    "INSERT INTO object_properties (object_id, name, value) VALUES (?1, 'SetForNewObjects', ?2)",
+                    params![id, set_for_new_objects.to_string()],
+                )
+                .unwrap();
+            id
+        }
+
+        /// Right `right` of role `role_id` on `object` (`Document.Заказ`) or its field `item`.
+        pub(crate) fn right(
+            &self,
+            role_id: i64,
+            object: &str,
+            item: Option<&str>,
+            right: &str,
+            granted: bool,
+            condition: Option<&str>,
+        ) {
+            self.conn
+                .execute(
+                    "INSERT INTO role_rights (role_id, object, object_lower, item, right_name, granted, condition) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![role_id, object, object.to_lowercase(), item, right, granted, condition],
+                )
+                .unwrap();
+        }
+
+        /// Query at `line` of `file` reading table `name` (`РегистрНакопления.Товары.Остатки`)
+        /// of `object` through `part`, with the fields it reads.
+        pub(crate) fn query(&self, file: &str, line: u32, name: &str, object: &str, part: Option<&str>, fields: &[&str]) {
+            self.conn
+                .execute(
+                    "INSERT INTO queries (file, start_line, end_line) VALUES (?1, ?2, ?2 + 3)",
+                    params![file, line],
+                )
+                .unwrap();
+            let fields = fields.join(", ");
+            self.conn
+                .execute(
+                    "INSERT INTO query_tables (query_id, file, name, object, object_lower, part, part_lower, fields, fields_lower)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        self.conn.last_insert_rowid(), file, name, object, object.to_lowercase(),
+                        part, part.map(str::to_lowercase), fields, fields.to_lowercase()
+                    ],
+                )
+                .unwrap();
+        }
+
+        /// Predefined item or enum value `name` of object `object_id`.
+        pub(crate) fn predefined(&self, object_id: i64, name: &str, code: Option<&str>, parent: Option<&str>) {
+            self.conn
+                .execute(
+                    "INSERT INTO predefined_values (object_id, name, name_lower, code, parent) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![object_id, name, name.to_lowercase(), code, parent],
+                )
+                .unwrap();
+        }
+
+        /// Form `name` of `owner` (`Document.Заказ`) parsed from `Form.xml` text. Returns the form id.
+        pub(crate) fn form(&self, owner: &str, name: &str, module_file: &str, xml: &str) -> i64 {
+            let form = crate::parser::form_xml::parse_form_xml(xml);
+            crate::metadata::insert_form(&self.conn, owner, name, module_file, &form).unwrap();
+            self.conn
+                .query_row("SELECT id FROM forms WHERE module_file = ?1", params![module_file], |r| r.get(0))
+                .unwrap()
+        }
+
+        /// Parameters of method `symbol_id` as `(name, default)`.
+        pub(crate) fn params(&self, symbol_id: i64, params: &[(&str, Option<&str>)]) {
+            for (position, (param, default)) in params.iter().enumerate() {
+                self.conn
+                    .execute(
+                        "INSERT INTO symbol_params (symbol_id, position, name, default_value) VALUES (?1, ?2, ?3, ?4)",
+                        params![symbol_id, position as i64, param, default],
+                    )
+                    .unwrap();
+            }
+        }
+
+        /// Call of `callee` of `callee_file` from method `caller` of `caller_file`, resolved the
+        /// way `resolve_calls` links it.
+        pub(crate) fn call(&self, caller_file: &str, caller: &str, callee_file: &str, callee: &str) {
+            self.conn
+                .execute(
+                    "INSERT INTO calls (caller_file, caller_name, caller_name_lower, callee_name, callee_name_lower, callee_file)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![caller_file, caller, caller.to_lowercase(), callee, callee.to_lowercase(), callee_file],
+                )
+                .unwrap();
+        }
+    }
+
+    const COMMON: &str = "CommonModules/Общий/Ext/Module.bsl";
+
+    #[test]
+    fn lookup_method_needs_exact_name() {
+        let index = TestIndex::new();
+        index.method(COMMON, "ЗаполнитьТаблицу", 1);
+        index.method(COMMON, "ЗаполнитьТаблицуЦен", 10);
+
+        let found = lookup_method(&index.conn, "Общий.ЗаполнитьТаблицу").unwrap();
+        assert_eq!((found.name.as_str(), found.start_line), ("ЗаполнитьТаблицу", 1));
+
+        match lookup_method(&index.conn, "Заполнить") {
+            Err(MethodLookupError::Ambiguous(similar)) => assert_eq!(similar.len(), 2),
+            other => panic!("expected ambiguous lookup, got {:?}", other),
+        }
+        assert!(matches!(lookup_method(&index.conn, "Провести"), Err(MethodLookupError::NotFound)));
+        assert!(matches!(get_call_tree(&index.path, "Заполнить", CallDirection::Callees, 2, 10), Err(MethodLookupError::Ambiguous(_))));
+    }
+
+    #[test]
+    fn symbol_doc_params_come_from_symbol_params() {
+        let index = TestIndex::new();
+        let split = index.method(COMMON, "Разделить", 1);
+        index.params(split, &[("Строка", None), ("Разделитель", Some("\",\""))]);
+
+        let doc = get_symbol_doc(&index.path, "Разделить").unwrap();
+        assert_eq!(doc.param_names, vec!["Строка", "Разделитель"]);
+        assert_eq!(doc.symbol.params, "Строка, Разделитель = \",\"");
+        assert!(doc.doc.is_none());
+    }
+
+    #[test]
+    fn module_outline_reads_regions_variables_and_methods() {
+        let index = TestIndex::new();
+        let top = index.method(COMMON, "Верхний", 2);
+        index.export(top);
+        index.method("CommonModules/Другой/Ext/Module.bsl", "Чужой", 1);
+        upsert_file_catalog(&index.conn, "CommonModules/Общий.xml", 0);
+        upsert_file_catalog(&index.conn, "CommonModules/Общий/Общий.mdo", 0);
+        let kinds: Vec<String> = index
+            .conn
+            .prepare("SELECT source_kind FROM indexed_files WHERE extension IN ('xml', 'mdo') ORDER BY extension")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(kinds, vec!["xml", "xml"], "metadata descriptions of both layouts");
+        index.exec(
+            "INSERT INTO module_regions (file, name, name_lower, start_line, end_line, depth) VALUES
+                 ('CommonModules/Общий/Ext/Module.bsl', 'Подраздел', 'подраздел', 5, 9, 1),
+                 ('CommonModules/Общий/Ext/Module.bsl', 'ПрограммныйИнтерфейс', 'программныйинтерфейс', 1, 10, 0);
+             INSERT INTO module_vars (file, name, name_lower, line, is_export, directive) VALUES
+                 ('CommonModules/Общий/Ext/Module.bsl', 'Кэш', 'кэш', 1, 1, 'НаСервере');",
+        );
+
+        let (outlines, total) = get_module_outline(&index.path, "CommonModules/Общий", 5);
+        assert_eq!(total, 1, "only BSL modules of the path");
+        let outline = &outlines[0];
+        assert_eq!(outline.file, COMMON);
+        let regions: Vec<_> = outline.regions.iter().map(|r| (r.name.as_str(), r.depth)).collect();
+        assert_eq!(regions, vec![("ПрограммныйИнтерфейс", 0), ("Подраздел", 1)]);
+        assert!(outline.variables[0].is_export);
+        assert_eq!(outline.variables[0].directive.as_deref(), Some("НаСервере"));
+        assert_eq!(outline.methods.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), vec!["Верхний"]);
+        assert!(outline.methods[0].is_export);
+
+        let (outlines, total) = get_module_outline(&index.path, "CommonModules/", 1);
+        assert_eq!((outlines.len(), total), (1, 2));
+    }
+
+    /// `Form.xml` of a document form: a group with one field, a form event and an item event.
+    pub(crate) const ORDER_FORM_XML: &str = r#"<Form xmlns="http://v8.1c.ru/8.3/xcf/logform">
+	<Events>
+		<Event name="OnOpen">ПриОткрытии</Event>
+	</Events>
+	<ChildItems>
+		<UsualGroup name="Шапка" id="1">
+			<ChildItems>
+				<InputField name="Контрагент" id="2">
+					<DataPath>Объект.Контрагент</DataPath>
+					<Events>
+						<Event name="OnChange">КонтрагентПриИзменении</Event>
+					</Events>
+				</InputField>
+			</ChildItems>
+		</UsualGroup>
+	</ChildItems>
+</Form>"#;
+
+    pub(crate) const ORDER_FORM_MODULE: &str = "Documents/Заказ/Forms/ФормаДокумента/Ext/Form/Module.bsl";
+
+    #[test]
+    fn forms_found_by_owner_and_read_back_in_item_order() {
+        let index = TestIndex::new();
+        let id = index.form("Document.Заказ", "ФормаДокумента", ORDER_FORM_MODULE, ORDER_FORM_XML);
+        index.form("Document.Заказ", "ФормаСписка", "Documents/Заказ/Forms/ФормаСписка/Ext/Form/Module.bsl", ORDER_FORM_XML);
+        index.form("Catalog.ЗаказНаряды", "ФормаЭлемента", "Catalogs/ЗаказНаряды/Forms/ФормаЭлемента/Ext/Form/Module.bsl", ORDER_FORM_XML);
+
+        assert_eq!(find_forms(&index.path, "Заказ", None).len(), 2, "a bare name matches the whole object name");
+        let forms = find_forms(&index.path, "Document.Заказ", Some("формадокумента"));
+        assert_eq!(forms.iter().map(|f| f.id).collect::<Vec<_>>(), vec![id]);
+        assert_eq!(forms[0].module_file, ORDER_FORM_MODULE);
+
+        let form = get_form_structure(&index.path, id).unwrap();
+        let items: Vec<_> = form.elements.iter().map(|e| (e.name.as_str(), e.parent.as_deref())).collect();
+        assert_eq!(items, vec![("Шапка", None), ("Контрагент", Some("Шапка"))]);
+        assert_eq!(form.elements[1].data_path.as_deref(), Some("Объект.Контрагент"));
+        let events: Vec<_> = form.events.iter().map(|e| (e.element.as_deref(), e.handler.as_str())).collect();
+        assert_eq!(events, vec![(None, "ПриОткрытии"), (Some("Контрагент"), "КонтрагентПриИзменении")]);
+    }
+
+    /// `Catalog.Контрагенты` referenced by a document (twice), a register and, through
+    /// `DefinedType.Плательщик`, by another document; it references `Catalog.Договоры` itself.
+    pub(crate) fn counterparty_references(index: &TestIndex) {
+        let counterparties = index.object("Catalog", "Контрагенты");
+        index.object("Catalog", "Договоры");
+        let sale = index.object("Document", "Реализация");
+        let settlements = index.object("AccumulationRegister", "Взаиморасчеты");
+        let payer = index.object("DefinedType", "Плательщик");
+        let payment = index.object("Document", "Оплата");
+        index.reference(counterparties, "ОсновнойДоговор", "Attribute", "Catalog.Договоры");
+        index.reference(sale, "Контрагент", "Attribute", "Catalog.Контрагенты");
+        index.reference(sale, "Товары.Получатель", "Attribute", "Catalog.Контрагенты");
+        index.reference(settlements, "Контрагент", "Dimension", "Catalog.Контрагенты");
+        index.reference(payer, "Type", "DefinedType", "Catalog.Контрагенты");
+        index.reference(payment, "Плательщик", "Attribute", "DefinedType.Плательщик");
+    }
+
+    #[test]
+    fn metadata_dependencies_follow_defined_types_one_level() {
+        let index = TestIndex::new();
+        counterparty_references(&index);
+
+        let deps = get_metadata_dependencies(&index.path, "Catalog.Контрагенты").unwrap();
+        assert_eq!(deps.object, "Catalog.Контрагенты");
+        assert_eq!(deps.uses.iter().map(|r| r.target.as_str()).collect::<Vec<_>>(), vec!["Catalog.Договоры"]);
+        let used_by: Vec<_> = deps
+            .used_by
+            .iter()
+            .map(|r| (r.object.as_str(), r.item.as_deref().unwrap_or(""), r.via.as_deref()))
+            .collect();
+        assert_eq!(
+            used_by,
+            vec![
+                ("AccumulationRegister.Взаиморасчеты", "Контрагент", None),
+                ("DefinedType.Плательщик", "Type", None),
+                ("Document.Оплата", "Плательщик", Some("DefinedType.Плательщик")),
+                ("Document.Реализация", "Контрагент", None),
+                ("Document.Реализация", "Товары.Получатель", None),
+            ]
+        );
+
+        let deps = get_metadata_dependencies(&index.path, "DefinedType.Плательщик").unwrap();
+        assert_eq!(deps.used_by.len(), 1, "a defined type's own users are not expanded again");
+    }
+
+    /// Accounts `Касса` (with subaccount `КассаОрганизации`) and `Товары` of `ChartOfAccounts.Хозрасчетный`.
+    pub(crate) fn chart_of_accounts(index: &TestIndex) {
+        let chart = index.object("ChartOfAccounts", "Хозрасчетный");
+        index.predefined(chart, "Касса", Some("50"), None);
+        index.predefined(chart, "КассаОрганизации", Some("50.01"), Some("Касса"));
+        index.predefined(chart, "Товары", Some("41"), None);
+    }
+
+    #[test]
+    fn predefined_values_of_exact_object_in_metadata_order() {
+        let index = TestIndex::new();
+        chart_of_accounts(&index);
+        index.object("ChartOfAccounts", "Хозрасчетный2");
+
+        let (object, values) = get_predefined_values(&index.path, "ChartOfAccounts", "хозрасчетный").unwrap();
+        assert_eq!(object, "ChartOfAccounts.Хозрасчетный");
+        let names: Vec<_> = values.iter().map(|v| (v.name.as_str(), v.parent.as_deref())).collect();
+        assert_eq!(names, vec![("Касса", None), ("КассаОрганизации", Some("Касса")), ("Товары", None)]);
+        assert!(get_predefined_values(&index.path, "ChartOfAccounts", "Хозрасчет").is_none());
+        assert!(get_predefined_values(&index.path, "ChartOfAccounts", "Хозрасчетный2").unwrap().1.is_empty());
+    }
+
+    #[test]
+    fn predefined_by_name_filters_object_before_limit() {
+        let index = TestIndex::new();
+        for i in 0..60 {
+            let catalog = index.object("Catalog", &format!("Справочник{:02}", i));
+            index.predefined(catalog, "Основной", None, None);
+        }
+        let wanted = index.object("Catalog", "Склады");
+        index.predefined(wanted, "Основной", None, None);
+
+        assert_eq!(find_predefined_by_name(&index.path, "основной", None).len(), 50);
+        let found = find_predefined_by_name(&index.path, "Основной", Some("склады"));
+        assert_eq!(found.iter().map(|p| p.object.as_str()).collect::<Vec<_>>(), vec!["Catalog.Склады"]);
+    }
+
+    /// Roles on `Document.Заказ`: `Менеджер` reads and updates under RLS, is denied posting and
+    /// viewing `Сумма`; `Наблюдатель` is only denied posting; `ПолныеПрава` reads and posts.
+    pub(crate) fn order_rights(index: &TestIndex) {
+        index.object("Document", "Заказ");
+        let rls = "#ПоЗначениям(\"Документ.Заказ\",\n  \"Организация\")";
+        let manager = index.role("Менеджер", false);
+        index.right(manager, "Document.Заказ", None, "Read", true, Some(rls));
+        index.right(manager, "Document.Заказ", None, "Update", true, Some(rls));
+        index.right(manager, "Document.Заказ", None, "Posting", false, None);
+        index.right(manager, "Document.Заказ", Some("Attribute.Сумма"), "View", false, None);
+        let observer = index.role("Наблюдатель", false);
+        index.right(observer, "Document.Заказ", None, "Posting", false, None);
+        let admin = index.role("ПолныеПрава", false);
+        index.right(admin, "Document.Заказ", None, "Read", true, None);
+        index.right(admin, "Document.Заказ", None, "Posting", true, None);
+    }
+
+    #[test]
+    fn object_rights_grouped_by_role_in_file_order() {
+        let index = TestIndex::new();
+        order_rights(&index);
+
+        let (object, rights) = get_object_rights(&index.path, "Заказ").unwrap();
+        assert_eq!(object, "Document.Заказ");
+        let rows: Vec<_> = rights.iter().map(|r| (r.role.as_str(), r.item.as_deref(), r.right.as_str())).collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Менеджер", None, "Read"),
+                ("Менеджер", None, "Update"),
+                ("Менеджер", None, "Posting"),
+                ("Менеджер", Some("Attribute.Сумма"), "View"),
+                ("Наблюдатель", None, "Posting"),
+                ("ПолныеПрава", None, "Read"),
+                ("ПолныеПрава", None, "Posting"),
+            ]
+        );
+        assert!(rights[0].condition.as_deref().is_some_and(|c| c.starts_with("#ПоЗначениям")));
+    }
+
+    #[test]
+    fn object_rights_need_exact_object_and_include_new_object_roles() {
+        let index = TestIndex::new();
+        order_rights(&index);
+        index.object("Document", "ЗаказПоставщику");
+        let all = index.role("Администратор", true);
+        index.right(all, "Document.ЗаказПоставщику", None, "Read", false, None);
+
+        let (_, rights) = get_object_rights(&index.path, "Document.Заказ").unwrap();
+        let inherited: Vec<_> = rights.iter().filter(|r| r.inherited).map(|r| r.role.as_str()).collect();
+        assert_eq!(inherited, vec!["Администратор"]);
+
+        let (_, rights) = get_object_rights(&index.path, "ЗаказПоставщику").unwrap();
+        assert!(rights.iter().all(|r| !r.inherited), "a role listing the object keeps its own rights");
+        assert!(get_object_rights(&index.path, "Заказы").is_none());
+        assert!(get_object_rights(&index.path, "Document.Зак").is_none());
+    }
+
+    #[test]
+    fn queries_filtered_by_part_and_field_before_limit() {
+        let index = TestIndex::new();
+        let register = "AccumulationRegister.ТоварыНаСкладах";
+        for line in 1..=3 {
+            index.query(COMMON, line * 10, "РегистрНакопления.ТоварыНаСкладах.Обороты", register, Some("Обороты"), &["Склад"]);
+        }
+        index.query(COMMON, 40, "РегистрНакопления.ТоварыНаСкладах.Остатки", register, Some("Остатки"), &["Номенклатура", "Склад"]);
+        index.query(COMMON, 50, "РегистрНакопления.ТоварыНаСкладах.Остатки", register, Some("Остатки"), &["НоменклатураГруппа"]);
+        index.query(COMMON, 60, "РегистрСведений.ТоварыНаСкладах", "InformationRegister.ТоварыНаСкладах", None, &[]);
+
+        assert_eq!(find_queries_using(&index.path, register, None, None, 100).1, 5);
+        assert_eq!(find_queries_using(&index.path, "ТоварыНаСкладах", None, None, 100).1, 6);
+
+        let (usages, total) = find_queries_using(&index.path, register, Some("остатки"), None, 1);
+        assert_eq!((usages.len(), total), (1, 2));
+        assert_eq!(usages[0].start_line, 40);
+
+        let (usages, total) = find_queries_using(&index.path, register, None, Some("номенклатура"), 10);
+        assert_eq!(total, 1, "whole field names only");
+        assert_eq!(usages[0].fields, vec!["Номенклатура", "Склад"]);
+    }
+
+    /// In-memory index of the current schema recorded as `version` (no `schema_version` for 0),
+    /// with one module symbol and its file, a code call, a metadata object and a platform edge.
+    fn index_at_version(version: i64) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO symbols (name, name_lower, kind, file, start_line, end_line)
+                 VALUES ('Провести', 'провести', 'procedure', 'CommonModules/Общий/Ext/Module.bsl', 1, 5);
+             INSERT INTO indexed_files (filepath, modified_at) VALUES ('CommonModules/Общий/Ext/Module.bsl', 1);
+             INSERT INTO objects (obj_type, name, name_lower) VALUES ('CommonModule', 'Общий', 'общий');
+             INSERT INTO calls (caller_file, caller_name, caller_name_lower, callee_name, callee_name_lower, edge_kind)
+                 VALUES ('CommonModules/Общий/Ext/Module.bsl', 'Провести', 'провести', 'Записать', 'записать', 'call'),
+                        ('EventSubscriptions/ПриЗаписи', 'ПриЗаписи', 'призаписи', 'Провести', 'провести', 'event_subscription');",
+        )
+        .unwrap();
+        if version > 0 {
+            conn.execute(
+                "INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', ?1)",
+                params![version.to_string()],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn rows(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0)).unwrap()
+    }
+
+    fn schema_version(conn: &Connection) -> Option<i64> {
+        stored_schema_version(conn).unwrap()
+    }
+
+    #[test]
+    fn index_without_schema_version_is_rebuilt() {
+        let conn = index_at_version(0);
+        assert_eq!(schema_version(&conn), Some(0));
+
+        migrate_schema(&conn).unwrap();
+        assert_eq!(schema_version(&conn), Some(SCHEMA_VERSION));
+        for table in ["symbols", "indexed_files", "objects", "calls"] {
+            assert_eq!(rows(&conn, table), 0, "{} dropped", table);
+        }
+    }
+
+    #[test]
+    fn index_of_newer_release_is_rebuilt() {
+        let conn = index_at_version(SCHEMA_VERSION + 1);
+
+        migrate_schema(&conn).unwrap();
+        assert_eq!(schema_version(&conn), Some(SCHEMA_VERSION));
+        assert_eq!(rows(&conn, "symbols"), 0);
+        assert_eq!(rows(&conn, "objects"), 0);
+    }
+
+    #[test]
+    fn failing_migration_step_falls_back_to_rebuild() {
+        // v4 adds columns to `query_tables`: without the table the step fails
+        let conn = index_at_version(3);
+        conn.execute_batch("DROP TABLE query_tables;").unwrap();
+
+        migrate_schema(&conn).unwrap();
+        assert_eq!(schema_version(&conn), Some(SCHEMA_VERSION));
+        assert_eq!(rows(&conn, "symbols"), 0);
+        assert_eq!(rows(&conn, "objects"), 0);
+        assert_eq!(rows(&conn, "query_tables"), 0, "created anew");
+    }
+
+    #[test]
+    fn clearing_parsed_modules_keeps_metadata() {
+        let conn = index_at_version(1);
+
+        migrate_schema(&conn).unwrap();
+        assert_eq!(schema_version(&conn), Some(SCHEMA_VERSION));
+        assert_eq!(rows(&conn, "symbols"), 0);
+        assert_eq!(rows(&conn, "indexed_files"), 0, "every module is parsed again");
+        assert_eq!(rows(&conn, "objects"), 1);
+        let edges: Vec<String> =
+            conn.prepare("SELECT edge_kind FROM calls").unwrap().query_map([], |r| r.get(0)).unwrap().flatten().collect();
+        assert_eq!(edges, vec!["event_subscription"], "platform edges come from metadata");
+    }
+
+    #[test]
+    fn query_table_filters_are_added_in_place() {
+        let conn = index_at_version(3);
+        conn.execute_batch(
+            "DROP TABLE query_tables;
+             CREATE TABLE query_tables (
+                 query_id INTEGER NOT NULL,
+                 file TEXT NOT NULL,
+                 name TEXT NOT NULL,
+                 object TEXT NOT NULL,
+                 object_lower TEXT NOT NULL,
+                 part TEXT,
+                 fields TEXT NOT NULL DEFAULT ''
+             );
+             INSERT INTO query_tables (query_id, file, name, object, object_lower, part, fields)
+                 VALUES (1, 'CommonModules/Общий/Ext/Module.bsl', 'Остатки', 'РегистрНакопления.Товары',
+                         'регистрнакопления.товары', 'Остатки', 'Номенклатура, Склад'),
+                        (2, 'CommonModules/Общий/Ext/Module.bsl', 'Товары', 'Справочник.Товары',
+                         'справочник.товары', NULL, '');",
+        )
+        .unwrap();
+
+        migrate_schema(&conn).unwrap();
+        assert_eq!(schema_version(&conn), Some(SCHEMA_VERSION));
+        assert_eq!(rows(&conn, "symbols"), 1, "modules are not parsed again");
+        let filters: Vec<(Option<String>, String)> = conn
+            .prepare("SELECT part_lower, fields_lower FROM query_tables ORDER BY query_id")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(
+            filters,
+            vec![(Some("остатки".to_string()), "номенклатура, склад".to_string()), (None, String::new())]
+        );
+    }
+
+    #[test]
+    fn exported_index_imports_with_its_fingerprint() {
+        let dir = std::env::temp_dir().join(format!("index-roundtrip-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let root = dir.join("config");
+        let modules = [
+            ("CommonModules/Общий/Ext/Module.bsl", "Процедура Провести() Экспорт\nКонецПроцедуры\n"),
+            ("CommonModules/Другой/Ext/Module.bsl", "Процедура Записать()\nКонецПроцедуры\n"),
+        ];
+        // Indexed on another machine: other mtimes, the same module texts
+        let source = dir.join("source.db");
+        let conn = init_db(&source).unwrap();
+        for (rel, text) in modules {
+            let path = root.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, text).unwrap();
+            conn.execute(
+                "INSERT INTO indexed_files (filepath, modified_at, content_hash) VALUES (?1, 1, ?2)",
+                params![rel, fnv_hash(text) as i64],
+            )
+            .unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO symbols (name, name_lower, kind, file, start_line, end_line, is_export)
+                 VALUES ('Провести', 'провести', 'procedure', 'CommonModules/Общий/Ext/Module.bsl', 1, 2, 1);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let archive = dir.join("index-archive.db");
+        let exported = export_index(&source, &archive).unwrap();
+        assert_eq!((exported.files, exported.symbols), (2, 1));
+
+        let target = dir.join("target.db");
+        init_db(&target).unwrap().execute_batch("INSERT INTO meta (key, value) VALUES ('metadata_version', '1');").unwrap();
+        let server = hold_index(&target).unwrap();
+        assert!(import_index(&archive, &target).is_err(), "refused while a server holds the index");
+        drop(server);
+
+        let imported = import_index(&archive, &target).unwrap();
+        assert_eq!(imported.fingerprint, exported.fingerprint);
+        assert_eq!(index_fingerprint(&target), Some(exported.fingerprint.clone()));
+        let metadata_version = Connection::open(&target)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM meta WHERE key = 'metadata_version'", [], |r| r.get::<_, i64>(0))
+            .unwrap();
+        assert_eq!(metadata_version, 0, "imported metadata is rebuilt");
+
+        fs::remove_file(root.join(modules[1].0)).unwrap();
+        let stats = sync_index(&root, &target).unwrap();
+        assert_eq!((stats.added, stats.updated, stats.removed, stats.unchanged), (0, 0, 1, 1));
+        assert_eq!(stats.total_symbols, 1, "unchanged modules are not parsed again");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    const ORDER: &str = "Documents/Заказ/Ext/ObjectModule.bsl";
+    const BANK: &str = "CommonModules/ОбменСБанком/Ext/Module.bsl";
+    const PAYMENTS: &str = "CommonModules/Платежи/Ext/Module.bsl";
+
+    fn names(nodes: &[CallNode]) -> Vec<&str> {
+        nodes.iter().map(|n| n.name.as_str()).collect()
+    }
+
+    #[test]
+    fn call_tree_marks_cycles_and_expands_each_method_once() {
+        let index = TestIndex::new();
+        index.method(ORDER, "ОбработкаПроведения", 10);
+        index.method(PAYMENTS, "ЗарегистрироватьОплату", 1);
+        index.method(BANK, "ОтправитьПлатежи", 40);
+        index.call(ORDER, "ОбработкаПроведения", PAYMENTS, "ЗарегистрироватьОплату");
+        index.call(ORDER, "ОбработкаПроведения", BANK, "ОтправитьПлатежи");
+        index.call(PAYMENTS, "ЗарегистрироватьОплату", BANK, "ОтправитьПлатежи");
+        index.call(BANK, "ОтправитьПлатежи", ORDER, "ОбработкаПроведения");
+
+        let tree = get_call_tree(&index.path, "ОбработкаПроведения", CallDirection::Callees, 3, 10).unwrap();
+        let children: Vec<&str> = tree.children.iter().map(|c| c.method.name.as_str()).collect();
+        assert_eq!(children, vec!["ОтправитьПлатежи", "ЗарегистрироватьОплату"], "callees by file");
+        let send = &tree.children[0];
+        assert_eq!(send.children.len(), 1);
+        assert!(send.children[0].cycle, "the call back to the root closes a cycle");
+        assert!(send.children[0].children.is_empty());
+        let register = &tree.children[1];
+        assert!(register.children[0].repeated, "ОтправитьПлатежи is already expanded");
+        assert!(register.children[0].children.is_empty());
+
+        let capped = get_call_tree(&index.path, "ОбработкаПроведения", CallDirection::Callees, 1, 1).unwrap();
+        assert_eq!((capped.children.len(), capped.omitted), (1, 1));
+        assert!(capped.children[0].children.is_empty(), "depth 1 lists direct callees only");
+
+        let callers = get_call_tree(&index.path, "ОтправитьПлатежи", CallDirection::Callers, 2, 10).unwrap();
+        let children: Vec<&str> = callers.children.iter().map(|c| c.method.name.as_str()).collect();
+        assert_eq!(children, vec!["ЗарегистрироватьОплату", "ОбработкаПроведения"], "callers by file");
+        assert!(callers.children[1].children[0].cycle);
+    }
+
+    #[test]
+    fn call_paths_return_every_shortest_chain() {
+        let index = TestIndex::new();
+        let target = "CommonModules/Цены/Ext/Module.bsl";
+        index.method(ORDER, "ОбработкаПроведения", 10);
+        index.method(PAYMENTS, "ЗарегистрироватьОплату", 1);
+        index.method(BANK, "ОтправитьПлатежи", 40);
+        index.method(COMMON, "Проверить", 1);
+        index.method(target, "ПересчитатьЦены", 1);
+        index.call(ORDER, "ОбработкаПроведения", PAYMENTS, "ЗарегистрироватьОплату");
+        index.call(ORDER, "ОбработкаПроведения", BANK, "ОтправитьПлатежи");
+        index.call(PAYMENTS, "ЗарегистрироватьОплату", target, "ПересчитатьЦены");
+        index.call(BANK, "ОтправитьПлатежи", target, "ПересчитатьЦены");
+        // A longer route and a cycle back to the start are not chains
+        index.call(ORDER, "ОбработкаПроведения", COMMON, "Проверить");
+        index.call(COMMON, "Проверить", BANK, "ОтправитьПлатежи");
+        index.call(PAYMENTS, "ЗарегистрироватьОплату", ORDER, "ОбработкаПроведения");
+
+        let found = find_call_paths(&index.path, "ОбработкаПроведения", "ПересчитатьЦены", 5, 10, 10).unwrap();
+        let paths: Vec<Vec<&str>> = found.paths.iter().map(|p| names(p)).collect();
+        assert_eq!(
+            paths,
+            vec![
+                vec!["ОбработкаПроведения", "ОтправитьПлатежи", "ПересчитатьЦены"],
+                vec!["ОбработкаПроведения", "ЗарегистрироватьОплату", "ПересчитатьЦены"],
+            ]
+        );
+        assert_eq!(found.explored, 5);
+        assert!(!found.capped);
+
+        let first = find_call_paths(&index.path, "ОбработкаПроведения", "ПересчитатьЦены", 5, 1, 10).unwrap();
+        assert_eq!(first.paths.len(), 1);
+        let too_short = find_call_paths(&index.path, "ОбработкаПроведения", "ПересчитатьЦены", 1, 10, 10).unwrap();
+        assert!(too_short.paths.is_empty());
+        assert!(find_call_paths(&index.path, "ПересчитатьЦены", "ОбработкаПроведения", 5, 10, 10).unwrap().paths.is_empty());
+    }
+
+    #[test]
+    fn call_paths_follow_only_first_callees_past_fan_out() {
+        let index = TestIndex::new();
+        let target = "CommonModules/Цены/Ext/Module.bsl";
+        index.method(ORDER, "ОбработкаПроведения", 10);
+        index.method(BANK, "ОтправитьПлатежи", 40);
+        index.method(COMMON, "Проверить", 1);
+        index.method(PAYMENTS, "ЗарегистрироватьОплату", 1);
+        index.method(target, "ПересчитатьЦены", 1);
+        index.call(ORDER, "ОбработкаПроведения", BANK, "ОтправитьПлатежи");
+        index.call(ORDER, "ОбработкаПроведения", COMMON, "Проверить");
+        index.call(ORDER, "ОбработкаПроведения", PAYMENTS, "ЗарегистрироватьОплату");
+        index.call(PAYMENTS, "ЗарегистрироватьОплату", target, "ПересчитатьЦены");
+
+        let capped = find_call_paths(&index.path, "ОбработкаПроведения", "ПересчитатьЦены", 5, 10, 2).unwrap();
+        assert!(capped.paths.is_empty(), "the third callee is not followed");
+        assert!(capped.capped);
+        assert_eq!(capped.explored, 3);
+
+        let found = find_call_paths(&index.path, "ОбработкаПроведения", "ПересчитатьЦены", 5, 10, 3).unwrap();
+        assert_eq!(names(&found.paths[0]), vec!["ОбработкаПроведения", "ЗарегистрироватьОплату", "ПересчитатьЦены"]);
+        assert!(!found.capped);
+    }
+
+    /// Names of the unused methods under `module_path`, by object.
+    fn unused(index: &TestIndex, module_path: &str) -> Vec<String> {
+        find_unused_symbols(&index.path, module_path, &SymbolFilter::default(), true, 100)
+            .unwrap()
+            .objects
+            .into_iter()
+            .flat_map(|(_, methods)| methods)
+            .map(|s| s.name)
+            .collect()
+    }
+
+    #[test]
+    fn unused_methods_count_resolved_calls_to_their_own_module() {
+        let index = TestIndex::new();
+        index.method(ORDER, "Пересчитать", 1);
+        index.method(ORDER, "Проверить", 10);
+        index.method(PAYMENTS, "Проверить", 1);
+        index.call(ORDER, "ОбработкаПроведения", ORDER, "Пересчитать");
+        index.call(PAYMENTS, "Провести", PAYMENTS, "Проверить");
+
+        assert_eq!(unused(&index, "Documents/Заказ"), vec!["Проверить"], "a call of the namesake in another module");
+    }
+
+    #[test]
+    fn unused_methods_count_unresolved_calls_by_name() {
+        let index = TestIndex::new();
+        index.method(ORDER, "Локальный", 1);
+        index.method(ORDER, "ЧерезЭтотОбъект", 10);
+        let send = index.method(PAYMENTS, "Отправить", 1);
+        index.export(send);
+        let idle = index.method(PAYMENTS, "НеВызывается", 10);
+        index.export(idle);
+        let unresolved = |caller_file: &str, callee: &str| {
+            index
+                .conn
+                .execute(
+                    "INSERT INTO calls (caller_file, caller_name, caller_name_lower, callee_name, callee_name_lower)
+                     VALUES (?1, 'Вызов', 'вызов', ?2, ?3)",
+                    params![caller_file, callee, callee.to_lowercase()],
+                )
+                .unwrap();
+        };
+        unresolved(PAYMENTS, "Локальный");
+        unresolved(ORDER, "ЧерезЭтотОбъект");
+        unresolved(BANK, "Отправить");
+
+        assert_eq!(unused(&index, ""), vec!["НеВызывается", "Локальный"]);
+    }
+
+    #[test]
+    fn unused_methods_count_names_in_string_literals() {
+        let index = TestIndex::new();
+        let after_choice = index.method(PAYMENTS, "ПослеВыбора", 1);
+        index.export(after_choice);
+        index.method(ORDER, "ОбработатьОтвет", 1);
+        index.method(ORDER, "ПослеЗакрытия", 10);
+        index.exec(
+            "INSERT INTO string_refs (file, name_lower) VALUES
+                 ('CommonModules/ОбменСБанком/Ext/Module.bsl', 'послевыбора'),
+                 ('CommonModules/Платежи/Ext/Module.bsl', 'обработатьответ'),
+                 ('Documents/Заказ/Ext/ObjectModule.bsl', 'послезакрытия');",
+        );
+
+        assert_eq!(unused(&index, ""), vec!["ОбработатьОтвет"]);
+    }
+
+    #[test]
+    fn unused_methods_skip_form_event_handlers() {
+        let index = TestIndex::new();
+        index.form("Document.Заказ", "ФормаДокумента", ORDER_FORM_MODULE, ORDER_FORM_XML);
+        index.method(ORDER_FORM_MODULE, "КонтрагентПриИзменении", 1);
+        index.method(ORDER_FORM_MODULE, "КонтрагентОчистка", 10);
+        index.method(ORDER, "КонтрагентПриИзменении", 1);
+
+        let found = find_unused_symbols(&index.path, "Documents/Заказ", &SymbolFilter::default(), true, 100).unwrap();
+        let methods: Vec<(&str, &str)> =
+            found.objects[0].1.iter().map(|s| (s.file.as_str(), s.name.as_str())).collect();
+        assert_eq!(
+            methods,
+            vec![(ORDER, "КонтрагентПриИзменении"), (ORDER_FORM_MODULE, "КонтрагентОчистка")],
+            "the handler is bound in the form's own module only"
+        );
+    }
+
+    #[test]
+    fn unused_methods_skip_form_command_actions() {
+        let index = TestIndex::new();
+        let form = index.form("Document.Заказ", "ФормаДокумента", ORDER_FORM_MODULE, ORDER_FORM_XML);
+        index.method(ORDER_FORM_MODULE, "ОтправитьКоманда", 1);
+        index.method(ORDER_FORM_MODULE, "ОтменитьКоманда", 10);
+        index
+            .conn
+            .execute(
+                "INSERT INTO form_commands (form_id, name, action, action_lower) VALUES (?1, 'Отправить', 'ОтправитьКоманда', 'отправитькоманда')",
+                params![form],
+            )
+            .unwrap();
+
+        assert_eq!(unused(&index, ORDER_FORM_MODULE), vec!["ОтменитьКоманда"]);
+    }
+
+    #[test]
+    fn unused_methods_skip_extension_interceptors() {
+        let index = TestIndex::new();
+        let interceptor = index.method(ORDER, "Расш_ОбработкаЗаполнения", 1);
+        index.method(ORDER, "Расш_Вспомогательный", 10);
+        index
+            .conn
+            .execute(
+                "INSERT INTO symbol_interceptions (symbol_id, kind, target, target_lower, base_file)
+                 VALUES (?1, 'Вместо', 'ОбработкаЗаполнения', 'обработказаполнения', ?2)",
+                params![interceptor, ORDER],
+            )
+            .unwrap();
+
+        assert_eq!(unused(&index, ""), vec!["Расш_Вспомогательный"]);
+    }
+
+    #[test]
+    fn unused_methods_skip_module_event_handlers_outside_common_modules() {
+        let index = TestIndex::new();
+        index.method(ORDER, "ПередЗаписью", 1);
+        index.method(ORDER, "OnWrite", 10);
+        index.method(PAYMENTS, "ПередЗаписью", 1);
+
+        assert_eq!(unused(&index, ""), vec!["ПередЗаписью"]);
+        assert_eq!(unused(&index, "Documents/Заказ"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn unused_methods_are_listed_by_whole_objects() {
+        let index = TestIndex::new();
+        index.method(PAYMENTS, "Первый", 1);
+        index.method(PAYMENTS, "Второй", 10);
+        index.method(ORDER, "Третий", 1);
+        index.method(ORDER, "Четвертый", 10);
+
+        let found = find_unused_symbols(&index.path, "", &SymbolFilter::default(), true, 3).unwrap();
+        assert_eq!((found.total, found.total_objects), (4, 2));
+        let listed: Vec<(&str, usize)> = found.objects.iter().map(|(o, m)| (o.as_str(), m.len())).collect();
+        assert_eq!(listed, vec![("CommonModule.Платежи", 2)], "the next object does not fit whole");
+
+        let found = find_unused_symbols(&index.path, "", &SymbolFilter::default(), true, 1).unwrap();
+        assert_eq!(found.objects[0].1.len(), 2, "the first object is listed whole");
+    }
+
+    #[test]
+    fn code_candidates_keep_a_module_or_directory_scope() {
+        let index = TestIndex::new();
+        for (file, text) in [
+            (COMMON, "Процедура Отправить() КонецПроцедуры"),
+            ("CommonModules/Общий2/Ext/Module.bsl", "Процедура Отправить() КонецПроцедуры"),
+            (ORDER, "Процедура Отправить() КонецПроцедуры"),
+        ] {
+            index.conn.execute("INSERT INTO code_files (file) VALUES (?1)", params![file]).unwrap();
+            index
+                .conn
+                .execute(
+                    "INSERT INTO code_trigrams (rowid, content) VALUES (?1, ?2)",
+                    params![index.conn.last_insert_rowid(), text],
+                )
+                .unwrap();
+        }
+        let literals = vec!["Отправить".to_string()];
+        let candidates = |scope: Option<&str>| code_candidate_files(&index.path, &literals, scope).unwrap();
+
+        assert_eq!(candidates(None).len(), 3);
+        assert_eq!(candidates(Some(COMMON)), vec![COMMON], "a single module");
+        assert_eq!(candidates(Some("CommonModules/Общий/")), vec![COMMON], "not the namesake prefix");
+        assert_eq!(candidates(Some("Documents")), vec![ORDER]);
+        assert!(candidates(Some("Documents/Заказ/Ext/ObjectModule")).is_empty());
+    }
+
+    #[test]
+    fn sync_counts_only_modules_it_could_read() {
+        let index = TestIndex::new();
+        index.method(COMMON, "Провести", 1);
+        let root = std::env::temp_dir().join(format!("index-sync-unreadable-{}", std::process::id()));
+        let missing = "CommonModules/Недоступный/Ext/Module.bsl";
+        let sync = |deleted: Vec<String>| {
+            let conn = Connection::open(&index.path).unwrap();
+            let to_check = vec![(missing.to_string(), 1, root.join(missing))];
+            apply_sync(conn, &root, &index.path, &std::collections::HashMap::new(), deleted, to_check).unwrap()
+        };
+
+        let stats = sync(vec![]);
+        assert_eq!((stats.added, stats.updated, stats.removed), (0, 0, 0), "nothing was read");
+
+        let stats = sync(vec![COMMON.to_string()]);
+        assert_eq!((stats.added, stats.updated, stats.removed), (0, 0, 1));
+        assert_eq!(stats.total_symbols, 0);
+    }
 }
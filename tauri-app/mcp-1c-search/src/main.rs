@@ -9,6 +9,7 @@ mod parser;
 mod index;
 mod metadata;
 mod semantic;
+mod watcher;
 
 /// Returns SQLite DB file size in MB (0.0 if not found).
 pub fn db_size_mb(path: &std::path::Path) -> f64 {
@@ -38,13 +39,57 @@ fn emit_search_status_json(
     );
 }
 
+/// `export-index <архив>`: write the index of `ONEC_CONFIG_PATH` to a portable archive.
+/// `import-index <архив>`: install an archive as the local index of `ONEC_CONFIG_PATH`, then
+/// re-index the modules that differ from the exported dump and rebuild the metadata.
+/// Returns the process exit code.
+fn run_index_command(command: &str, args: &[String], config_path: Option<&std::path::Path>) -> i32 {
+    let (Some(archive), Some(root)) = (args.first().map(PathBuf::from), config_path) else {
+        eprintln!("Использование: ONEC_CONFIG_PATH=<путь к конфигурации> mcp-1c-search {} <архив>", command);
+        return 2;
+    };
+    let db_path = index::get_db_path(root);
+    let result = if command == "export-index" {
+        index::export_index(&db_path, &archive).map(|a| {
+            println!(
+                "Индекс выгружен в {}: {} файлов, {} символов, отпечаток выгрузки {}",
+                archive.display(), a.files, a.symbols, a.fingerprint
+            );
+        })
+    } else {
+        index::import_index(&archive, &db_path).and_then(|a| {
+            println!("Индекс загружен: {} файлов, {} символов", a.files, a.symbols);
+            let stats = index::sync_index(root, &db_path)?;
+            if index::index_fingerprint(&db_path).as_deref() == Some(a.fingerprint.as_str()) {
+                println!("Модули совпадают с выгруженными (отпечаток {})", a.fingerprint);
+            } else {
+                println!(
+                    "Переиндексированы отличающиеся модули: +{} ~{} -{}, без изменений {}",
+                    stats.added, stats.updated, stats.removed, stats.unchanged
+                );
+            }
+            let objects = metadata::build_metadata(root, &db_path)?;
+            println!("Метаданные перестроены: {} объектов", objects);
+            Ok(())
+        })
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let config_path_str = std::env::var("ONEC_CONFIG_PATH").unwrap_or_default();
     let config_path: Option<PathBuf> = if !config_path_str.is_empty() {
         let p = PathBuf::from(&config_path_str);
         if p.exists() && p.is_dir() {
-            Some(p)
+            // A 1C:EDT project is indexed from its `src` folder
+            Some(index::source_root(&p))
         } else {
             None
         }
@@ -52,9 +97,41 @@ async fn main() {
         None
     };
 
+    // Configuration extensions (CFE) indexed together with the base configuration:
+    // ONEC_EXTENSION_PATHS is a list of dumped extension directories (`;` on Windows, `:` elsewhere)
+    if config_path.is_some() {
+        let mut roots: Vec<index::ExtensionRoot> = Vec::new();
+        for path in std::env::split_paths(&std::env::var_os("ONEC_EXTENSION_PATHS").unwrap_or_default()) {
+            if path.as_os_str().is_empty() {
+                continue;
+            }
+            if !path.is_dir() {
+                eprintln!("[1c-search] Extension directory not found: {}", path.display());
+                continue;
+            }
+            let path = index::source_root(&path);
+            let name = index::extension_name(&path);
+            if roots.iter().any(|r| r.name == name) {
+                eprintln!("[1c-search] Duplicate extension name '{}', skipping {}", name, path.display());
+                continue;
+            }
+            eprintln!("[1c-search] Extension '{}': {}", name, path.display());
+            roots.push(index::ExtensionRoot { name, path });
+        }
+        index::set_extension_roots(roots);
+    }
+
     // Derive db_path for symbol index (always Some when config_path is Some)
     let db_path: Option<PathBuf> = config_path.as_ref().map(|p| index::get_db_path(p));
 
+    // Index sharing: `mcp-1c-search export-index <архив>` / `import-index <архив>`
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(command @ ("export-index" | "import-index")) = args.first().map(String::as_str) {
+        std::process::exit(run_index_command(command, &args[1..], config_path.as_deref()));
+    }
+    // Held until exit: `import-index` refuses to replace the index under a running server
+    let _index_lock = db_path.as_deref().and_then(index::hold_index);
+
     // Report status via stderr — parsed by mcp_client.rs
     // IMPORTANT: Do NOT call count_files_and_size() here synchronously.
     // On large configs (5GB+, 100k+ files) it blocks the async main for 30+ seconds,
@@ -91,7 +168,7 @@ async fn main() {
         // Detached background task — JoinHandle intentionally dropped
         let _ = tokio::task::spawn_blocking(move || {
             emit_search_status_json("schema_init", 1, "Инициализация схемы индекса...", 0, 0.0, 0);
-            // Ensure DB schema exists before anything else
+            // Create the DB schema, or migrate an index of an earlier release, before anything else
             if let Err(e) = index::ensure_schema(&db_for_index) {
                 eprintln!("[1c-search] Schema init failed: {}", e);
                 emit_search_status_json(
@@ -105,18 +182,12 @@ async fn main() {
                 return;
             }
 
-            // Migrate: if calls table is empty but symbols exist, reset indexed_files
-            // so the next sync will re-parse all files and populate the call graph.
-            index::migrate_if_needed(&db_for_index);
-
-            // Migrate: if symbol_terms FTS is empty but symbols exist → rebuild FTS.
-            // Happens when upgrading from a version without semantic search.
-            index::migrate_semantic_fts_if_needed(&db_for_index);
-
             // Build metadata if missing, or if objects exist but have no attributes
-            // (happens when ConfigDumpInfo.xml was absent on first run — per-object XMLs will be parsed now)
+            // (happens when ConfigDumpInfo.xml was absent on first run — per-object XMLs will be parsed now),
+            // or if it was built by an older version that did not extract forms etc.
             let needs_metadata = !index::metadata_exists(&db_for_index)
-                || (index::metadata_exists(&db_for_index) && !index::metadata_has_items(&db_for_index));
+                || (index::metadata_exists(&db_for_index) && !index::metadata_has_items(&db_for_index))
+                || !metadata::metadata_is_current(&db_for_index);
             if needs_metadata {
                 emit_search_status_json(
                     "metadata_indexing",
@@ -133,7 +204,7 @@ async fn main() {
             }
 
             if index::index_exists(&db_for_index) {
-                // ─── Incremental sync (mtime-based) ─────────────────────────
+                // ─── Incremental sync (mtime + content hash) ────────────────
                 eprintln!("[1c-search] Index found — running incremental sync...");
                 emit_search_status_json(
                     "syncing_index",
@@ -148,8 +219,8 @@ async fn main() {
                         let size = db_size_mb(&db_for_index);
                         let built_at = index::get_built_at(&db_for_index).unwrap_or(0);
                         eprintln!(
-                            "[1c-search] Sync done: +{} ~{} -{} total={}",
-                            stats.added, stats.updated, stats.removed, stats.total_symbols
+                            "[1c-search] Sync done: +{} ~{} -{} ={} total={}",
+                            stats.added, stats.updated, stats.removed, stats.unchanged, stats.total_symbols
                         );
                         eprintln!(
                             "SEARCH_STATUS:ready:{}:{:.2}:{}",
@@ -191,7 +262,10 @@ async fn main() {
                 // ─── Full build ─────────────────────────────────────────────
                 eprintln!("[1c-search] No index found — starting full build...");
                 emit_search_status_json("building_index", 0, "Первичная индексация...", 0, 0.0, 0);
-                match index::build_index(&root, &db_for_index) {
+                let on_progress = |percent: u32, message: &str, symbols: usize| {
+                    emit_search_status_json("indexing", percent, message, symbols, 0.0, 0);
+                };
+                match index::build_index(&root, &db_for_index, &on_progress) {
                     Ok(sym_count) => {
                         let size = db_size_mb(&db_for_index);
                         let built_at = index::get_built_at(&db_for_index).unwrap_or(0);
@@ -207,6 +281,15 @@ async fn main() {
                     }
                 }
             }
+
+            // ─── Live updates ───────────────────────────────────────────────
+            // Plain thread, not spawn_blocking: the runtime waits for blocking tasks on shutdown,
+            // and the watcher never returns.
+            std::thread::spawn(move || {
+                if let Err(e) = watcher::watch(&root, &db_for_index) {
+                    eprintln!("[1c-search] File watcher stopped: {}", e);
+                }
+            });
         });
     }
 
@@ -1,7 +1,21 @@
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use regex::Regex;
 use rusqlite::{params, Connection};
 
+use crate::index::SourceLayout;
+use crate::parser::form_xml::{self, FormStructure};
+use crate::parser::metadata_xml::{self, MdAttribute, MdObject};
+use crate::parser::rights_xml;
+
+/// Bump when `build_metadata` starts extracting new data, so existing indexes rebuild it.
+/// 1 — managed forms, 2 — attribute types and properties from per-object XML,
+/// 3 — register dimensions/resources, register properties and recorders,
+/// 4 — metadata reference graph, 5 — enum values and predefined items, 6 — subsystem tree,
+/// 7 — role rights and restriction templates, 8 — entry point call edges,
+/// 9 — common module flags, 10 — 1C:EDT projects, 11 — HTTP and web service handlers,
+/// 12 — 1C:EDT predefined items and common module flags that are off.
+const METADATA_VERSION: i64 = 12;
+
 /// Known top-level 1C object types that appear in Configuration.xml ChildObjects.
 const OBJECT_TYPES: &[&str] = &[
     "Catalog", "Document", "CommonModule", "InformationRegister",
@@ -18,11 +32,24 @@ const OBJECT_TYPES: &[&str] = &[
     "CommonForm", "CommonPicture", "CommonTemplate", "StyleItem",
 ];
 
-/// Build the metadata graph (objects + object_items tables).
+/// Build the metadata graph (objects + object_items tables, managed forms).
 ///
-/// Sources (tried in order):
+/// Sources (tried in order), named as in a Designer dump; a 1C:EDT project (see `SourceLayout`)
+/// has the same ones as `.mdo` files beside the object's folder contents:
 /// 1. `Configuration.xml` — always present; provides object type + name list
-/// 2. `ConfigDumpInfo.xml` — optional; provides attributes, tabular sections, forms, modules
+/// 2. Per-object XML (`Catalogs/Валюты.xml`) — attributes and tabular sections with types,
+///    synonyms and flags, forms, commands; modules from the object's `Ext` folder;
+///    references to other objects named by those types (`metadata_references`); enum values
+///    and `Ext/Predefined.xml` items of catalogs and charts (`predefined_values`; EDT: the
+///    `<predefined>` section of the `.mdo`); common module flags (`objects` columns)
+/// 3. `ConfigDumpInfo.xml` — names only, for objects whose XML is missing from the dump
+/// 4. `Forms/*/Ext/Form.xml` of every object and common form — items, attributes, commands, handlers
+///    (EDT: `Forms/*/Form.form`)
+/// 5. `Subsystems/*.xml`, recursively — subsystem tree and the objects each subsystem includes
+/// 6. `Roles/*/Ext/Rights.xml` — per-object rights of every role, RLS conditions and templates
+///    (EDT: `Roles/*/Rights.rights`)
+/// 7. Event subscription and scheduled job handlers, common command modules — `calls` edges
+///    from the metadata object to the method the platform invokes
 ///
 /// Returns the number of top-level objects indexed.
 pub fn build_metadata(root: &Path, db_path: &Path) -> Result<usize, String> {
@@ -31,72 +58,230 @@ pub fn build_metadata(root: &Path, db_path: &Path) -> Result<usize, String> {
 
     // Clear existing metadata
     conn.execute("DELETE FROM object_items", []).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM object_properties", []).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM metadata_references", []).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM predefined_values", []).map_err(|e| e.to_string())?;
+    conn.execute_batch("DELETE FROM subsystem_content; DELETE FROM subsystems;")
+        .map_err(|e| e.to_string())?;
+    conn.execute_batch("DELETE FROM role_rights; DELETE FROM role_templates;")
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM calls WHERE edge_kind <> 'call'", []).map_err(|e| e.to_string())?;
     conn.execute("DELETE FROM objects", []).map_err(|e| e.to_string())?;
+    conn.execute_batch(
+        "DELETE FROM form_attributes; DELETE FROM form_commands;
+         DELETE FROM form_elements; DELETE FROM form_events; DELETE FROM forms;",
+    )
+    .map_err(|e| e.to_string())?;
 
-    let mut object_ids: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
+    let mut object_ids: HashMap<String, i64> = HashMap::new();
+    let layout = SourceLayout::detect(root);
 
     // Step 1: Parse Configuration.xml for the object list
-    let config_xml = root.join("Configuration.xml");
+    let config_xml = match layout {
+        SourceLayout::Designer => root.join("Configuration.xml"),
+        SourceLayout::Edt => root.join("Configuration").join("Configuration.mdo"),
+    };
     if config_xml.exists() {
-        parse_configuration_xml(&config_xml, &conn, &mut object_ids)
+        parse_configuration_xml(&config_xml, layout, &conn, &mut object_ids)
             .unwrap_or_else(|e| eprintln!("[1c-search] Configuration.xml: {}", e));
     }
 
-    // Step 2: Parse ConfigDumpInfo.xml for detailed structure (optional)
+    // Step 2: Per-object XML files
+    let mut parsed: HashSet<i64> = HashSet::new();
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    for (key, &obj_id) in &object_ids {
+        let Some((obj_type, obj_name)) = key.split_once('.') else { continue };
+        let Some(folder) = obj_type_to_folder(obj_type) else { continue };
+        let xml_path = root.join(layout.object_file(folder, obj_name));
+        if !xml_path.exists() {
+            continue;
+        }
+        match parse_object_xml(&xml_path, layout, &conn, obj_type, obj_id) {
+            Ok(()) => {
+                let ext_dir = root.join(layout.module_dir(folder, obj_name));
+                insert_module_items(&ext_dir, &conn, obj_id);
+                let predefined_path = match layout {
+                    SourceLayout::Designer => ext_dir.join("Predefined.xml"),
+                    SourceLayout::Edt => xml_path.clone(),
+                };
+                insert_predefined_items(&predefined_path, layout, &conn, obj_id);
+                parsed.insert(obj_id);
+            }
+            Err(e) => eprintln!("[1c-search] {}.xml: {}", obj_name, e),
+        }
+    }
+    conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+
+    // Step 3: ConfigDumpInfo.xml for objects without their own XML (optional)
     let config_dump = root.join("ConfigDumpInfo.xml");
-    if config_dump.exists() && !object_ids.is_empty() {
-        parse_config_dump_info(&config_dump, &conn, &object_ids)
+    if config_dump.exists() && parsed.len() < object_ids.len() {
+        parse_config_dump_info(&config_dump, &conn, &object_ids, &parsed)
             .unwrap_or_else(|e| eprintln!("[1c-search] ConfigDumpInfo.xml: {}", e));
-    } else if !config_dump.exists() && !object_ids.is_empty() {
-        // Step 3: No ConfigDumpInfo.xml — parse per-object XML files for attributes/tabular sections
-        for (key, &obj_id) in &object_ids {
-            let parts: Vec<&str> = key.splitn(2, '.').collect();
-            if parts.len() != 2 { continue; }
-            let obj_type = parts[0];
-            let obj_name = parts[1];
-            if let Some(folder) = obj_type_to_folder(obj_type) {
-                let xml_path = root.join(folder).join(format!("{}.xml", obj_name));
-                if xml_path.exists() {
-                    parse_object_xml(&xml_path, &conn, obj_id)
-                        .unwrap_or_else(|e| eprintln!("[1c-search] {}.xml: {}", obj_name, e));
-                }
-            }
+    }
+
+    // Step 4: Managed forms
+    let form_count = index_forms(root, layout, &conn, &object_ids)?;
+    eprintln!("[1c-search] Forms indexed: {}", form_count);
+
+    // Step 5: Subsystem tree
+    let mut top_subsystems: Vec<&str> = object_ids
+        .keys()
+        .filter_map(|key| key.strip_prefix("Subsystem."))
+        .collect();
+    top_subsystems.sort_unstable();
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let subsystem_count: usize = top_subsystems
+        .iter()
+        .map(|name| index_subsystem(root, layout, "Subsystems", name, &conn, None))
+        .sum();
+    conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+    eprintln!("[1c-search] Subsystems indexed: {}", subsystem_count);
+
+    // Step 6: Role rights
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let mut role_count = 0;
+    for (key, &role_id) in &object_ids {
+        let Some(role) = key.strip_prefix("Role.") else { continue };
+        let rights_xml = root.join(layout.rights_file(role));
+        if insert_role_rights(&rights_xml, &conn, role_id) {
+            role_count += 1;
         }
     }
+    conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+    eprintln!("[1c-search] Roles indexed: {}", role_count);
+
+    // Step 7: Entry points invoked by the platform
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let entry_points = insert_entry_point_calls(root, layout, &conn);
+    conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+    eprintln!("[1c-search] Entry point calls: {}", entry_points);
+
+    let _ = conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('metadata_version', ?1)",
+        params![METADATA_VERSION.to_string()],
+    );
 
     Ok(object_ids.len())
 }
 
-/// Parse `<ChildObjects>` section in Configuration.xml.
+/// Whether the metadata was built by the current `build_metadata` (see `METADATA_VERSION`).
+pub fn metadata_is_current(db_path: &Path) -> bool {
+    Connection::open(db_path)
+        .ok()
+        .and_then(|conn| {
+            conn.query_row("SELECT value FROM meta WHERE key='metadata_version'", [], |r| r.get::<_, String>(0))
+                .ok()
+        })
+        .and_then(|v| v.parse::<i64>().ok())
+        .is_some_and(|v| v >= METADATA_VERSION)
+}
+
+/// Parse `Form.xml` of every form of every known object into the `forms` / `form_*` tables.
+/// Returns the number of forms indexed.
+fn index_forms(
+    root: &Path,
+    layout: SourceLayout,
+    conn: &Connection,
+    object_ids: &HashMap<String, i64>,
+) -> Result<usize, String> {
+    // (owner "Type.Name", form name, form folder relative to root)
+    let mut found: Vec<(String, String, String)> = Vec::new();
+    for key in object_ids.keys() {
+        let Some((obj_type, obj_name)) = key.split_once('.') else { continue };
+        let Some(folder) = obj_type_to_folder(obj_type) else { continue };
+        if obj_type == "CommonForm" {
+            found.push((key.clone(), obj_name.to_string(), format!("{}/{}", folder, obj_name)));
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(root.join(folder).join(obj_name).join("Forms")) else { continue };
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                let form_name = entry.file_name().to_string_lossy().to_string();
+                let form_dir = format!("{}/{}/Forms/{}", folder, obj_name, form_name);
+                found.push((key.clone(), form_name, form_dir));
+            }
+        }
+    }
+    found.sort();
+
+    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
+    let mut count = 0;
+    for (owner, form_name, form_dir) in &found {
+        let (form_file, module_file) = layout.form_files(form_dir);
+        let Ok(content) = crate::index::read_file_to_string_lossy(&root.join(form_file)) else { continue };
+        let form = match layout {
+            SourceLayout::Designer => form_xml::parse_form_xml(&content),
+            SourceLayout::Edt => form_xml::parse_edt_form(&content),
+        };
+        if insert_form(conn, owner, form_name, &module_file, &form).is_ok() {
+            count += 1;
+        }
+    }
+    conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+pub(crate) fn insert_form(
+    conn: &Connection,
+    owner: &str,
+    form_name: &str,
+    module_file: &str,
+    form: &FormStructure,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO forms (owner, owner_lower, name, name_lower, module_file) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![owner, owner.to_lowercase(), form_name, form_name.to_lowercase(), module_file],
+    )?;
+    let form_id = conn.last_insert_rowid();
+    for attr in &form.attributes {
+        conn.execute(
+            "INSERT INTO form_attributes (form_id, name, types, is_main) VALUES (?1, ?2, ?3, ?4)",
+            params![form_id, attr.name, attr.types, attr.is_main as i32],
+        )?;
+    }
+    for cmd in &form.commands {
+        conn.execute(
+            "INSERT INTO form_commands (form_id, name, action, action_lower) VALUES (?1, ?2, ?3, ?4)",
+            params![form_id, cmd.name, cmd.action, cmd.action.as_ref().map(|a| a.to_lowercase())],
+        )?;
+    }
+    for (position, el) in form.elements.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO form_elements (form_id, position, name, kind, data_path, parent) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![form_id, position as i64, el.name, el.kind, el.data_path, el.parent],
+        )?;
+    }
+    for ev in &form.events {
+        conn.execute(
+            "INSERT INTO form_events (form_id, element, event, handler, handler_lower) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![form_id, ev.element, ev.event, ev.handler, ev.handler.to_lowercase()],
+        )?;
+    }
+    Ok(())
+}
+
+/// Parse `<ChildObjects>` section in Configuration.xml (the object list of `Configuration.mdo` in EDT).
 /// Populates the `objects` table and fills `object_ids` map ("Type.Name" → rowid).
 fn parse_configuration_xml(
     path: &Path,
+    layout: SourceLayout,
     conn: &Connection,
-    object_ids: &mut std::collections::HashMap<String, i64>,
+    object_ids: &mut HashMap<String, i64>,
 ) -> Result<(), String> {
     let content = crate::index::read_file_to_string_lossy(path)
         .map_err(|e| format!("Чтение Configuration.xml: {}", e))?;
-
-    // Find ChildObjects section
-    let child_start = match content.find("<ChildObjects>") {
-        Some(pos) => pos,
-        None => return Ok(()), // No ChildObjects — possibly a root Configuration.xml without objects
+    let children = match layout {
+        SourceLayout::Designer => metadata_xml::parse_configuration_children(&content),
+        SourceLayout::Edt => metadata_xml::parse_edt_configuration(&content),
     };
-    let child_end = content.find("</ChildObjects>").unwrap_or(content.len());
-    let section = &content[child_start..child_end];
-
-    // Match: <ObjectType>ObjectName</ObjectType>
-    // Must match only known types to avoid <Name>, <Version>, etc.
-    let types_pattern = OBJECT_TYPES.join("|");
-    let pattern = format!(r"<({})>([^<\n]+)</(?:{})>", types_pattern, types_pattern);
-    let re = Regex::new(&pattern).map_err(|e| e.to_string())?;
 
     conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
 
-    for cap in re.captures_iter(section) {
-        let obj_type = cap.get(1).unwrap().as_str();
-        let obj_name = cap.get(2).unwrap().as_str().trim();
-        if obj_name.is_empty() {
+    // Only known types: ChildObjects also lists languages, styles, etc. we do not model
+    for (obj_type, obj_name) in children {
+        if !OBJECT_TYPES.contains(&obj_type.as_str()) {
             continue;
         }
         if conn
@@ -138,31 +323,198 @@ fn obj_type_to_folder(obj_type: &str) -> Option<&'static str> {
         "DocumentJournal"            => Some("DocumentJournals"),
         "FilterCriterion"            => Some("FilterCriteria"),
         "ScheduledJob"               => Some("ScheduledJobs"),
+        "EventSubscription"          => Some("EventSubscriptions"),
+        "WebService"                 => Some("WebServices"),
+        "HTTPService"                => Some("HTTPServices"),
         "CommonForm"                 => Some("CommonForms"),
         "CommonAttribute"            => Some("CommonAttributes"),
         "CommonCommand"              => Some("CommonCommands"),
         "Role"                       => Some("Roles"),
+        "DefinedType"                => Some("DefinedTypes"),
+        "SessionParameter"           => Some("SessionParameters"),
         _ => None,
     }
 }
 
+/// Index `<dir>/<name>.xml` and, recursively, its nested subsystems from `<dir>/<name>/Subsystems/`.
+/// `dir` is relative to `root`; `parent` is the id and path of the enclosing subsystem.
+/// Returns the number of subsystems indexed.
+fn index_subsystem(
+    root: &Path,
+    layout: SourceLayout,
+    dir: &str,
+    name: &str,
+    conn: &Connection,
+    parent: Option<(i64, &str)>,
+) -> usize {
+    let Ok(content) = crate::index::read_file_to_string_lossy(&root.join(layout.object_file(dir, name))) else {
+        return 0;
+    };
+    let object = parse_description(&content, layout);
+    let path = match parent {
+        Some((_, parent_path)) => format!("{}/{}", parent_path, name),
+        None => name.to_string(),
+    };
+    if conn
+        .execute(
+            "INSERT INTO subsystems (parent_id, name, name_lower, path, path_lower, synonym) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                parent.map(|(id, _)| id), name, name.to_lowercase(),
+                path, path.to_lowercase(), non_empty(&object.synonym)
+            ],
+        )
+        .is_err()
+    {
+        return 0;
+    }
+    let id = conn.last_insert_rowid();
+    for item in &object.content {
+        // Content may also list nested objects by uuid in older dumps: keep `Type.Name` refs only
+        let Some((obj_type, obj_name)) = item.split_once('.') else { continue };
+        let _ = conn.execute(
+            "INSERT INTO subsystem_content (subsystem_id, obj_type, obj_name) VALUES (?1, ?2, ?3)",
+            params![id, obj_type, obj_name],
+        );
+    }
+    let child_dir = format!("{}/{}/Subsystems", dir, name);
+    1 + object
+        .subsystems
+        .iter()
+        .map(|child| index_subsystem(root, layout, &child_dir, child, conn, Some((id, &path))))
+        .sum::<usize>()
+}
+
+/// Rights of one role: a `role_rights` row per right on an object or one of its fields
+/// (`item` = `Attribute.Сумма`), restriction templates, and the "rights for new objects" flag
+/// as an object property. Returns false when the role has no `Rights.xml`.
+fn insert_role_rights(path: &Path, conn: &Connection, role_id: i64) -> bool {
+    let Ok(content) = crate::index::read_file_to_string_lossy(path) else { return false };
+    let rights = rights_xml::parse_rights_xml(&content);
+    let _ = conn.execute(
+        "INSERT INTO object_properties (object_id, name, value) VALUES (?1, 'SetForNewObjects', ?2)",
+        params![role_id, rights.set_for_new_objects.to_string()],
+    );
+    for object in &rights.objects {
+        // `Document.Заказ.TabularSection.Товары.Attribute.Цена` → (`Document.Заказ`, `TabularSection.Товары...`)
+        let (name, item) = match object.object.match_indices('.').nth(1) {
+            Some((pos, _)) => (&object.object[..pos], Some(&object.object[pos + 1..])),
+            None => (object.object.as_str(), None),
+        };
+        for right in &object.rights {
+            let _ = conn.execute(
+                "INSERT INTO role_rights (role_id, object, object_lower, item, right_name, granted, condition) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![role_id, name, name.to_lowercase(), item, right.name, right.value, right.condition],
+            );
+        }
+    }
+    for (name, condition) in &rights.templates {
+        let _ = conn.execute(
+            "INSERT INTO role_templates (role_id, name, condition) VALUES (?1, ?2, ?3)",
+            params![role_id, name, condition],
+        );
+    }
+    true
+}
+
+/// Method the platform invokes for a metadata object.
+struct EntryPoint {
+    obj_type: String,
+    name: String,
+    edge_kind: &'static str,
+    method: String,
+    /// Common module holding the method, as a call receiver.
+    receiver: Option<String>,
+    module_file: String,
+}
+
+/// `calls` rows for methods the platform invokes on behalf of metadata objects, each with its
+/// own `edge_kind`: event subscription handlers and scheduled job methods
+/// (`CommonModule.ОбменДанными.ПриЗаписи`), HTTP and web service handlers in the service's own
+/// module and `ОбработкаКоманды` of common commands.
+/// The caller is the object itself (`EventSubscription.ПриЗаписи`, file `EventSubscriptions/ПриЗаписи.xml`).
+/// Returns the number of edges added.
+fn insert_entry_point_calls(root: &Path, layout: SourceLayout, conn: &Connection) -> usize {
+    let mut edges: Vec<EntryPoint> = Vec::new();
+    if let Ok(mut stmt) = conn.prepare(
+        "SELECT o.obj_type, o.name, p.value FROM object_properties p JOIN objects o ON o.id = p.object_id \
+         WHERE p.name IN ('Handler', 'MethodName') ORDER BY o.obj_type, o.name",
+    ) {
+        let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?)));
+        for (obj_type, name, handler) in rows.into_iter().flatten().flatten() {
+            let edge_kind = match obj_type.as_str() {
+                "EventSubscription" => "event_subscription",
+                "ScheduledJob" => "scheduled_job",
+                "HTTPService" => "http_service",
+                "WebService" => "web_service",
+                _ => continue,
+            };
+            let parts: Vec<&str> = handler.split('.').collect();
+            let (method, receiver, module_file) = match parts.as_slice() {
+                ["CommonModule", module, method] => (
+                    method.to_string(),
+                    Some(module.to_string()),
+                    format!("{}/Module.bsl", layout.module_dir("CommonModules", module)),
+                ),
+                [method] if matches!(edge_kind, "http_service" | "web_service") => {
+                    let Some(folder) = obj_type_to_folder(&obj_type) else { continue };
+                    (method.to_string(), None, format!("{}/Module.bsl", layout.module_dir(folder, &name)))
+                }
+                _ => continue,
+            };
+            edges.push(EntryPoint { obj_type, name, edge_kind, method, receiver, module_file });
+        }
+    }
+    if let Ok(mut stmt) = conn.prepare("SELECT name FROM objects WHERE obj_type = 'CommonCommand' ORDER BY name") {
+        let rows = stmt.query_map([], |r| r.get::<_, String>(0));
+        for name in rows.into_iter().flatten().flatten() {
+            edges.push(EntryPoint {
+                obj_type: "CommonCommand".to_string(),
+                module_file: format!("{}/CommandModule.bsl", layout.module_dir("CommonCommands", &name)),
+                name,
+                edge_kind: "common_command",
+                method: "ОбработкаКоманды".to_string(),
+                receiver: None,
+            });
+        }
+    }
+
+    let mut count = 0;
+    for EntryPoint { obj_type, name, edge_kind, method, receiver, module_file } in edges {
+        let Some(folder) = obj_type_to_folder(&obj_type) else { continue };
+        let caller = format!("{}.{}", obj_type, name);
+        let callee_file = root.join(&module_file).exists().then_some(module_file);
+        let inserted = conn.execute(
+            "INSERT INTO calls (caller_file, caller_name, caller_name_lower, callee_name, callee_name_lower, \
+             callee_receiver, callee_receiver_lower, callee_file, edge_kind) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                layout.object_file(folder, &name), caller, caller.to_lowercase(),
+                method, method.to_lowercase(), receiver, receiver.as_ref().map(|r| r.to_lowercase()),
+                callee_file, edge_kind
+            ],
+        );
+        if inserted.is_ok() {
+            count += 1;
+        }
+    }
+    count
+}
+
 /// Parse ConfigDumpInfo.xml: extract `<Metadata name="...">` entries and
-/// populate `object_items` (attributes, tabular sections, forms, commands, modules).
+/// populate `object_items` (attributes, tabular sections, forms, commands, modules)
+/// for objects not in `skip` (already described by their own XML).
 fn parse_config_dump_info(
     path: &Path,
     conn: &Connection,
-    object_ids: &std::collections::HashMap<String, i64>,
+    object_ids: &HashMap<String, i64>,
+    skip: &HashSet<i64>,
 ) -> Result<(), String> {
     let content = crate::index::read_file_to_string_lossy(path)
         .map_err(|e| format!("Чтение ConfigDumpInfo.xml: {}", e))?;
 
-    let re = Regex::new(r#"<Metadata\s[^>]*?name="([^"]+)""#)
-        .map_err(|e| e.to_string())?;
-
-    let names: Vec<String> = re
-        .captures_iter(&content)
-        .filter_map(|c| c.get(1).map(|m| m.as_str().to_string()))
-        .collect();
+    let names = metadata_xml::parse_dump_info_names(&content);
 
     conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
 
@@ -174,8 +526,8 @@ fn parse_config_dump_info(
 
         let parent_key = format!("{}.{}", parts[0], parts[1]);
         let obj_id = match object_ids.get(&parent_key) {
-            Some(&id) => id,
-            None => continue,
+            Some(&id) if !skip.contains(&id) => id,
+            _ => continue,
         };
 
         match parts.len() {
@@ -193,8 +545,13 @@ fn parse_config_dump_info(
                 // e.g. Catalog.Agent.Attribute.Code
                 let child_type = parts[2];
                 let child_name = parts[3];
+                if child_type == "EnumValue" {
+                    insert_predefined(conn, obj_id, child_name, "", "", false, None);
+                    continue;
+                }
                 let mapped = match child_type {
-                    "Attribute" | "Dimension" | "Resource" | "AccountingFlag"
+                    "Dimension" | "Resource" => child_type,
+                    "Attribute" | "AccountingFlag"
                     | "ExtDimensionAccountingFlag" | "AddressingAttribute" => "Attribute",
                     "TabularSection" | "StandardTabularSection" => "TabularSection",
                     "Form" => "Form",
@@ -228,118 +585,201 @@ fn parse_config_dump_info(
     Ok(())
 }
 
-/// Parse a per-object XML file (e.g. `Catalogs/Валюты.xml`) and populate
-/// `object_items` with attributes, tabular sections and their columns.
-///
-/// Structure inside top-level `<ChildObjects>`:
-/// ```xml
-/// <Attribute uuid="..."><Properties><Name>AttrName</Name>...</Properties></Attribute>
-/// <TabularSection uuid="...">
-///   <Properties><Name>TSName</Name></Properties>
-///   <ChildObjects>
-///     <Attribute uuid="..."><Properties><Name>ColName</Name>...</Properties></Attribute>
-///   </ChildObjects>
-/// </TabularSection>
-/// ```
-fn parse_object_xml(path: &Path, conn: &Connection, obj_id: i64) -> Result<(), String> {
+/// Parse a per-object XML file (e.g. `Catalogs/Валюты.xml`) and populate `object_items`
+/// with attributes, register dimensions and resources (type, synonym, comment, indexing,
+/// fill checking), tabular sections and their columns, forms, commands and register records,
+/// `object_properties` with register settings, `objects` columns with common module flags, and
+/// `metadata_references` with the objects named by attribute types, register records and linked
+/// charts. Runs inside the caller's transaction.
+fn parse_object_xml(
+    path: &Path,
+    layout: SourceLayout,
+    conn: &Connection,
+    obj_type: &str,
+    obj_id: i64,
+) -> Result<(), String> {
     let content = crate::index::read_file_to_string_lossy(path)
         .map_err(|e| format!("Чтение {}: {}", path.display(), e))?;
+    let object = parse_description(&content, layout);
 
-    // Find the top-level <ChildObjects> block (after object <Properties>)
-    let child_start = match content.find("<ChildObjects>") {
-        Some(p) => p + "<ChildObjects>".len(),
-        None => return Ok(()),
-    };
-    let section = &content[child_start..];
-
-    let name_re = Regex::new(r"<Name>([^<\n]{1,200})</Name>").map_err(|e| e.to_string())?;
-
-    conn.execute_batch("BEGIN").map_err(|e| e.to_string())?;
-
-    let mut pos = 0;
-    let slen = section.len();
+    for attr in &object.attributes {
+        insert_attribute(conn, obj_id, attr, None);
+    }
+    for ts in &object.tabular_sections {
+        let _ = conn.execute(
+            "INSERT INTO object_items (object_id, item_type, item_name, parent_section, synonym, comment) \
+             VALUES (?1, 'TabularSection', ?2, NULL, ?3, ?4)",
+            params![obj_id, ts.name, non_empty(&ts.synonym), non_empty(&ts.comment)],
+        );
+        for attr in &ts.attributes {
+            insert_attribute(conn, obj_id, attr, Some(&ts.name));
+        }
+    }
+    for (name, value) in &object.properties {
+        let _ = conn.execute(
+            "INSERT INTO object_properties (object_id, name, value) VALUES (?1, ?2, ?3)",
+            params![obj_id, name, value],
+        );
+        if matches!(name.as_str(), "ChartOfAccounts" | "ChartOfCalculationTypes") {
+            insert_reference(conn, obj_id, Some(name.as_str()), "Property", value);
+        }
+    }
+    for handler in &object.service_handlers {
+        let _ = conn.execute(
+            "INSERT INTO object_properties (object_id, name, value) VALUES (?1, 'Handler', ?2)",
+            params![obj_id, handler],
+        );
+    }
+    // Every column of a common module is written: `.mdo` omits the flags that are off
+    if obj_type == "CommonModule" {
+        for &(flag, column) in MODULE_FLAG_COLUMNS {
+            let value = object.module_flags.iter().find(|(name, _)| name == flag).map(|(_, v)| v.as_str());
+            let value = if column == "return_values_reuse" {
+                rusqlite::types::Value::Text(value.unwrap_or("DontUse").to_string())
+            } else {
+                rusqlite::types::Value::Integer((value == Some("true")) as i64)
+            };
+            let _ = conn.execute(&format!("UPDATE objects SET {} = ?1 WHERE id = ?2", column), params![value, obj_id]);
+        }
+    }
+    for register in &object.register_records {
+        insert_reference(conn, obj_id, None, "RegisterRecord", register);
+    }
+    insert_type_references(conn, obj_id, None, "Type", &object.value_type);
+    for value in &object.enum_values {
+        insert_predefined(conn, obj_id, &value.name, &value.synonym, "", false, None);
+    }
+    // Registers a document writes to; read back as the register's recorders
+    for (item_type, names) in [
+        ("Form", &object.forms),
+        ("Command", &object.commands),
+        ("RegisterRecord", &object.register_records),
+    ] {
+        for name in names {
+            let _ = conn.execute(
+                "INSERT INTO object_items (object_id, item_type, item_name, parent_section) \
+                 VALUES (?1, ?2, ?3, NULL)",
+                params![obj_id, item_type, name],
+            );
+        }
+    }
+    Ok(())
+}
 
-    while pos < slen {
-        let rest = &section[pos..];
+/// Object description in either layout: `Catalogs/Валюты.xml` or `Catalogs/Валюты/Валюты.mdo`.
+fn parse_description(content: &str, layout: SourceLayout) -> MdObject {
+    match layout {
+        SourceLayout::Designer => metadata_xml::parse_object_xml(content),
+        SourceLayout::Edt => metadata_xml::parse_edt_object(content),
+    }
+}
 
-        if rest.starts_with("</ChildObjects>") {
-            break;
-        }
+/// Common module flags (`metadata_xml::COMMON_MODULE_FLAGS`) → `objects` columns.
+const MODULE_FLAG_COLUMNS: &[(&str, &str)] = &[
+    ("Global",                    "global"),
+    ("ClientManagedApplication",  "client_managed"),
+    ("ClientOrdinaryApplication", "client_ordinary"),
+    ("Server",                    "server"),
+    ("ExternalConnection",        "external_connection"),
+    ("ServerCall",                "server_call"),
+    ("Privileged",                "privileged"),
+    ("ReturnValuesReuse",         "return_values_reuse"),
+];
 
-        if rest.starts_with("<Attribute ") || rest.starts_with("<Attribute\t") || rest.starts_with("<Attribute\n") {
-            if let Some(close_rel) = rest.find("</Attribute>") {
-                let block = &rest[..close_rel];
-                if let Some(cap) = name_re.captures(block) {
-                    let attr_name = cap[1].trim().to_string();
-                    if !attr_name.is_empty() {
-                        let _ = conn.execute(
-                            "INSERT INTO object_items (object_id, item_type, item_name, parent_section) \
-                             VALUES (?1, 'Attribute', ?2, NULL)",
-                            params![obj_id, attr_name],
-                        );
-                    }
-                }
-                pos += close_rel + "</Attribute>".len();
-                continue;
-            }
-        } else if rest.starts_with("<TabularSection ") || rest.starts_with("<TabularSection\t") || rest.starts_with("<TabularSection\n") {
-            if let Some(close_rel) = rest.find("</TabularSection>") {
-                let ts_block = &rest[..close_rel];
+/// Predefined catalog and chart items from `Ext/Predefined.xml` (absent for objects without
+/// any) or from the object's `.mdo`.
+fn insert_predefined_items(path: &Path, layout: SourceLayout, conn: &Connection, obj_id: i64) {
+    let Ok(content) = crate::index::read_file_to_string_lossy(path) else { return };
+    let items = match layout {
+        SourceLayout::Designer => metadata_xml::parse_predefined_xml(&content),
+        SourceLayout::Edt => metadata_xml::parse_edt_predefined(&content),
+    };
+    for item in items {
+        insert_predefined(
+            conn, obj_id, &item.name, &item.description, &item.code, item.is_folder, item.parent.as_deref(),
+        );
+    }
+}
 
-                // Extract TS name from <Properties> (before nested <ChildObjects>)
-                let props_end = ts_block.find("<ChildObjects>").unwrap_or(ts_block.len().min(600));
-                let ts_name = name_re.captures(&ts_block[..props_end])
-                    .map(|c| c[1].trim().to_string())
-                    .unwrap_or_default();
+fn insert_predefined(
+    conn: &Connection,
+    obj_id: i64,
+    name: &str,
+    synonym: &str,
+    code: &str,
+    is_folder: bool,
+    parent: Option<&str>,
+) {
+    let _ = conn.execute(
+        "INSERT INTO predefined_values (object_id, name, name_lower, synonym, code, is_folder, parent) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![obj_id, name, name.to_lowercase(), non_empty(synonym), non_empty(code), is_folder as i64, parent],
+    );
+}
 
-                if !ts_name.is_empty() {
-                    let _ = conn.execute(
-                        "INSERT INTO object_items (object_id, item_type, item_name, parent_section) \
-                         VALUES (?1, 'TabularSection', ?2, NULL)",
-                        params![obj_id, &ts_name],
-                    );
+fn non_empty(text: &str) -> Option<&str> {
+    Some(text).filter(|t| !t.is_empty())
+}
 
-                    // Parse column attributes inside TS's <ChildObjects>
-                    if let Some(ts_child_start) = ts_block.find("<ChildObjects>") {
-                        let ts_children = &ts_block[ts_child_start + "<ChildObjects>".len()..];
-                        let mut ts_pos = 0;
-                        while ts_pos < ts_children.len() {
-                            let ts_rest = &ts_children[ts_pos..];
-                            if ts_rest.starts_with("</ChildObjects>") { break; }
-                            if ts_rest.starts_with("<Attribute ") || ts_rest.starts_with("<Attribute\t") {
-                                if let Some(col_close) = ts_rest.find("</Attribute>") {
-                                    let col_block = &ts_rest[..col_close];
-                                    if let Some(col_cap) = name_re.captures(col_block) {
-                                        let col_name = col_cap[1].trim().to_string();
-                                        if !col_name.is_empty() {
-                                            let _ = conn.execute(
-                                                "INSERT INTO object_items (object_id, item_type, item_name, parent_section) \
-                                                 VALUES (?1, 'Attribute', ?2, ?3)",
-                                                params![obj_id, col_name, &ts_name],
-                                            );
-                                        }
-                                    }
-                                    ts_pos += col_close + "</Attribute>".len();
-                                    continue;
-                                }
-                            }
-                            ts_pos += 1;
-                        }
-                    }
-                }
-                pos += close_rel + "</TabularSection>".len();
-                continue;
-            }
-        }
+fn insert_attribute(conn: &Connection, obj_id: i64, attr: &MdAttribute, section: Option<&str>) {
+    if attr.name.is_empty() {
+        return;
+    }
+    let item_type = match attr.kind.as_str() {
+        "Dimension" | "Resource" => attr.kind.as_str(),
+        _ => "Attribute",
+    };
+    let _ = conn.execute(
+        "INSERT INTO object_items \
+         (object_id, item_type, item_name, parent_section, value_type, synonym, comment, indexing, fill_checking) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            obj_id, item_type, attr.name, section,
+            non_empty(&attr.value_type), non_empty(&attr.synonym), non_empty(&attr.comment),
+            attr.indexing, attr.fill_checking
+        ],
+    );
+    let item = match section {
+        Some(section) => format!("{}.{}", section, attr.name),
+        None => attr.name.clone(),
+    };
+    insert_type_references(conn, obj_id, Some(&item), item_type, &attr.value_type);
+}
 
-        // Advance past current '<'
-        if let Some(next) = rest[1..].find('<') {
-            pos += next + 1;
-        } else {
-            break;
+/// One `metadata_references` edge per object named in a (possibly composite) formatted type.
+fn insert_type_references(conn: &Connection, obj_id: i64, item: Option<&str>, kind: &str, value_type: &str) {
+    let mut seen = HashSet::new();
+    for target in value_type.split(", ").filter_map(metadata_xml::referenced_object) {
+        if seen.insert(target.clone()) {
+            insert_reference(conn, obj_id, item, kind, &target);
         }
     }
+}
 
-    conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
-    Ok(())
+fn insert_reference(conn: &Connection, obj_id: i64, item: Option<&str>, kind: &str, target: &str) {
+    let _ = conn.execute(
+        "INSERT INTO metadata_references (object_id, item, kind, target, target_lower) \
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![obj_id, item, kind, target, target.to_lowercase()],
+    );
+}
+
+/// Modules of an object are not listed in its XML: take them from `<Object>/Ext/*Module.bsl`
+/// (EDT: `<Object>/*Module.bsl`).
+fn insert_module_items(ext_dir: &Path, conn: &Connection, obj_id: i64) {
+    let Ok(entries) = std::fs::read_dir(ext_dir) else { return };
+    let mut modules: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name.strip_suffix(".bsl").filter(|stem| stem.ends_with("Module")).map(str::to_string)
+        })
+        .collect();
+    modules.sort();
+    for module in modules {
+        let _ = conn.execute(
+            "INSERT INTO object_items (object_id, item_type, item_name, parent_section) VALUES (?1, ?2, ?3, NULL)",
+            params![obj_id, module, module],
+        );
+    }
 }
@@ -1,5 +1,7 @@
 use tree_sitter::Parser;
 
+use super::bsl_doc::{doc_comment_lines, parse_doc_comment, BslDoc};
+
 pub fn create_bsl_parser() -> Parser {
     let mut parser = Parser::new();
     let language: tree_sitter::Language = tree_sitter_bsl::LANGUAGE.into();
@@ -14,7 +16,40 @@ pub struct BslSymbol {
     pub start_line: u32, // 1-based
     pub end_line: u32,   // 1-based
     pub is_export: bool,
-    pub calls: Vec<String>, // names of called functions/procedures
+    /// Compilation directive in canonical form (`НаСервере`, `НаКлиенте`, ...), see `normalize_directive`.
+    pub directive: Option<String>,
+    pub params: Vec<BslParam>,
+    /// Extension annotation (`&Перед("Метод")`, ...) — the base method this one intercepts.
+    pub interception: Option<BslInterception>,
+    /// Structured description block from the comment above the definition.
+    pub doc: Option<BslDoc>,
+    pub calls: Vec<BslCall>, // called functions/procedures
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BslParam {
+    pub name: String,
+    /// Declared with `Знач` / `Val` (passed by value).
+    pub by_value: bool,
+    /// Default value as written in source (`0`, `Неопределено`, `";"`), `None` if required.
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BslInterception {
+    /// Canonical annotation: `Перед`, `После`, `Вместо`, `ИзменениеИКонтроль`.
+    pub kind: String,
+    /// Name of the intercepted method of the base configuration.
+    pub target: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BslCall {
+    pub name: String,
+    /// Receiver expression before the dot: `ОбщегоНазначения` for
+    /// `ОбщегоНазначения.ЗначениеРеквизитаОбъекта()`, `Справочники.Валюты` for manager calls.
+    /// `None` for unqualified calls (`ПодготовитьДанные()`).
+    pub receiver: Option<String>,
 }
 
 /// BSL keywords that appear as identifiers in calls but are not function names.
@@ -42,8 +77,225 @@ const BSL_KEYWORDS: &[&str] = &[
     "var",
 ];
 
-/// Collect all `method_call` node names within a subtree (excluding nested function/proc bodies).
-fn extract_calls_from_node(node: tree_sitter::Node, source: &[u8], seen: &mut std::collections::HashSet<String>, result: &mut Vec<String>) {
+/// Canonical compilation directives: (Russian, English).
+const DIRECTIVES: &[(&str, &str)] = &[
+    ("НаКлиенте",                      "AtClient"),
+    ("НаСервере",                      "AtServer"),
+    ("НаСервереБезКонтекста",          "AtServerNoContext"),
+    ("НаКлиентеНаСервереБезКонтекста", "AtClientAtServerNoContext"),
+    ("НаКлиентеНаСервере",             "AtClientAtServer"),
+];
+
+/// Normalize a directive written in either language, with or without `&`,
+/// to its canonical Russian spelling: `&AtServer` → `НаСервере`.
+pub fn normalize_directive(raw: &str) -> Option<&'static str> {
+    let name = raw.trim().trim_start_matches('&').trim();
+    DIRECTIVES
+        .iter()
+        .find(|(ru, en)| name.eq_ignore_ascii_case(en) || name.to_lowercase() == ru.to_lowercase())
+        .map(|(ru, _)| *ru)
+}
+
+/// Extension (CFE) annotations: (Russian, English).
+const INTERCEPTIONS: &[(&str, &str)] = &[
+    ("Перед",              "Before"),
+    ("После",              "After"),
+    ("Вместо",             "Around"),
+    ("ИзменениеИКонтроль", "ChangeAndValidate"),
+];
+
+/// Parse an extension annotation written without `&`: `Перед("ПередЗаписью")` →
+/// kind `Перед`, target `ПередЗаписью`. English spellings are normalized to Russian.
+pub fn parse_interception(annotation: &str) -> Option<BslInterception> {
+    let (name, rest) = annotation.trim().trim_start_matches('&').split_once('(')?;
+    let name = name.trim();
+    let kind = INTERCEPTIONS
+        .iter()
+        .find(|(ru, en)| name.eq_ignore_ascii_case(en) || name.to_lowercase() == ru.to_lowercase())
+        .map(|(ru, _)| *ru)?;
+    let target = rest.split('"').nth(1)?.trim();
+    if target.is_empty() {
+        return None;
+    }
+    Some(BslInterception { kind: kind.to_string(), target: target.to_string() })
+}
+
+/// Annotations (`&НаСервере`, `&Перед("Метод")`) attached to a procedure/function definition,
+/// without the leading `&`.
+///
+/// Annotation nodes are looked up inside the definition and among its preceding siblings;
+/// if the tree has none (e.g. the grammar folded them into an ERROR node), the source lines
+/// directly above the definition are scanned instead.
+fn definition_annotations(node: tree_sitter::Node, source: &[u8]) -> Vec<String> {
+    let text_of = |n: tree_sitter::Node| {
+        n.utf8_text(source).unwrap_or("").trim().trim_start_matches('&').trim().to_string()
+    };
+
+    let mut result: Vec<String> = Vec::new();
+    for i in 0..node.named_child_count() {
+        if let Some(child) = node.named_child(i) {
+            if child.kind().contains("annotation") {
+                result.push(text_of(child));
+            }
+        }
+    }
+    let mut prev = node.prev_named_sibling();
+    while let Some(sib) = prev {
+        if !sib.kind().contains("annotation") {
+            break;
+        }
+        result.insert(0, text_of(sib));
+        prev = sib.prev_named_sibling();
+    }
+    if !result.is_empty() {
+        return result;
+    }
+
+    // Text fallback: leading `&...` lines of the definition itself, then the lines right above it
+    // (blank lines and comments skipped)
+    for line in node.utf8_text(source).unwrap_or("").lines() {
+        match line.trim().strip_prefix('&') {
+            Some(a) => result.push(a.trim().to_string()),
+            None => break,
+        }
+    }
+    if !result.is_empty() {
+        return result;
+    }
+    let before = std::str::from_utf8(&source[..node.start_byte()]).unwrap_or("");
+    for line in before.lines().rev() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        match line.strip_prefix('&') {
+            Some(a) => result.insert(0, a.trim().to_string()),
+            None => break,
+        }
+    }
+    result
+}
+
+/// Parse the parameter list of a definition: the first `(...)` after byte offset `after`
+/// (end of the method name). String literals in default values may contain `,` and `)`.
+/// `source` should end with the signature: everything after `after` is decoded.
+pub fn signature_params(source: &[u8], after: usize) -> Vec<BslParam> {
+    let rest = match source.get(after..).and_then(|b| std::str::from_utf8(b).ok()) {
+        Some(r) => r,
+        None => return vec![],
+    };
+    let open = match rest.find('(') {
+        Some(i) if rest[..i].trim().is_empty() => i,
+        _ => return vec![],
+    };
+
+    let mut raw_params: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut in_string = false;
+    let mut depth = 0usize;
+    for c in rest[open + 1..].chars() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string && depth == 0 => break,
+            ')' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                raw_params.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    raw_params.push(current);
+
+    raw_params
+        .iter()
+        .filter_map(|raw| {
+            let raw = raw.trim();
+            if raw.is_empty() {
+                return None;
+            }
+            let (decl, default) = match raw.split_once('=') {
+                Some((d, v)) => (d.trim(), Some(v.trim().to_string())),
+                None => (raw, None),
+            };
+            let mut words = decl.split_whitespace();
+            let first = words.next()?;
+            let by_value = first.to_lowercase() == "знач" || first.eq_ignore_ascii_case("val");
+            let name = if by_value { words.next()? } else { first };
+            Some(BslParam { name: name.to_string(), by_value, default })
+        })
+        .collect()
+}
+
+/// Bytes before a call that `call_receiver` looks at: enough for any receiver chain written in
+/// practice, including the argument lists of intermediate calls.
+const RECEIVER_WINDOW: usize = 1024;
+
+/// Extract the receiver chain written before a call that starts at `call_start` (byte offset).
+///
+/// `Модуль.Метод()` → `Some("Модуль")`, `Справочники.Валюты.Метод()` → `Some("Справочники.Валюты")`,
+/// `Объект.Получить().Метод()` → `Some("Объект.Получить()")`, `Метод()` → `None`.
+/// Argument lists of intermediate calls are collapsed to `()` / `[]`.
+///
+/// Only the last `RECEIVER_WINDOW` bytes before the call are read, so the cost per call does not
+/// grow with its offset in the module; a longer receiver is cut at an unbalanced bracket.
+pub fn call_receiver(source: &[u8], call_start: usize) -> Option<String> {
+    let prefix = source.get(..call_start)?;
+    // Start the window on a character boundary (skip UTF-8 continuation bytes)
+    let mut start = call_start.saturating_sub(RECEIVER_WINDOW);
+    while start < call_start && (prefix[start] & 0xC0) == 0x80 {
+        start += 1;
+    }
+    let before = std::str::from_utf8(&prefix[start..]).ok()?;
+    let before = before.trim_end().strip_suffix('.')?;
+    let chars: Vec<char> = before.trim_end().chars().collect();
+
+    let mut parts: Vec<String> = Vec::new();
+    let mut i = chars.len();
+    while i > 0 {
+        let c = chars[i - 1];
+        if c == ')' || c == ']' {
+            let open = if c == ')' { '(' } else { '[' };
+            let mut depth = 0usize;
+            let mut j = i;
+            while j > 0 {
+                j -= 1;
+                if chars[j] == c {
+                    depth += 1;
+                } else if chars[j] == open {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            if depth != 0 {
+                break;
+            }
+            parts.push(if c == ')' { "()".to_string() } else { "[]".to_string() });
+            i = j;
+        } else if c.is_alphanumeric() || c == '_' || c == '.' {
+            parts.push(c.to_string());
+            i -= 1;
+        } else {
+            break;
+        }
+    }
+    parts.reverse();
+    let receiver = parts.concat();
+    let receiver = receiver.trim_matches('.');
+    if receiver.is_empty() {
+        None
+    } else {
+        Some(receiver.to_string())
+    }
+}
+
+/// Collect all `method_call` nodes within a subtree (excluding nested function/proc bodies).
+/// Calls are deduplicated by (receiver, name), case-insensitively.
+fn extract_calls_from_node(node: tree_sitter::Node, source: &[u8], seen: &mut std::collections::HashSet<String>, result: &mut Vec<BslCall>) {
     let kind = node.kind();
 
     // Do not descend into nested function/procedure definitions
@@ -57,9 +309,16 @@ fn extract_calls_from_node(node: tree_sitter::Node, source: &[u8], seen: &mut st
             let name = name_node.utf8_text(source).unwrap_or("").trim().to_string();
             if !name.is_empty() {
                 let name_lower = name.to_lowercase();
-                if !BSL_KEYWORDS.contains(&name_lower.as_str()) && !seen.contains(&name_lower) {
-                    seen.insert(name_lower);
-                    result.push(name);
+                if !BSL_KEYWORDS.contains(&name_lower.as_str()) {
+                    let receiver = call_receiver(source, name_node.start_byte());
+                    let key = format!(
+                        "{}|{}",
+                        receiver.as_deref().unwrap_or("").to_lowercase(),
+                        name_lower
+                    );
+                    if seen.insert(key) {
+                        result.push(BslCall { name, receiver });
+                    }
                 }
             }
         }
@@ -81,13 +340,13 @@ pub fn extract_symbols(source: &str) -> Vec<BslSymbol> {
         None => return vec![],
     };
     let root = tree.root_node();
-    let source_bytes = source.as_bytes();
     let mut symbols = Vec::new();
-    traverse_for_symbols(root, source_bytes, &mut symbols);
+    traverse_for_symbols(root, source, &mut symbols);
     symbols
 }
 
-fn traverse_for_symbols(node: tree_sitter::Node, source: &[u8], symbols: &mut Vec<BslSymbol>) {
+fn traverse_for_symbols(node: tree_sitter::Node, text: &str, symbols: &mut Vec<BslSymbol>) {
+    let source = text.as_bytes();
     let kind = node.kind();
     if kind == "procedure_definition" || kind == "function_definition" {
         if let Some(name_node) = node.child_by_field_name("name") {
@@ -97,6 +356,19 @@ fn traverse_for_symbols(node: tree_sitter::Node, source: &[u8], symbols: &mut Ve
                 let start_line = node.start_position().row as u32 + 1;
                 let end_line = node.end_position().row as u32 + 1;
                 let sym_kind = if kind == "procedure_definition" { "procedure" } else { "function" };
+                // Read the signature only: up to the end of the parameter list (or of the definition)
+                let signature_end = (0..node.child_count())
+                    .filter_map(|i| node.child(i))
+                    .find(|child| child.kind() == "parameters")
+                    .map_or(node.end_byte(), |p| p.end_byte());
+                let params = signature_params(&source[..signature_end], name_node.end_byte());
+                let doc = parse_doc_comment(&doc_comment_lines(text, node.start_byte()));
+                let annotations = definition_annotations(node, source);
+                let directive = annotations
+                    .iter()
+                    .find_map(|a| normalize_directive(a))
+                    .map(|d| d.to_string());
+                let interception = annotations.iter().find_map(|a| parse_interception(a));
 
                 // Extract calls from function body
                 let mut seen = std::collections::HashSet::new();
@@ -118,6 +390,10 @@ fn traverse_for_symbols(node: tree_sitter::Node, source: &[u8], symbols: &mut Ve
                     start_line,
                     end_line,
                     is_export,
+                    directive,
+                    params,
+                    interception,
+                    doc,
                     calls,
                 });
             }
@@ -129,7 +405,7 @@ fn traverse_for_symbols(node: tree_sitter::Node, source: &[u8], symbols: &mut Ve
     let child_count = node.child_count();
     for i in 0..child_count {
         if let Some(child) = node.child(i) {
-            traverse_for_symbols(child, source, symbols);
+            traverse_for_symbols(child, text, symbols);
         }
     }
 }
@@ -165,8 +441,122 @@ mod tests {
         let symbols = extract_symbols(code);
         assert_eq!(symbols.len(), 1);
         let calls = &symbols[0].calls;
-        assert!(calls.contains(&"ПодготовитьДанные".to_string()));
-        assert!(calls.contains(&"ЗаписатьВЖурнал".to_string()));
-        assert!(calls.contains(&"МойМетод".to_string()));
+        let names: Vec<&str> = calls.iter().map(|c| c.name.as_str()).collect();
+        assert!(names.contains(&"ПодготовитьДанные"));
+        assert!(names.contains(&"ЗаписатьВЖурнал"));
+        assert!(names.contains(&"МойМетод"));
+        let qualified = calls.iter().find(|c| c.name == "МойМетод").unwrap();
+        assert_eq!(qualified.receiver.as_deref(), Some("Объект"));
+        let local = calls.iter().find(|c| c.name == "ПодготовитьДанные").unwrap();
+        assert_eq!(local.receiver, None);
+    }
+
+    #[test]
+    fn test_extract_calls_same_name_different_modules() {
+        let code = "Процедура Тест()\n\tЗначениеРеквизитаОбъекта(Ссылка);\n\tОбщегоНазначения.ЗначениеРеквизитаОбъекта(Ссылка, \"Код\");\nКонецПроцедуры\n";
+        let symbols = extract_symbols(code);
+        let receivers: Vec<Option<&str>> = symbols[0].calls.iter().map(|c| c.receiver.as_deref()).collect();
+        assert_eq!(receivers.len(), 2);
+        assert!(receivers.contains(&None));
+        assert!(receivers.contains(&Some("ОбщегоНазначения")));
+    }
+
+    #[test]
+    fn test_extract_directives() {
+        let code = "&НаКлиенте\nПроцедура ПриОткрытии(Отказ)\nКонецПроцедуры\n\n&AtServerNoContext\nФункция Получить()\n\tВозврат 1;\nКонецФункции\n\nПроцедура БезДирективы()\nКонецПроцедуры\n";
+        let symbols = extract_symbols(code);
+        assert_eq!(symbols.len(), 3);
+        assert_eq!(symbols[0].directive.as_deref(), Some("НаКлиенте"));
+        assert_eq!(symbols[1].directive.as_deref(), Some("НаСервереБезКонтекста"));
+        assert_eq!(symbols[2].directive, None);
+    }
+
+    #[test]
+    fn test_normalize_directive() {
+        assert_eq!(normalize_directive("&НаСервере"), Some("НаСервере"));
+        assert_eq!(normalize_directive("насервере"), Some("НаСервере"));
+        assert_eq!(normalize_directive("&AtClientAtServerNoContext"), Some("НаКлиентеНаСервереБезКонтекста"));
+        assert_eq!(normalize_directive("&Перед(\"Метод\")"), None);
+    }
+
+    #[test]
+    fn test_extract_params() {
+        let code = "Функция Сумма(Знач А, Б = 0, Разделитель = \",\") Экспорт\n\tВозврат А + Б;\nКонецФункции\n\nПроцедура БезПараметров()\nКонецПроцедуры\n";
+        let symbols = extract_symbols(code);
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].params.len(), 3);
+        assert!(symbols[0].params[0].by_value);
+        assert_eq!(symbols[0].params[1].default.as_deref(), Some("0"));
+        assert_eq!(symbols[0].params[2].default.as_deref(), Some("\",\""));
+        assert!(symbols[1].params.is_empty());
+    }
+
+    #[test]
+    fn test_extract_doc() {
+        let code = "// Складывает числа.\n//\n// Параметры:\n//  А - Число - первое слагаемое.\n//\n// Возвращаемое значение:\n//  Число - сумма.\n&НаСервере\nФункция Сумма(А) Экспорт\n\tВозврат А;\nКонецФункции\n\nПроцедура БезОписания()\nКонецПроцедуры\n";
+        let symbols = extract_symbols(code);
+        assert_eq!(symbols.len(), 2);
+        let doc = symbols[0].doc.as_ref().unwrap();
+        assert_eq!(doc.summary, "Складывает числа.");
+        assert_eq!(doc.params[0].name, "А");
+        assert_eq!(doc.returns.as_ref().unwrap().types, "Число");
+        assert!(symbols[1].doc.is_none());
+    }
+
+    #[test]
+    fn test_extract_interception() {
+        let code = "&НаСервере\n&Перед(\"ПередЗаписью\")\nПроцедура Расш1_ПередЗаписью(Отказ)\nКонецПроцедуры\n";
+        let symbols = extract_symbols(code);
+        assert_eq!(symbols.len(), 1);
+        let interception = symbols[0].interception.as_ref().unwrap();
+        assert_eq!(interception.kind, "Перед");
+        assert_eq!(interception.target, "ПередЗаписью");
+        assert_eq!(symbols[0].directive.as_deref(), Some("НаСервере"));
+    }
+
+    #[test]
+    fn test_parse_interception() {
+        let i = parse_interception("Вместо(\"ЗаполнитьСписок\")").unwrap();
+        assert_eq!((i.kind.as_str(), i.target.as_str()), ("Вместо", "ЗаполнитьСписок"));
+        let i = parse_interception("&ChangeAndValidate(\"Fill\")").unwrap();
+        assert_eq!(i.kind, "ИзменениеИКонтроль");
+        assert!(parse_interception("НаСервере").is_none());
+        assert!(parse_interception("Перед()").is_none());
+    }
+
+    #[test]
+    fn test_signature_params() {
+        let src = "Процедура Записать(Знач Объект, Отказ, Режим = Неопределено, Текст = \"(a, b)\")";
+        let at = src.find('(').unwrap();
+        let params = signature_params(src.as_bytes(), at);
+        let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Объект", "Отказ", "Режим", "Текст"]);
+        assert!(params[0].by_value && !params[1].by_value);
+        assert_eq!(params[2].default.as_deref(), Some("Неопределено"));
+        assert_eq!(params[3].default.as_deref(), Some("\"(a, b)\""));
+
+        let src = "Val Param1, Param2";
+        assert!(signature_params(src.as_bytes(), 0).is_empty());
+    }
+
+    #[test]
+    fn test_call_receiver() {
+        let src = "А = Справочники.Валюты.НайтиПоКоду(Код);";
+        let at = src.find("НайтиПоКоду").unwrap();
+        assert_eq!(call_receiver(src.as_bytes(), at).as_deref(), Some("Справочники.Валюты"));
+
+        let src = "Запрос.Выполнить().Выгрузить();";
+        let at = src.find("Выгрузить").unwrap();
+        assert_eq!(call_receiver(src.as_bytes(), at).as_deref(), Some("Запрос.Выполнить()"));
+
+        let src = "\tПодготовитьДанные();";
+        let at = src.find("ПодготовитьДанные").unwrap();
+        assert_eq!(call_receiver(src.as_bytes(), at), None);
+
+        // Far into a module, with the window starting inside a multi-byte character
+        let src = format!("{}\nОбщегоНазначения.СообщитьПользователю(Текст);", "// ё\n".repeat(700));
+        let at = src.find("СообщитьПользователю").unwrap();
+        assert!(at > RECEIVER_WINDOW);
+        assert_eq!(call_receiver(src.as_bytes(), at).as_deref(), Some("ОбщегоНазначения"));
     }
 }
@@ -0,0 +1,225 @@
+//! Parser for the standard 1C method description block written above a procedure/function:
+//!
+//! ```text
+//! // Краткое описание.
+//! //
+//! // Параметры:
+//! //  Объект - ДокументОбъект - записываемый документ.
+//! //  Отказ  - Булево - признак отказа.
+//! //
+//! // Возвращаемое значение:
+//! //  Строка - представление документа.
+//! //
+//! // Пример:
+//! //  Текст = Модуль.Метод(Объект, Ложь);
+//! ```
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BslDoc {
+    pub summary: String,
+    pub params: Vec<BslDocParam>,
+    pub returns: Option<BslDocReturn>,
+    pub examples: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BslDocParam {
+    pub name: String,
+    /// Types as written: `Строка, Неопределено`.
+    pub types: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BslDocReturn {
+    pub types: String,
+    pub description: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Summary,
+    Params,
+    Returns,
+    Example,
+    Other,
+}
+
+fn section_header(line: &str) -> Option<Section> {
+    let header = line.trim().strip_suffix(':')?.trim().to_lowercase();
+    match header.as_str() {
+        "параметры" | "parameters" => Some(Section::Params),
+        "возвращаемое значение" | "returns" | "return value" => Some(Section::Returns),
+        "пример" | "примеры" | "пример использования" | "example" | "examples" => Some(Section::Example),
+        "варианты вызова" | "call options" | "описание" | "description" => Some(Section::Other),
+        _ => None,
+    }
+}
+
+/// Comment lines (without `//`) directly above the definition starting at `def_start` (byte offset).
+/// Annotation lines (`&НаСервере`) between the comment and the definition are skipped.
+pub fn doc_comment_lines(source: &str, def_start: usize) -> Vec<String> {
+    let before = source.get(..def_start).unwrap_or("");
+    let mut lines: Vec<String> = Vec::new();
+    // The first element is the (possibly empty) text before the definition on its own line
+    for (i, line) in before.lines().rev().enumerate() {
+        let trimmed = line.trim();
+        if i == 0 && !before.ends_with('\n') && trimmed.is_empty() {
+            continue;
+        }
+        if trimmed.starts_with('&') && lines.is_empty() {
+            continue;
+        }
+        match trimmed.strip_prefix("//") {
+            Some(text) => lines.push(text.strip_prefix(' ').unwrap_or(text).trim_end().to_string()),
+            None => break,
+        }
+    }
+    lines.reverse();
+    lines
+}
+
+/// Split `Имя - Тип - Описание` style lines on the first dash separator (`-`, `–`, `—`).
+fn split_dash(text: &str) -> Option<(&str, &str)> {
+    let (pos, dash) = text
+        .char_indices()
+        .find(|(i, c)| matches!(c, '-' | '–' | '—') && text[..*i].ends_with(char::is_whitespace))?;
+    Some((text[..pos].trim(), text[pos + dash.len_utf8()..].trim()))
+}
+
+fn append_line(target: &mut String, line: &str) {
+    if !target.is_empty() {
+        target.push('\n');
+    }
+    target.push_str(line);
+}
+
+/// Parse a description block. Returns `None` when the comment is empty.
+pub fn parse_doc_comment(lines: &[String]) -> Option<BslDoc> {
+    let mut doc = BslDoc::default();
+    let mut section = Section::Summary;
+    let mut in_returns_body = false;
+
+    for raw in lines {
+        let line = raw.trim();
+        if let Some(s) = section_header(line) {
+            section = s;
+            in_returns_body = false;
+            continue;
+        }
+        match section {
+            Section::Summary => {
+                if !line.is_empty() {
+                    append_line(&mut doc.summary, line);
+                }
+            }
+            Section::Params => {
+                if line.is_empty() {
+                    continue;
+                }
+                let is_field = line.starts_with('*');
+                let new_param = if is_field {
+                    None
+                } else {
+                    split_dash(line).filter(|(name, _)| {
+                        !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                    })
+                };
+                match (new_param, doc.params.last_mut()) {
+                    (Some((name, rest)), _) => {
+                        let (types, description) = split_dash(rest).unwrap_or((rest, ""));
+                        doc.params.push(BslDocParam {
+                            name: name.to_string(),
+                            types: types.to_string(),
+                            description: description.to_string(),
+                        });
+                    }
+                    (None, Some(last)) => append_line(&mut last.description, line),
+                    (None, None) => {}
+                }
+            }
+            Section::Returns => {
+                if line.is_empty() {
+                    continue;
+                }
+                match doc.returns.as_mut() {
+                    Some(ret) if in_returns_body => append_line(&mut ret.description, line),
+                    _ => {
+                        let (types, description) = split_dash(line).unwrap_or((line, ""));
+                        doc.returns = Some(BslDocReturn {
+                            types: types.to_string(),
+                            description: description.to_string(),
+                        });
+                        in_returns_body = true;
+                    }
+                }
+            }
+            Section::Example => {
+                if !line.is_empty() || !doc.examples.is_empty() {
+                    append_line(&mut doc.examples, raw.strip_prefix(' ').unwrap_or(raw));
+                }
+            }
+            Section::Other => {}
+        }
+    }
+    doc.examples = doc.examples.trim_end().to_string();
+
+    if doc.summary.is_empty() && doc.params.is_empty() && doc.returns.is_none() && doc.examples.is_empty() {
+        None
+    } else {
+        Some(doc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_standard_description() {
+        let comment = lines(
+            "Возвращает представление документа.\n\
+             \n\
+             Параметры:\n \
+             Объект - ДокументОбъект, ДокументСсылка - документ.\n \
+                 Может быть не записан.\n \
+             Отказ  - Булево - признак отказа.\n \
+             Настройки - Структура:\n \
+               * Формат - Строка - формат вывода.\n\
+             \n\
+             Возвращаемое значение:\n \
+             Строка - представление.\n\
+             \n\
+             Пример:\n \
+             Текст = Модуль.Представление(Объект, Ложь);",
+        );
+        let doc = parse_doc_comment(&comment).unwrap();
+        assert_eq!(doc.summary, "Возвращает представление документа.");
+        assert_eq!(doc.params.len(), 3);
+        assert_eq!(doc.params[0].name, "Объект");
+        assert_eq!(doc.params[0].types, "ДокументОбъект, ДокументСсылка");
+        assert!(doc.params[0].description.contains("Может быть не записан."));
+        assert_eq!(doc.params[1].types, "Булево");
+        assert_eq!(doc.params[2].types, "Структура:");
+        assert!(doc.params[2].description.contains("* Формат"));
+        let ret = doc.returns.unwrap();
+        assert_eq!(ret.types, "Строка");
+        assert_eq!(ret.description, "представление.");
+        assert!(doc.examples.contains("Модуль.Представление"));
+    }
+
+    #[test]
+    fn test_doc_comment_lines_skip_annotations() {
+        let src = "КонецПроцедуры\n\n// Описание метода.\n//\n&НаСервере\nПроцедура Метод()\nКонецПроцедуры\n";
+        let at = src.find("Процедура Метод").unwrap();
+        assert_eq!(doc_comment_lines(src, at), vec!["Описание метода.".to_string(), String::new()]);
+
+        let src = "Процедура Без()\nКонецПроцедуры\n";
+        assert!(doc_comment_lines(src, 0).is_empty());
+        assert!(parse_doc_comment(&[]).is_none());
+    }
+}
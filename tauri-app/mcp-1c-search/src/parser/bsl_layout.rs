@@ -0,0 +1,210 @@
+//! Module layout that lives outside procedure bodies: `#Область ... #КонецОбласти` regions
+//! and module-level `Перем` declarations.
+//!
+//! Both are line-oriented preprocessor/declaration constructs, so they are read from the
+//! source text directly rather than from the syntax tree.
+
+use super::bsl_ast::normalize_directive;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BslRegion {
+    pub name: String,
+    pub start_line: u32, // 1-based, `#Область` line
+    pub end_line: u32,   // 1-based, `#КонецОбласти` line (last line of the module if unclosed)
+    /// Nesting level, 0 for top-level regions.
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BslModuleVar {
+    pub name: String,
+    pub line: u32, // 1-based
+    pub is_export: bool,
+    /// Compilation directive of the declaration (form modules), canonical form.
+    pub directive: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BslModuleLayout {
+    /// Regions in order of their opening line.
+    pub regions: Vec<BslRegion>,
+    pub variables: Vec<BslModuleVar>,
+}
+
+/// Line text without a trailing `//` comment (string literals are respected).
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut prev_slash = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => {
+                in_string = !in_string;
+                prev_slash = false;
+            }
+            '/' if !in_string => {
+                if prev_slash {
+                    return &line[..i - 1];
+                }
+                prev_slash = true;
+            }
+            _ => prev_slash = false,
+        }
+    }
+    line
+}
+
+/// First word of a line, lowercased.
+fn first_word(line: &str) -> String {
+    line.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .find(|w| !w.is_empty())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+fn is_method_start(line_lower: &str) -> bool {
+    let rest = line_lower
+        .strip_prefix("асинх ")
+        .or_else(|| line_lower.strip_prefix("async "))
+        .unwrap_or(line_lower)
+        .trim_start();
+    ["процедура ", "функция ", "procedure ", "function "]
+        .iter()
+        .any(|kw| rest.starts_with(kw))
+}
+
+/// `Перем А, Б Экспорт;` → variables `А` and `Б` (the latter exported).
+fn parse_var_statement(decl: &str, line: u32, directive: &Option<String>, out: &mut Vec<BslModuleVar>) {
+    let decl = decl.split(';').next().unwrap_or("");
+    for part in decl.split(',') {
+        let mut words = part.split_whitespace();
+        let Some(name) = words.next() else { continue };
+        let is_export = words.any(|w| w.to_lowercase() == "экспорт" || w.eq_ignore_ascii_case("export"));
+        out.push(BslModuleVar {
+            name: name.to_string(),
+            line,
+            is_export,
+            directive: directive.clone(),
+        });
+    }
+}
+
+/// Extract regions and module-level variables from BSL source.
+pub fn extract_module_layout(source: &str) -> BslModuleLayout {
+    let mut layout = BslModuleLayout::default();
+    // Indexes into `layout.regions` of the currently open regions
+    let mut open: Vec<usize> = Vec::new();
+    let mut in_method = false;
+    let mut pending_directive: Option<String> = None;
+    let mut last_line = 0u32;
+
+    for (i, raw) in source.lines().enumerate() {
+        let line_no = i as u32 + 1;
+        last_line = line_no;
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let lower = line.to_lowercase();
+
+        if let Some(directive) = lower.strip_prefix('#') {
+            let keyword = first_word(directive);
+            match keyword.as_str() {
+                "область" | "region" => {
+                    let name = line[1..].trim()[keyword.len()..].trim();
+                    let name = name.split_whitespace().next().unwrap_or("").to_string();
+                    layout.regions.push(BslRegion {
+                        name,
+                        start_line: line_no,
+                        end_line: 0,
+                        depth: open.len() as u32,
+                    });
+                    open.push(layout.regions.len() - 1);
+                }
+                "конецобласти" | "endregion" => {
+                    if let Some(idx) = open.pop() {
+                        layout.regions[idx].end_line = line_no;
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if let Some(annotation) = line.strip_prefix('&') {
+            pending_directive = normalize_directive(annotation).map(|d| d.to_string());
+            continue;
+        }
+
+        let keyword = first_word(&lower);
+        if in_method {
+            if matches!(keyword.as_str(), "конецпроцедуры" | "конецфункции" | "endprocedure" | "endfunction") {
+                in_method = false;
+            }
+        } else if is_method_start(&lower) {
+            in_method = !lower.contains("конецпроцедуры")
+                && !lower.contains("конецфункции")
+                && !lower.contains("endprocedure")
+                && !lower.contains("endfunction");
+        } else if keyword == "перем" || keyword == "var" {
+            parse_var_statement(line[keyword.len()..].trim(), line_no, &pending_directive, &mut layout.variables);
+        }
+        pending_directive = None;
+    }
+
+    for idx in open {
+        layout.regions[idx].end_line = last_line;
+    }
+    layout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_regions_and_variables() {
+        let code = "\
+&НаКлиенте
+Перем КэшДанных Экспорт, Счетчик; // кэш
+
+#Область ПрограммныйИнтерфейс
+
+Функция Получить() Экспорт
+\tПерем Локальная;
+\tВозврат КэшДанных;
+КонецФункции
+
+#Область Подраздел
+Процедура Вложенная()
+КонецПроцедуры
+#КонецОбласти
+
+#КонецОбласти
+
+#Region Private
+Procedure Helper() EndProcedure
+";
+        let layout = extract_module_layout(code);
+
+        let names: Vec<(&str, u32)> = layout.regions.iter().map(|r| (r.name.as_str(), r.depth)).collect();
+        assert_eq!(names, vec![("ПрограммныйИнтерфейс", 0), ("Подраздел", 1), ("Private", 0)]);
+        assert_eq!((layout.regions[0].start_line, layout.regions[0].end_line), (4, 16));
+        assert_eq!((layout.regions[1].start_line, layout.regions[1].end_line), (11, 14));
+        // Unclosed region runs to the end of the module
+        assert_eq!(layout.regions[2].end_line, 19);
+
+        assert_eq!(layout.variables.len(), 2);
+        assert_eq!(layout.variables[0].name, "КэшДанных");
+        assert!(layout.variables[0].is_export);
+        assert_eq!(layout.variables[0].directive.as_deref(), Some("НаКлиенте"));
+        assert_eq!(layout.variables[1].name, "Счетчик");
+        assert!(!layout.variables[1].is_export);
+        assert_eq!(layout.variables[1].line, 2);
+    }
+
+    #[test]
+    fn test_strip_comment_keeps_strings() {
+        assert_eq!(strip_comment("А = \"http://x\"; // c"), "А = \"http://x\"; ");
+        assert_eq!(strip_comment("Перем А;"), "Перем А;");
+    }
+}
@@ -0,0 +1,339 @@
+//! Query texts embedded in BSL code as string literals:
+//!
+//! ```bsl
+//! Запрос.Текст =
+//!     "ВЫБРАТЬ
+//!     |    Остатки.Номенклатура,
+//!     |    Остатки.КоличествоОстаток
+//!     |ИЗ
+//!     |    РегистрНакопления.ТоварыНаСкладах.Остатки(&Период) КАК Остатки";
+//! ```
+//!
+//! Literals are read from the source text, like the module layout. A literal is a query when
+//! it starts with `ВЫБРАТЬ` / `SELECT` or reads metadata tables (a fragment like
+//! `" ИЗ Справочник.Валюты КАК Валюты"` counts too).
+//!
+//! The same literals name methods called by name: `Новый ОписаниеОповещения("ПослеВыбора", ЭтотОбъект)`,
+//! `Выполнить("ОбменСБанком.Отправить(Параметры)")` — see `extract_method_refs`.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BslQuery {
+    pub start_line: u32, // 1-based, line of the opening quote
+    pub end_line: u32,   // 1-based, line of the closing quote
+    pub tables: Vec<QueryTable>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryTable {
+    /// As written in the query: `РегистрНакопления.ТоварыНаСкладах.Остатки`.
+    pub name: String,
+    /// Metadata object the table belongs to: `AccumulationRegister.ТоварыНаСкладах`.
+    pub object: String,
+    /// Virtual table or tabular section after the object name: `Остатки`, `Товары`.
+    pub part: Option<String>,
+    pub alias: Option<String>,
+    /// Fields read through the alias (`Остатки.Номенклатура` → `Номенклатура`), in order of appearance.
+    pub fields: Vec<String>,
+}
+
+/// Query language table types (Russian and English) → metadata object type.
+const QUERY_TABLE_TYPES: &[(&str, &str, &str)] = &[
+    ("справочник", "catalog", "Catalog"),
+    ("документ", "document", "Document"),
+    ("журналдокументов", "documentjournal", "DocumentJournal"),
+    ("перечисление", "enum", "Enum"),
+    ("планвидовхарактеристик", "chartofcharacteristictypes", "ChartOfCharacteristicTypes"),
+    ("плансчетов", "chartofaccounts", "ChartOfAccounts"),
+    ("планвидоврасчета", "chartofcalculationtypes", "ChartOfCalculationTypes"),
+    ("регистрсведений", "informationregister", "InformationRegister"),
+    ("регистрнакопления", "accumulationregister", "AccumulationRegister"),
+    ("регистрбухгалтерии", "accountingregister", "AccountingRegister"),
+    ("регистррасчета", "calculationregister", "CalculationRegister"),
+    ("бизнеспроцесс", "businessprocess", "BusinessProcess"),
+    ("задача", "task", "Task"),
+    ("планобмена", "exchangeplan", "ExchangePlan"),
+    ("константа", "constant", "Constant"),
+    ("критерийотбора", "filtercriterion", "FilterCriterion"),
+    ("последовательность", "sequence", "Sequence"),
+];
+
+/// Metadata object type for a query table type: `РегистрНакопления` → `AccumulationRegister`.
+pub fn query_table_type(word: &str) -> Option<&'static str> {
+    let lower = word.to_lowercase();
+    QUERY_TABLE_TYPES
+        .iter()
+        .find(|(ru, en, _)| *ru == lower || *en == lower)
+        .map(|(_, _, obj_type)| *obj_type)
+}
+
+/// String literals of a module as (start line, end line, text). `""` is unescaped and the
+/// leading `|` of continuation lines is dropped. Text in `//` comments is skipped.
+fn string_literals(source: &str) -> Vec<(u32, u32, String)> {
+    let mut literals = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1u32;
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '"' => {
+                let start_line = line;
+                let mut text = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            text.push('"');
+                        }
+                        '"' => break,
+                        '\n' => {
+                            line += 1;
+                            while chars.peek().is_some_and(|&c| c == ' ' || c == '\t' || c == '\r') {
+                                chars.next();
+                            }
+                            // An unterminated literal: stop at the end of its line
+                            if chars.peek() != Some(&'|') {
+                                break;
+                            }
+                            chars.next();
+                            text.push('\n');
+                        }
+                        _ => text.push(c),
+                    }
+                }
+                literals.push((start_line, line, text));
+            }
+            _ => {}
+        }
+    }
+    literals
+}
+
+/// Query text split into words (dotted names kept whole: `Т.Поле`, `&Период`) and punctuation.
+/// Query comments and string constants are dropped.
+fn query_tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '/' && chars.peek() == Some(&'/') {
+            while chars.peek().is_some_and(|&c| c != '\n') {
+                chars.next();
+            }
+        } else if c == '"' {
+            while chars.next().is_some_and(|c| c != '"') {}
+        } else if c.is_alphanumeric() || c == '_' || c == '&' {
+            let mut word = c.to_string();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(word.trim_end_matches('.').to_string());
+        } else if !c.is_whitespace() {
+            tokens.push(c.to_string());
+        }
+    }
+    tokens
+}
+
+/// Metadata tables read by one statement of a query (tokens between `;`), with the fields
+/// each one is read through its alias.
+fn statement_tables(tokens: &[String]) -> Vec<QueryTable> {
+    let mut tables: Vec<QueryTable> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let Some(prev) = i.checked_sub(1).map(|p| tokens[p].to_uppercase()) else { continue };
+        if !matches!(prev.as_str(), "ИЗ" | "FROM" | "СОЕДИНЕНИЕ" | "JOIN" | ",") {
+            continue;
+        }
+        let parts: Vec<&str> = token.split('.').collect();
+        let (Some(obj_type), Some(name)) = (query_table_type(parts[0]), parts.get(1)) else { continue };
+
+        // Skip virtual table parameters, then read `КАК Алиас`
+        let mut j = i + 1;
+        if tokens.get(j).map(String::as_str) == Some("(") {
+            let mut depth = 0;
+            while let Some(t) = tokens.get(j) {
+                match t.as_str() {
+                    "(" => depth += 1,
+                    ")" => depth -= 1,
+                    _ => {}
+                }
+                j += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+        let alias = match tokens.get(j).map(|t| t.to_uppercase()) {
+            Some(kw) if kw == "КАК" || kw == "AS" => tokens.get(j + 1).cloned(),
+            _ => None,
+        };
+        tables.push(QueryTable {
+            name: token.clone(),
+            object: format!("{}.{}", obj_type, name),
+            part: parts.get(2).map(|p| p.to_string()),
+            alias,
+            fields: Vec::new(),
+        });
+    }
+
+    for token in tokens {
+        let Some((alias, rest)) = token.split_once('.') else { continue };
+        let field = rest.split('.').next().unwrap_or(rest);
+        let alias = alias.to_lowercase();
+        let Some(table) = tables.iter_mut().find(|t| t.alias.as_ref().is_some_and(|a| a.to_lowercase() == alias)) else {
+            continue;
+        };
+        if !table.fields.iter().any(|f| f.to_lowercase() == field.to_lowercase()) {
+            table.fields.push(field.to_string());
+        }
+    }
+    tables
+}
+
+/// Query texts of a module with the metadata tables and fields they read.
+pub fn extract_queries(source: &str) -> Vec<BslQuery> {
+    let mut queries = Vec::new();
+    for (start_line, end_line, text) in string_literals(source) {
+        let tokens = query_tokens(&text);
+        // `"Выбрать файл"` is a message, `"ВЫБРАТЬ 1 КАК Поле"` is a query
+        let upper = |t: &String| t.to_uppercase();
+        let starts_select = tokens.first().map(upper).is_some_and(|t| t == "ВЫБРАТЬ" || t == "SELECT")
+            && tokens.iter().map(upper).any(|t| matches!(t.as_str(), "ИЗ" | "FROM" | "КАК" | "AS"));
+        let tables: Vec<QueryTable> = tokens
+            .split(|t| t == ";")
+            .flat_map(statement_tables)
+            .collect();
+        if starts_select || !tables.is_empty() {
+            queries.push(BslQuery { start_line, end_line, tables });
+        }
+    }
+    queries
+}
+
+/// Method names the string literals of a module may call, lowercased, each once:
+/// a literal that is a name (`"ПослеВыбора"`, `"ОбменСБанком.Отправить"`) gives its last part,
+/// and every name followed by `(` in a literal with code (`"ОбменСБанком.Отправить(Параметры)"`).
+/// The literal is taken whatever it is passed to, so some of the names are not methods at all.
+pub fn extract_method_refs(source: &str) -> Vec<String> {
+    let is_name = |s: &str| {
+        s.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+            && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+    };
+    let mut names: Vec<String> = Vec::new();
+    let mut add = |name: &str| {
+        let name = name.to_lowercase();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    };
+    for (_, _, text) in string_literals(source) {
+        let text = text.trim();
+        if text.split('.').all(is_name) {
+            add(text.rsplit('.').next().unwrap_or(text));
+            continue;
+        }
+        for (pos, _) in text.match_indices('(') {
+            let before = text[..pos].trim_end();
+            let start = before
+                .char_indices()
+                .rev()
+                .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+                .last()
+                .map_or(before.len(), |(i, _)| i);
+            if is_name(&before[start..]) {
+                add(&before[start..]);
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_multiline_query() {
+        let code = "\
+Функция Остатки(Период) Экспорт
+	// \"ВЫБРАТЬ в комментарии\"
+	Запрос = Новый Запрос;
+	Запрос.Текст =
+	\"ВЫБРАТЬ
+	|	Остатки.Номенклатура КАК Номенклатура,
+	|	Остатки.КоличествоОстаток,
+	|	Номенклатура.Наименование // \"\"комментарий\"\"
+	|ИЗ
+	|	РегистрНакопления.ТоварыНаСкладах.Остатки(&Период, Склад = &Склад) КАК Остатки
+	|		ЛЕВОЕ СОЕДИНЕНИЕ Справочник.Номенклатура КАК Номенклатура
+	|		ПО Остатки.Номенклатура = Номенклатура.Ссылка
+	|ГДЕ
+	|	Номенклатура.Вид = \"\"Товар\"\"
+	|;
+	|ВЫБРАТЬ Т.Код ИЗ Справочник.Валюты КАК Т\";
+	Сообщить(\"Выбрать файл\");
+	Возврат Запрос.Выполнить();
+КонецФункции
+";
+        let queries = extract_queries(code);
+        assert_eq!(queries.len(), 1);
+        let q = &queries[0];
+        assert_eq!((q.start_line, q.end_line), (5, 16));
+
+        let tables: Vec<(&str, &str, Option<&str>, Option<&str>)> = q
+            .tables
+            .iter()
+            .map(|t| (t.name.as_str(), t.object.as_str(), t.part.as_deref(), t.alias.as_deref()))
+            .collect();
+        assert_eq!(
+            tables,
+            vec![
+                ("РегистрНакопления.ТоварыНаСкладах.Остатки", "AccumulationRegister.ТоварыНаСкладах", Some("Остатки"), Some("Остатки")),
+                ("Справочник.Номенклатура", "Catalog.Номенклатура", None, Some("Номенклатура")),
+                ("Справочник.Валюты", "Catalog.Валюты", None, Some("Т")),
+            ]
+        );
+        assert_eq!(q.tables[0].fields, vec!["Номенклатура", "КоличествоОстаток"]);
+        assert_eq!(q.tables[1].fields, vec!["Наименование", "Ссылка", "Вид"]);
+        assert_eq!(q.tables[2].fields, vec!["Код"]);
+    }
+
+    #[test]
+    fn test_query_fragments_and_plain_strings() {
+        let code = "\
+Текст = \"ВЫБРАТЬ 1 КАК Поле\";
+Текст = Текст + \" ИЗ Document.Заказ AS Д\";
+Имя = \"Справочник.Валюты\";
+";
+        let queries = extract_queries(code);
+        assert_eq!(queries.len(), 2);
+        assert!(queries[0].tables.is_empty());
+        assert_eq!(queries[1].tables[0].object, "Document.Заказ");
+        assert_eq!(queries[1].start_line, 2);
+    }
+
+    #[test]
+    fn test_method_refs_from_literals() {
+        let code = "\
+Оповещение = Новый ОписаниеОповещения(\"ПослеВыбораФайла\", ЭтотОбъект);
+ПодключитьОбработчикОжидания(\"Подключаемый_ОбновитьСтатус\", 1, Истина);
+Выполнить(\"ОбменСБанком.ОтправитьПлатежи(Параметры, Отказ)\");
+ОбщегоНазначения.ВыполнитьМетодКонфигурации(\"ОбменСБанком.ПолучитьВыписку\", Параметры);
+// Выполнить(\"Закомментировано()\")
+Сообщить(\"Не удалось выбрать файл: \" + ОписаниеОшибки());
+Текст = \"ВЫБРАТЬ ЕСТЬNULL(Т.Сумма, 0) ИЗ Документ.Заказ КАК Т\";
+Оповещение = Новый ОписаниеОповещения(\"послевыборафайла\", ЭтотОбъект);
+";
+        assert_eq!(
+            extract_method_refs(code),
+            vec!["послевыборафайла", "подключаемый_обновитьстатус", "отправитьплатежи", "получитьвыписку", "естьnull"]
+        );
+    }
+}
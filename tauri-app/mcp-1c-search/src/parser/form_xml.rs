@@ -0,0 +1,609 @@
+//! Managed form description (`Ext/Form.xml` of an object or common form): form attributes,
+//! commands, items with their data paths, and event handler bindings. 1C:EDT keeps the same
+//! form in `Form.form` (see `parse_edt_form`).
+//!
+//! ```xml
+//! <Form>
+//!   <Events><Event name="OnCreateAtServer">ПриСозданииНаСервере</Event></Events>
+//!   <ChildItems>
+//!     <InputField name="Контрагент" id="5">
+//!       <DataPath>Объект.Контрагент</DataPath>
+//!       <Events><Event name="OnChange">КонтрагентПриИзменении</Event></Events>
+//!     </InputField>
+//!   </ChildItems>
+//!   <Attributes>
+//!     <Attribute name="Объект" id="1">
+//!       <Type><v8:Type>cfg:DocumentObject.Заказ</v8:Type></Type>
+//!       <MainAttribute>true</MainAttribute>
+//!     </Attribute>
+//!   </Attributes>
+//!   <Commands>
+//!     <Command name="Заполнить" id="1"><Action>Заполнить</Action></Command>
+//!   </Commands>
+//! </Form>
+//! ```
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use super::metadata_xml::format_type_name;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormAttribute {
+    pub name: String,
+    /// Value types without namespace prefixes, comma-separated: `DocumentObject.Заказ`
+    /// (see `metadata_xml::format_type_name`).
+    pub types: String,
+    /// The main form attribute (`Объект`, `Список`, `Запись`).
+    pub is_main: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormCommand {
+    pub name: String,
+    /// Handler procedure of the command.
+    pub action: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormElement {
+    pub name: String,
+    /// Item kind: `InputField`, `Table`, `UsualGroup`, `Button`, ...
+    pub kind: String,
+    pub data_path: Option<String>,
+    /// Enclosing item (group, table, command bar), `None` at the top level.
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormEvent {
+    /// Item the handler is attached to, `None` for events of the form itself.
+    pub element: Option<String>,
+    /// Event name as stored in the dump: `OnChange`, `OnCreateAtServer`.
+    pub event: String,
+    pub handler: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormStructure {
+    pub attributes: Vec<FormAttribute>,
+    pub commands: Vec<FormCommand>,
+    /// Items in document order.
+    pub elements: Vec<FormElement>,
+    pub events: Vec<FormEvent>,
+}
+
+/// Russian name of a form/item event, as shown in the Designer.
+pub fn event_name_ru(event: &str) -> Option<&'static str> {
+    Some(match event {
+        "OnCreateAtServer" => "ПриСозданииНаСервере",
+        "OnOpen" => "ПриОткрытии",
+        "BeforeClose" => "ПередЗакрытием",
+        "OnClose" => "ПриЗакрытии",
+        "OnReadAtServer" => "ПриЧтенииНаСервере",
+        "BeforeWrite" => "ПередЗаписью",
+        "BeforeWriteAtServer" => "ПередЗаписьюНаСервере",
+        "OnWriteAtServer" => "ПриЗаписиНаСервере",
+        "AfterWriteAtServer" => "ПослеЗаписиНаСервере",
+        "AfterWrite" => "ПослеЗаписи",
+        "FillCheckProcessingAtServer" => "ОбработкаПроверкиЗаполненияНаСервере",
+        "NotificationProcessing" => "ОбработкаОповещения",
+        "ChoiceProcessing" => "ОбработкаВыбора",
+        "ExternalEvent" => "ВнешнееСобытие",
+        "OnLoadDataFromSettingsAtServer" => "ПриЗагрузкеДанныхИзНастроекНаСервере",
+        "BeforeLoadDataFromSettingsAtServer" => "ПередЗагрузкойДанныхИзНастроекНаСервере",
+        "OnSaveDataInSettingsAtServer" => "ПриСохраненииДанныхВНастройкахНаСервере",
+        "OnChange" => "ПриИзменении",
+        "StartChoice" => "НачалоВыбора",
+        "Clearing" => "Очистка",
+        "AutoComplete" => "АвтоПодбор",
+        "TextEditEnd" => "ОкончаниеВводаТекста",
+        "Opening" => "Открытие",
+        "Click" => "Нажатие",
+        "Selection" => "Выбор",
+        "OnActivateRow" => "ПриАктивизацииСтроки",
+        "BeforeAddRow" => "ПередНачаломДобавления",
+        "BeforeRowChange" => "ПередНачаломИзменения",
+        "BeforeDeleteRow" => "ПередУдалением",
+        "AfterDeleteRow" => "ПослеУдаления",
+        "OnStartEdit" => "ПриНачалеРедактирования",
+        "OnEditEnd" => "ПриОкончанииРедактирования",
+        "BeforeEditEnd" => "ПередОкончаниемРедактирования",
+        "OnCurrentPageChange" => "ПриСменеСтраницы",
+        _ => return None,
+    })
+}
+
+/// An open tag on the parser stack.
+struct Frame {
+    name: String,
+    /// Index into `elements` when this tag is a form item.
+    element: Option<usize>,
+    /// Accumulated text content.
+    text: String,
+    /// `name` attribute (events, attributes, commands).
+    name_attr: Option<String>,
+}
+
+/// Parse a `Form.xml` document. Unknown or malformed parts are skipped.
+pub fn parse_form_xml(xml: &str) -> FormStructure {
+    let mut form = FormStructure::default();
+    let mut stack: Vec<Frame> = Vec::new();
+
+    let mut reader = Reader::from_str(xml);
+    loop {
+        let (start, empty) = match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(Event::Start(e)) => (e, false),
+            Ok(Event::Empty(e)) => (e, true),
+            Ok(Event::Text(t)) => {
+                if let (Some(frame), Ok(text)) = (stack.last_mut(), t.unescape()) {
+                    frame.text.push_str(&text);
+                }
+                continue;
+            }
+            Ok(Event::End(_)) => {
+                close_frame(&mut form, &mut stack);
+                continue;
+            }
+            Ok(_) => continue,
+        };
+
+        let name = String::from_utf8_lossy(start.local_name().as_ref()).into_owned();
+        let name_attr = start
+            .try_get_attribute("name")
+            .ok()
+            .flatten()
+            .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()));
+        let parent = stack.last().map(|f| f.name.as_str());
+        let is_element = match parent {
+            Some("ChildItems") => true,
+            // The command bar of the form itself sits outside ChildItems
+            Some("Form") => name == "AutoCommandBar",
+            _ => false,
+        };
+        let mut frame = Frame { name, element: None, text: String::new(), name_attr };
+        if is_element {
+            if let Some(element_name) = frame.name_attr.clone() {
+                let enclosing = stack.iter().rev().find_map(|f| f.element);
+                form.elements.push(FormElement {
+                    name: element_name,
+                    kind: frame.name.clone(),
+                    data_path: None,
+                    parent: enclosing.map(|i| form.elements[i].name.clone()),
+                });
+                frame.element = Some(form.elements.len() - 1);
+            }
+        }
+        match (parent, frame.name.as_str()) {
+            (Some("Attributes"), "Attribute") if in_form_section(&stack) => {
+                if let Some(attr_name) = &frame.name_attr {
+                    form.attributes.push(FormAttribute {
+                        name: attr_name.clone(),
+                        types: String::new(),
+                        is_main: false,
+                    });
+                }
+            }
+            (Some("Commands"), "Command") if in_form_section(&stack) => {
+                if let Some(cmd_name) = &frame.name_attr {
+                    form.commands.push(FormCommand { name: cmd_name.clone(), action: None });
+                }
+            }
+            _ => {}
+        }
+        if !empty {
+            stack.push(frame);
+        }
+    }
+    form
+}
+
+/// Pop the closed tag and record what its text content means in its position.
+fn close_frame(form: &mut FormStructure, stack: &mut Vec<Frame>) {
+    let Some(frame) = stack.pop() else { return };
+    let text = frame.text.trim().to_string();
+    let names: Vec<&str> = stack.iter().rev().take(3).map(|f| f.name.as_str()).collect();
+    match (frame.name.as_str(), names.as_slice()) {
+        ("DataPath", [_, ..]) => {
+            if let Some(i) = stack.last().and_then(|f| f.element) {
+                if !text.is_empty() {
+                    form.elements[i].data_path = Some(text);
+                }
+            }
+        }
+        ("Event", ["Events", owner, ..]) if !text.is_empty() => {
+            let Some(event) = frame.name_attr else { return };
+            let owner_frame = &stack[stack.len() - 2];
+            let element = match owner_frame.element {
+                Some(i) => Some(form.elements[i].name.clone()),
+                None if *owner == "Form" => None,
+                None => return,
+            };
+            form.events.push(FormEvent { element, event, handler: text });
+        }
+        ("Type", ["Type", "Attribute", "Attributes"]) => {
+            if let Some(attr) = form.attributes.last_mut() {
+                if !attr.types.is_empty() {
+                    attr.types.push_str(", ");
+                }
+                attr.types.push_str(&format_type_name(&text));
+            }
+        }
+        ("MainAttribute", ["Attribute", "Attributes", ..]) => {
+            if let Some(attr) = form.attributes.last_mut() {
+                attr.is_main = text == "true";
+            }
+        }
+        ("Action", ["Command", "Commands", ..]) if !text.is_empty() => {
+            if let Some(cmd) = form.commands.last_mut() {
+                cmd.action = Some(text);
+            }
+        }
+        _ => {}
+    }
+}
+/// Whether the stack top is a direct section of the form (`Form/Attributes`, `Form/Commands`),
+/// not a nested list such as table columns.
+fn in_form_section(stack: &[Frame]) -> bool {
+    stack.len() >= 2 && stack[stack.len() - 2].name == "Form"
+}
+
+/// Item kind of an EDT form item: `<type>` for generic fields, groups and decorations
+/// (`InputField`, `UsualGroup`, `LabelDecoration`), the `xsi:type` class otherwise (`Table`, `Button`).
+fn edt_element_kind(class: &str, type_text: &str) -> String {
+    match class {
+        "FormField" | "FormGroup" => type_text.to_string(),
+        "Decoration" => format!("{}Decoration", type_text),
+        _ => class.to_string(),
+    }
+}
+
+/// Parse a 1C:EDT form (`Forms/<Форма>/Form.form`) into the structure `parse_form_xml` returns.
+///
+/// ```xml
+/// <form:Form xmlns:form="http://g5.1c.ru/v8/dt/form">
+///   <items xsi:type="form:FormField">
+///     <name>Контрагент</name>
+///     <dataPath xsi:type="form:DataPath"><segments>Объект.Контрагент</segments></dataPath>
+///     <handlers><event>OnChange</event><name>КонтрагентПриИзменении</name></handlers>
+///     <type>InputField</type>
+///   </items>
+///   <handlers><event>OnCreateAtServer</event><name>ПриСозданииНаСервере</name></handlers>
+///   <attributes><name>Объект</name><valueType><types>DocumentObject.Заказ</types></valueType><main>true</main></attributes>
+///   <formCommands><name>Заполнить</name><action><handler><name>Заполнить</name></handler></action></formCommands>
+/// </form:Form>
+/// ```
+pub fn parse_edt_form(xml: &str) -> FormStructure {
+    let mut form = FormStructure::default();
+    // (tag, index into `elements` when the tag is a form item)
+    let mut stack: Vec<(String, Option<usize>)> = Vec::new();
+    // `xsi:type` class of every item, by index into `elements`
+    let mut classes: Vec<String> = Vec::new();
+    // (event, handler) of the open <handlers> block
+    let mut binding: (String, String) = Default::default();
+
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(Event::Start(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                let depth = stack.len();
+                // Items sit in the form itself, in other items and in the form's command bar
+                let container = depth == 1 || stack.last().is_some_and(|(_, el)| el.is_some());
+                let mut element = None;
+                if (name == "items" && container) || (depth == 1 && name == "autoCommandBar") {
+                    let class = match name.as_str() {
+                        "autoCommandBar" => "AutoCommandBar".to_string(),
+                        _ => e
+                            .try_get_attribute("xsi:type")
+                            .ok()
+                            .flatten()
+                            .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+                            .map(|v| v.rsplit(':').next().unwrap_or_default().to_string())
+                            .unwrap_or_default(),
+                    };
+                    let enclosing = stack.iter().rev().find_map(|(_, el)| *el);
+                    form.elements.push(FormElement {
+                        name: String::new(),
+                        kind: class.clone(),
+                        data_path: None,
+                        parent: enclosing.map(|i| form.elements[i].name.clone()),
+                    });
+                    classes.push(class);
+                    element = Some(form.elements.len() - 1);
+                }
+                match (depth, name.as_str()) {
+                    (1, "attributes") => form.attributes.push(FormAttribute {
+                        name: String::new(),
+                        types: String::new(),
+                        is_main: false,
+                    }),
+                    (1, "formCommands") => form.commands.push(FormCommand { name: String::new(), action: None }),
+                    (_, "handlers") => binding = Default::default(),
+                    _ => {}
+                }
+                stack.push((name, element));
+            }
+            Ok(Event::End(_)) => {
+                let Some((name, _)) = stack.pop() else { break };
+                if name != "handlers" || binding.1.is_empty() {
+                    continue;
+                }
+                let element = match stack.last() {
+                    Some((_, Some(i))) => Some(form.elements[*i].name.clone()),
+                    _ if stack.len() == 1 => None,
+                    _ => continue,
+                };
+                let (event, handler) = std::mem::take(&mut binding);
+                form.events.push(FormEvent { element, event, handler });
+            }
+            Ok(Event::Text(t)) => {
+                let Ok(text) = t.unescape() else { continue };
+                let text = text.trim().to_string();
+                let n = stack.len();
+                let tail = |k: usize| stack.get(n.wrapping_sub(k)).map(|(tag, _)| tag.as_str()).unwrap_or("");
+                // The item this tag (or its parent tag) belongs to
+                let owner = |k: usize| stack.get(n.wrapping_sub(k)).and_then(|(_, el)| *el);
+                match (tail(2), tail(1)) {
+                    ("handlers", "event") => binding.0 = text,
+                    ("handlers", "name") => binding.1 = text,
+                    (_, "name") if owner(2).is_some() => form.elements[owner(2).unwrap_or_default()].name = text,
+                    (_, "type") if owner(2).is_some() => {
+                        let i = owner(2).unwrap_or_default();
+                        form.elements[i].kind = edt_element_kind(&classes[i], &text);
+                    }
+                    ("dataPath", "segments") => {
+                        if let Some(i) = owner(3) {
+                            let path = form.elements[i].data_path.get_or_insert_with(String::new);
+                            if !path.is_empty() {
+                                path.push('.');
+                            }
+                            path.push_str(&text);
+                        }
+                    }
+                    ("attributes", "name") if n == 3 => {
+                        if let Some(attr) = form.attributes.last_mut() {
+                            attr.name = text;
+                        }
+                    }
+                    ("valueType", "types") if n == 4 && tail(3) == "attributes" => {
+                        if let Some(attr) = form.attributes.last_mut() {
+                            if !attr.types.is_empty() {
+                                attr.types.push_str(", ");
+                            }
+                            attr.types.push_str(&format_type_name(&text));
+                        }
+                    }
+                    ("attributes", "main") if n == 3 => {
+                        if let Some(attr) = form.attributes.last_mut() {
+                            attr.is_main = text == "true";
+                        }
+                    }
+                    ("formCommands", "name") if n == 3 => {
+                        if let Some(cmd) = form.commands.last_mut() {
+                            cmd.name = text;
+                        }
+                    }
+                    // formCommands / action / handler / name
+                    (_, "name") if n >= 4 && tail(n - 1) == "formCommands" && stack[2].0 == "action" => {
+                        if let Some(cmd) = form.commands.last_mut() {
+                            cmd.action = Some(text).filter(|a| !a.is_empty());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    form.elements.retain(|e| !e.name.is_empty());
+    form.attributes.retain(|a| !a.name.is_empty());
+    form.commands.retain(|c| !c.name.is_empty());
+    form
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Form xmlns="http://v8.1c.ru/8.3/xcf/logform" xmlns:v8="http://v8.1c.ru/8.1/data/core" version="2.16">
+	<AutoCommandBar name="ФормаКоманднаяПанель" id="-1">
+		<ChildItems>
+			<Button name="ФормаЗаполнить" id="20">
+				<CommandName>Form.Command.Заполнить</CommandName>
+			</Button>
+		</ChildItems>
+	</AutoCommandBar>
+	<Events>
+		<Event name="OnCreateAtServer">ПриСозданииНаСервере</Event>
+	</Events>
+	<ChildItems>
+		<UsualGroup name="Шапка" id="1">
+			<ChildItems>
+				<InputField name="Контрагент" id="2">
+					<DataPath>Объект.Контрагент</DataPath>
+					<ContextMenu name="КонтрагентКонтекстноеМеню" id="3"/>
+					<Events>
+						<Event name="OnChange">КонтрагентПриИзменении</Event>
+					</Events>
+				</InputField>
+			</ChildItems>
+		</UsualGroup>
+		<Table name="Товары" id="4">
+			<DataPath>Объект.Товары</DataPath>
+			<ChildItems>
+				<InputField name="ТоварыКоличество" id="5">
+					<DataPath>Объект.Товары.Количество</DataPath>
+				</InputField>
+			</ChildItems>
+		</Table>
+	</ChildItems>
+	<Attributes>
+		<Attribute name="Объект" id="1">
+			<Type>
+				<v8:Type>cfg:DocumentObject.Заказ</v8:Type>
+			</Type>
+			<MainAttribute>true</MainAttribute>
+		</Attribute>
+		<Attribute name="Итоги" id="2">
+			<Type><v8:Type>v8:ValueTable</v8:Type></Type>
+			<Columns>
+				<Column name="Сумма" id="1"/>
+			</Columns>
+		</Attribute>
+	</Attributes>
+	<Commands>
+		<Command name="Заполнить" id="1">
+			<Title><v8:item><v8:content>Заполнить &amp; провести</v8:content></v8:item></Title>
+			<Action>Заполнить</Action>
+		</Command>
+	</Commands>
+</Form>"#;
+
+    #[test]
+    fn test_parse_form_xml() {
+        let form = parse_form_xml(FORM);
+
+        let elements: Vec<(&str, &str, Option<&str>)> = form
+            .elements
+            .iter()
+            .map(|e| (e.name.as_str(), e.kind.as_str(), e.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            elements,
+            vec![
+                ("ФормаКоманднаяПанель", "AutoCommandBar", None),
+                ("ФормаЗаполнить", "Button", Some("ФормаКоманднаяПанель")),
+                ("Шапка", "UsualGroup", None),
+                ("Контрагент", "InputField", Some("Шапка")),
+                ("Товары", "Table", None),
+                ("ТоварыКоличество", "InputField", Some("Товары")),
+            ]
+        );
+        assert_eq!(form.elements[3].data_path.as_deref(), Some("Объект.Контрагент"));
+        assert_eq!(form.elements[5].data_path.as_deref(), Some("Объект.Товары.Количество"));
+
+        assert_eq!(
+            form.events,
+            vec![
+                FormEvent { element: None, event: "OnCreateAtServer".into(), handler: "ПриСозданииНаСервере".into() },
+                FormEvent {
+                    element: Some("Контрагент".into()),
+                    event: "OnChange".into(),
+                    handler: "КонтрагентПриИзменении".into(),
+                },
+            ]
+        );
+
+        assert_eq!(form.attributes.len(), 2);
+        assert_eq!(form.attributes[0].types, "DocumentObject.Заказ");
+        assert_eq!(form.attributes[1].types, "ValueTable");
+        assert!(form.attributes[0].is_main);
+        assert!(!form.attributes[1].is_main);
+
+        assert_eq!(form.commands, vec![FormCommand { name: "Заполнить".into(), action: Some("Заполнить".into()) }]);
+    }
+
+    #[test]
+    fn test_parse_edt_form() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<form:Form xmlns:form="http://g5.1c.ru/v8/dt/form" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <items xsi:type="form:FormGroup">
+    <name>Шапка</name>
+    <id>1</id>
+    <items xsi:type="form:FormField">
+      <name>Контрагент</name>
+      <id>2</id>
+      <dataPath xsi:type="form:DataPath">
+        <segments>Объект.Контрагент</segments>
+      </dataPath>
+      <handlers>
+        <event>OnChange</event>
+        <name>КонтрагентПриИзменении</name>
+      </handlers>
+      <extendedTooltip>
+        <name>КонтрагентРасширеннаяПодсказка</name>
+      </extendedTooltip>
+      <type>InputField</type>
+    </items>
+    <type>UsualGroup</type>
+  </items>
+  <items xsi:type="form:Table">
+    <name>Товары</name>
+    <dataPath xsi:type="form:DataPath"><segments>Объект.Товары</segments></dataPath>
+  </items>
+  <autoCommandBar>
+    <name>ФормаКоманднаяПанель</name>
+    <id>-1</id>
+    <items xsi:type="form:Button">
+      <name>ФормаЗаполнить</name>
+      <type>CommandBarButton</type>
+    </items>
+  </autoCommandBar>
+  <handlers>
+    <event>OnCreateAtServer</event>
+    <name>ПриСозданииНаСервере</name>
+  </handlers>
+  <attributes>
+    <name>Объект</name>
+    <valueType>
+      <types>DocumentObject.Заказ</types>
+    </valueType>
+    <main>true</main>
+  </attributes>
+  <formCommands>
+    <name>Заполнить</name>
+    <action xsi:type="form:FormCommandHandlerContainer">
+      <handler>
+        <name>Заполнить</name>
+      </handler>
+    </action>
+  </formCommands>
+</form:Form>"#;
+        let form = parse_edt_form(xml);
+
+        let elements: Vec<(&str, &str, Option<&str>)> = form
+            .elements
+            .iter()
+            .map(|e| (e.name.as_str(), e.kind.as_str(), e.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            elements,
+            vec![
+                ("Шапка", "UsualGroup", None),
+                ("Контрагент", "InputField", Some("Шапка")),
+                ("Товары", "Table", None),
+                ("ФормаКоманднаяПанель", "AutoCommandBar", None),
+                ("ФормаЗаполнить", "Button", Some("ФормаКоманднаяПанель")),
+            ]
+        );
+        assert_eq!(form.elements[1].data_path.as_deref(), Some("Объект.Контрагент"));
+        assert_eq!(form.elements[2].data_path.as_deref(), Some("Объект.Товары"));
+
+        assert_eq!(
+            form.events,
+            vec![
+                FormEvent {
+                    element: Some("Контрагент".into()),
+                    event: "OnChange".into(),
+                    handler: "КонтрагентПриИзменении".into(),
+                },
+                FormEvent { element: None, event: "OnCreateAtServer".into(), handler: "ПриСозданииНаСервере".into() },
+            ]
+        );
+        assert_eq!(form.attributes.len(), 1);
+        assert_eq!(form.attributes[0].types, "DocumentObject.Заказ");
+        assert!(form.attributes[0].is_main);
+        assert_eq!(form.commands, vec![FormCommand { name: "Заполнить".into(), action: Some("Заполнить".into()) }]);
+    }
+
+    #[test]
+    fn test_event_name_ru() {
+        assert_eq!(event_name_ru("OnChange"), Some("ПриИзменении"));
+        assert_eq!(event_name_ru("OnSomethingNew"), None);
+    }
+}
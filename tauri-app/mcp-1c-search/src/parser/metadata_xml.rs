@@ -0,0 +1,1251 @@
+//! Streaming parsers for the metadata XML of a configuration dump: `Configuration.xml`,
+//! `ConfigDumpInfo.xml`, per-object descriptions such as `Catalogs/Валюты.xml` and
+//! predefined items (`Catalogs/Валюты/Ext/Predefined.xml`). 1C:EDT projects describe the same
+//! objects in `.mdo` files (`Catalogs/Валюты/Валюты.mdo`, `Configuration/Configuration.mdo`),
+//! read by the `parse_edt_*` functions into the same structures.
+//!
+//! ```xml
+//! <MetaDataObject>
+//!   <Catalog uuid="...">
+//!     <Properties><Name>Валюты</Name><Synonym>...</Synonym></Properties>
+//!     <ChildObjects>
+//!       <Attribute uuid="...">
+//!         <Properties>
+//!           <Name>Курс</Name>
+//!           <Type><v8:Type>xs:decimal</v8:Type><v8:NumberQualifiers>...</v8:NumberQualifiers></Type>
+//!           <Indexing>DontIndex</Indexing>
+//!           <FillChecking>ShowError</FillChecking>
+//!         </Properties>
+//!       </Attribute>
+//!       <TabularSection uuid="...">
+//!         <Properties><Name>Курсы</Name></Properties>
+//!         <ChildObjects><Attribute>...</Attribute></ChildObjects>
+//!       </TabularSection>
+//!       <Form>ФормаЭлемента</Form>
+//!       <Command uuid="..."><Properties><Name>Загрузить</Name></Properties></Command>
+//!     </ChildObjects>
+//!   </Catalog>
+//! </MetaDataObject>
+//! ```
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// Object-level properties kept verbatim: register kind, periodicity and write mode, linked
+/// charts, event subscription and scheduled job handlers. Values are stored as written in the
+/// dump (`Balance`, `Month`, `ChartOfAccounts.Хозрасчетный`, `CommonModule.ОбменДанными.ПриЗаписи`).
+pub const OBJECT_PROPERTIES: &[&str] = &[
+    "InformationRegisterPeriodicity", "WriteMode", "RegisterType",
+    "ChartOfAccounts", "Correspondence", "Periodicity", "ChartOfCalculationTypes",
+    "ActionPeriod", "BasePeriod", "Event", "Handler", "MethodName",
+];
+
+/// Common module flags: where the module is compiled, whether the client may call it, privileged
+/// mode and return value reuse (`DontUse`, `DuringRequest`, `DuringSession`).
+pub const COMMON_MODULE_FLAGS: &[&str] = &[
+    "Global", "ClientManagedApplication", "ClientOrdinaryApplication", "Server",
+    "ExternalConnection", "ServerCall", "Privileged", "ReturnValuesReuse",
+];
+
+/// Child elements of `<ChildObjects>` described like attributes (name, type, flags).
+const ATTRIBUTE_KINDS: &[&str] = &[
+    "Attribute", "Dimension", "Resource", "AccountingFlag",
+    "ExtDimensionAccountingFlag", "AddressingAttribute", "EnumValue",
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MdAttribute {
+    /// Element kind: `Attribute`, `Dimension`, `Resource`, ...
+    pub kind: String,
+    pub name: String,
+    /// Value type in 1C notation: `CatalogRef.Номенклатура`, `Number(15,2)`, `String(50)`;
+    /// composite types are comma-separated.
+    pub value_type: String,
+    pub synonym: String,
+    pub comment: String,
+    /// `Index`, `IndexWithAdditionalOrder`, `DontIndex`.
+    pub indexing: Option<String>,
+    /// `ShowError`, `DontCheck`.
+    pub fill_checking: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MdTabularSection {
+    pub name: String,
+    pub synonym: String,
+    pub comment: String,
+    pub attributes: Vec<MdAttribute>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MdObject {
+    pub name: String,
+    pub synonym: String,
+    pub comment: String,
+    pub attributes: Vec<MdAttribute>,
+    pub tabular_sections: Vec<MdTabularSection>,
+    pub forms: Vec<String>,
+    pub commands: Vec<String>,
+    /// Properties from `OBJECT_PROPERTIES` as (name, value).
+    pub properties: Vec<(String, String)>,
+    /// Common module flags from `COMMON_MODULE_FLAGS` as (name, value): (`ServerCall`, `true`).
+    pub module_flags: Vec<(String, String)>,
+    /// The object's own value type: defined types and charts of characteristic types.
+    pub value_type: String,
+    /// Registers a document writes records to: `AccumulationRegister.ТоварыНаСкладах`.
+    pub register_records: Vec<String>,
+    /// Values of an enumeration (kind `EnumValue`; only name, synonym and comment are set).
+    pub enum_values: Vec<MdAttribute>,
+    /// Objects included in a subsystem: `Catalog.Контрагенты`, `CommonModule.ПродажиСервер`.
+    pub content: Vec<String>,
+    /// Names of nested subsystems.
+    pub subsystems: Vec<String>,
+    /// Methods of the service module the platform calls: handlers of HTTP service URL template
+    /// methods and procedures of web service operations, each once.
+    pub service_handlers: Vec<String>,
+}
+
+/// Predefined item of a catalog or chart from `Ext/Predefined.xml` (`<predefined>` of an `.mdo`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PredefinedItem {
+    pub name: String,
+    /// Code of a catalog item or account code (`50.01`) of a chart of accounts.
+    pub code: String,
+    pub description: String,
+    pub is_folder: bool,
+    /// Name of the enclosing predefined item in a hierarchy.
+    pub parent: Option<String>,
+}
+
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+/// `<v8:Type>` values and qualifiers of one `<Type>` block.
+#[derive(Default)]
+struct TypeSpec {
+    types: Vec<String>,
+    length: Option<String>,
+    digits: Option<String>,
+    fraction_digits: Option<String>,
+    date_fractions: Option<String>,
+}
+
+impl TypeSpec {
+    /// `xs:decimal` + `Digits 15, FractionDigits 2` → `Number(15,2)`; `cfg:CatalogRef.X` → `CatalogRef.X`.
+    fn format(&self) -> String {
+        self.types
+            .iter()
+            .map(|t| {
+                let bare = t.split_once(':').map_or(t.as_str(), |(_, rest)| rest);
+                match bare {
+                    "string" => match self.length.as_deref().filter(|len| *len != "0") {
+                        Some(len) => format!("String({})", len),
+                        None => "String".to_string(),
+                    },
+                    "decimal" => match (self.digits.as_deref(), self.fraction_digits.as_deref()) {
+                        (Some(d), Some(f)) if f != "0" => format!("Number({},{})", d, f),
+                        (Some(d), _) => format!("Number({})", d),
+                        _ => "Number".to_string(),
+                    },
+                    "boolean" => "Boolean".to_string(),
+                    "dateTime" => match self.date_fractions.as_deref() {
+                        Some("Date") => "Date".to_string(),
+                        Some("Time") => "Time".to_string(),
+                        _ => "DateTime".to_string(),
+                    },
+                    other => other.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// One type name without qualifiers: `cfg:CatalogRef.X` → `CatalogRef.X`, `xs:string` → `String`.
+pub fn format_type_name(name: &str) -> String {
+    TypeSpec { types: vec![name.to_string()], ..Default::default() }.format()
+}
+
+/// Reference type prefixes and the metadata object type they point to.
+const REFERENCE_TYPES: &[(&str, &str)] = &[
+    ("CatalogRef", "Catalog"),
+    ("DocumentRef", "Document"),
+    ("EnumRef", "Enum"),
+    ("ChartOfCharacteristicTypesRef", "ChartOfCharacteristicTypes"),
+    ("ChartOfAccountsRef", "ChartOfAccounts"),
+    ("ChartOfCalculationTypesRef", "ChartOfCalculationTypes"),
+    ("BusinessProcessRef", "BusinessProcess"),
+    ("TaskRef", "Task"),
+    ("ExchangePlanRef", "ExchangePlan"),
+    ("DefinedType", "DefinedType"),
+    ("Characteristic", "ChartOfCharacteristicTypes"),
+];
+
+/// Metadata object named by one formatted type: `CatalogRef.Контрагенты` → `Catalog.Контрагенты`.
+/// Primitive and platform types (`String(50)`, `ValueStorage`) yield `None`.
+pub fn referenced_object(type_name: &str) -> Option<String> {
+    let (kind, name) = type_name.trim().split_once('.')?;
+    let (_, obj_type) = REFERENCE_TYPES.iter().find(|(k, _)| *k == kind)?;
+    Some(format!("{}.{}", obj_type, name))
+}
+
+/// Item of `<ChildObjects>` being read.
+enum Item {
+    Attribute(MdAttribute),
+    TabularSection(MdTabularSection),
+    Command(String),
+}
+
+/// Parse a per-object description. Unknown elements are skipped; a malformed document
+/// yields whatever was read before the error.
+pub fn parse_object_xml(xml: &str) -> MdObject {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut object = MdObject::default();
+    let mut stack: Vec<String> = Vec::new();
+    // Open items; a tabular section may hold an attribute on top of it
+    let mut items: Vec<Item> = Vec::new();
+    let mut type_spec: Option<TypeSpec> = None;
+
+    loop {
+        let event = match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(e) => e,
+        };
+        match event {
+            Event::Start(e) => {
+                let name = local_name(&e);
+                if stack.last().map(String::as_str) == Some("ChildObjects") {
+                    match name.as_str() {
+                        kind if ATTRIBUTE_KINDS.contains(&kind) => {
+                            items.push(Item::Attribute(MdAttribute { kind: kind.to_string(), ..Default::default() }))
+                        }
+                        "TabularSection" => items.push(Item::TabularSection(MdTabularSection::default())),
+                        "Command" => items.push(Item::Command(String::new())),
+                        _ => {}
+                    }
+                }
+                if name == "Type" && stack.last().map(String::as_str) == Some("Properties") {
+                    type_spec = Some(TypeSpec::default());
+                }
+                stack.push(name);
+            }
+            Event::End(_) => {
+                let Some(name) = stack.pop() else { break };
+                let parent = stack.last().map(String::as_str);
+                if name == "Type" && parent == Some("Properties") {
+                    // MetaDataObject / DefinedType / Properties
+                    match (type_spec.take(), items.last_mut()) {
+                        (Some(spec), Some(Item::Attribute(attr))) => attr.value_type = spec.format(),
+                        (Some(spec), None) if stack.len() == 3 => object.value_type = spec.format(),
+                        _ => {}
+                    }
+                }
+                if parent != Some("ChildObjects") {
+                    continue;
+                }
+                let closes_item = match items.last() {
+                    Some(Item::Attribute(a)) => a.kind == name,
+                    Some(Item::TabularSection(_)) => name == "TabularSection",
+                    Some(Item::Command(_)) => name == "Command",
+                    None => false,
+                };
+                if !closes_item {
+                    continue;
+                }
+                match (items.pop(), items.last_mut()) {
+                    (Some(Item::Attribute(a)), _) if a.kind == "EnumValue" => object.enum_values.push(a),
+                    (Some(Item::Attribute(a)), Some(Item::TabularSection(ts))) => ts.attributes.push(a),
+                    (Some(Item::Attribute(a)), _) => object.attributes.push(a),
+                    (Some(Item::TabularSection(ts)), _) => object.tabular_sections.push(ts),
+                    (Some(Item::Command(c)), _) if !c.is_empty() => object.commands.push(c),
+                    _ => {}
+                }
+            }
+            Event::Text(t) => {
+                let Ok(text) = t.unescape() else { continue };
+                let text = text.trim().to_string();
+                let n = stack.len();
+                let tail = |k: usize| stack.get(n.wrapping_sub(k)).map(String::as_str).unwrap_or("");
+
+                // Type block: <Properties><Type><v8:Type>…</v8:Type><v8:…Qualifiers>…
+                if let Some(spec) = type_spec.as_mut() {
+                    match (tail(2), tail(1)) {
+                        ("Type", "Type") | ("Type", "TypeSet") => spec.types.push(text),
+                        ("StringQualifiers", "Length") => spec.length = Some(text),
+                        ("NumberQualifiers", "Digits") => spec.digits = Some(text),
+                        ("NumberQualifiers", "FractionDigits") => spec.fraction_digits = Some(text),
+                        ("DateQualifiers", "DateFractions") => spec.date_fractions = Some(text),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                if tail(2) == "ChildObjects" {
+                    match tail(1) {
+                        "Form" => object.forms.push(text),
+                        "Subsystem" => object.subsystems.push(text),
+                        _ => {}
+                    }
+                    continue;
+                }
+                // MetaDataObject / Document / Properties / RegisterRecords / Item
+                if n == 5 && tail(1) == "Item" {
+                    match tail(2) {
+                        "RegisterRecords" => object.register_records.push(text),
+                        "Content" => object.content.push(text),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // HTTPService / ChildObjects / URLTemplate / ChildObjects / Method / Properties / Handler,
+                // WebService / ChildObjects / Operation / Properties / ProcedureName
+                if tail(2) == "Properties"
+                    && matches!((tail(3), tail(1)), ("Method", "Handler") | ("Operation", "ProcedureName"))
+                {
+                    if !object.service_handlers.contains(&text) {
+                        object.service_handlers.push(text);
+                    }
+                    continue;
+                }
+
+                // Properties of the innermost open item, or of the object itself
+                // (MetaDataObject / Catalog / Properties / Name)
+                // (position of <Properties> in the stack, property name)
+                let (props_at, field) = if tail(2) == "Properties" {
+                    (n - 2, tail(1))
+                } else if tail(4) == "Properties" && tail(3) == "Synonym" && tail(1) == "content" {
+                    (n - 4, "Synonym")
+                } else {
+                    continue;
+                };
+                let is_object_props = props_at == 2;
+                let set_once = |target: &mut String, value: String| {
+                    if target.is_empty() {
+                        *target = value;
+                    }
+                };
+                match items.last_mut() {
+                    Some(Item::Attribute(a)) => match field {
+                        "Name" => a.name = text,
+                        "Synonym" => set_once(&mut a.synonym, text),
+                        "Comment" => a.comment = text,
+                        "Indexing" => a.indexing = Some(text),
+                        "FillChecking" => a.fill_checking = Some(text),
+                        _ => {}
+                    },
+                    Some(Item::TabularSection(ts)) => match field {
+                        "Name" => ts.name = text,
+                        "Synonym" => set_once(&mut ts.synonym, text),
+                        "Comment" => ts.comment = text,
+                        _ => {}
+                    },
+                    Some(Item::Command(c)) if field == "Name" => *c = text,
+                    Some(Item::Command(_)) => {}
+                    None if is_object_props => match field {
+                        "Name" => object.name = text,
+                        "Synonym" => set_once(&mut object.synonym, text),
+                        "Comment" => object.comment = text,
+                        f if OBJECT_PROPERTIES.contains(&f) => object.properties.push((f.to_string(), text)),
+                        f if COMMON_MODULE_FLAGS.contains(&f) => object.module_flags.push((f.to_string(), text)),
+                        _ => {}
+                    },
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    object
+}
+
+/// Predefined items in document order, parents before their children.
+///
+/// ```xml
+/// <PredefinedData>
+///   <Item id="..."><Name>Рубль</Name><Code>643</Code><Description>руб.</Description>
+///     <IsFolder>false</IsFolder><ChildItems><Item>...</Item></ChildItems></Item>
+/// </PredefinedData>
+/// ```
+pub fn parse_predefined_xml(xml: &str) -> Vec<PredefinedItem> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut items: Vec<PredefinedItem> = Vec::new();
+    // Indexes into `items` of the open <Item> elements
+    let mut open: Vec<usize> = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(Event::Start(e)) => {
+                let name = local_name(&e);
+                if name == "Item" {
+                    let parent = open.last().map(|&i| items[i].name.clone());
+                    items.push(PredefinedItem { parent, ..Default::default() });
+                    open.push(items.len() - 1);
+                }
+                stack.push(name);
+            }
+            Ok(Event::End(_)) => {
+                let closed = stack.pop();
+                if closed.as_deref() == Some("Item") {
+                    open.pop();
+                }
+            }
+            Ok(Event::Text(t)) if stack.len() >= 2 && stack[stack.len() - 2] == "Item" => {
+                let (Some(&i), Ok(text)) = (open.last(), t.unescape()) else { continue };
+                let text = text.trim().to_string();
+                match stack[stack.len() - 1].as_str() {
+                    "Name" => items[i].name = text,
+                    "Code" => items[i].code = text,
+                    "Description" => items[i].description = text,
+                    "IsFolder" => items[i].is_folder = text == "true",
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    items.retain(|item| !item.name.is_empty());
+    items
+}
+
+/// `(type, name)` of every top-level object listed in `<ChildObjects>` of `Configuration.xml`.
+pub fn parse_configuration_children(xml: &str) -> Vec<(String, String)> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut children = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(Event::Start(e)) => stack.push(local_name(&e)),
+            Ok(Event::End(_)) => {
+                stack.pop();
+            }
+            // MetaDataObject / Configuration / ChildObjects / <Type>
+            Ok(Event::Text(t)) if stack.len() == 4 && stack[2] == "ChildObjects" => {
+                if let Ok(text) = t.unescape() {
+                    let name = text.trim();
+                    if !name.is_empty() {
+                        children.push((stack[3].clone(), name.to_string()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    children
+}
+
+/// `name` of every `<Metadata>` entry of `ConfigDumpInfo.xml`: `Catalog.Валюты.Attribute.Курс`.
+pub fn parse_dump_info_names(xml: &str) -> Vec<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut names = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"Metadata" => {
+                if let Ok(Some(attr)) = e.try_get_attribute("name") {
+                    if let Ok(value) = attr.unescape_value() {
+                        names.push(value.into_owned());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+// ─── 1C:EDT (.mdo) ───────────────────────────────────────────────────────────
+//
+// ```xml
+// <mdclass:Catalog xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass" uuid="...">
+//   <name>Валюты</name>
+//   <synonym><key>ru</key><value>Валюты</value></synonym>
+//   <attributes uuid="...">
+//     <name>Курс</name>
+//     <type><types>Number</types><numberQualifiers><precision>15</precision><scale>4</scale></numberQualifiers></type>
+//     <indexing>Index</indexing>
+//   </attributes>
+//   <tabularSections uuid="..."><name>Курсы</name><attributes>...</attributes></tabularSections>
+//   <forms uuid="..."><name>ФормаЭлемента</name></forms>
+// </mdclass:Catalog>
+// ```
+
+/// Child elements of an `.mdo` object described like attributes → `MdAttribute::kind`.
+const EDT_ATTRIBUTE_KINDS: &[(&str, &str)] = &[
+    ("attributes", "Attribute"),
+    ("dimensions", "Dimension"),
+    ("resources", "Resource"),
+    ("accountingFlags", "AccountingFlag"),
+    ("extDimensionAccountingFlags", "ExtDimensionAccountingFlag"),
+    ("addressingAttributes", "AddressingAttribute"),
+    ("enumValues", "EnumValue"),
+];
+
+/// EDT type name → the Designer one `TypeSpec::format` understands: `Number` → `xs:decimal`.
+fn edt_type(name: &str) -> String {
+    match name {
+        "String" => "xs:string".to_string(),
+        "Number" => "xs:decimal".to_string(),
+        "Boolean" => "xs:boolean".to_string(),
+        "Date" => "xs:dateTime".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// `registerType` → `RegisterType`: EDT writes Designer property names in camelCase.
+fn pascal_case(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Parse an EDT object description (`Catalogs/Валюты/Валюты.mdo`) into the structure
+/// `parse_object_xml` returns for a Designer dump.
+pub fn parse_edt_object(xml: &str) -> MdObject {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut object = MdObject::default();
+    let mut stack: Vec<String> = Vec::new();
+    // Open items with the stack depth of their element; a tabular section may hold a column
+    let mut items: Vec<(usize, Item)> = Vec::new();
+    let mut type_spec: Option<TypeSpec> = None;
+    loop {
+        let event = match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(e) => e,
+        };
+        match event {
+            Event::Start(e) => {
+                let name = local_name(&e);
+                let depth = stack.len();
+                let in_section = matches!(items.last(), Some((d, Item::TabularSection(_))) if *d + 1 == depth);
+                if depth == 1 || in_section {
+                    match EDT_ATTRIBUTE_KINDS.iter().find(|(tag, _)| *tag == name) {
+                        Some((_, kind)) if depth == 1 || *kind == "Attribute" => items.push((
+                            depth,
+                            Item::Attribute(MdAttribute { kind: kind.to_string(), ..Default::default() }),
+                        )),
+                        _ if depth == 1 && name == "tabularSections" => {
+                            items.push((depth, Item::TabularSection(MdTabularSection::default())))
+                        }
+                        _ => {}
+                    }
+                }
+                // <type> of the object itself (depth 1) or of the innermost item
+                let owner_depth = items.last().map_or(0, |(d, _)| *d);
+                if name == "type" && depth == owner_depth + 1 {
+                    type_spec = Some(TypeSpec::default());
+                }
+                stack.push(name);
+            }
+            Event::End(_) => {
+                let Some(name) = stack.pop() else { break };
+                let depth = stack.len();
+                let owner_depth = items.last().map_or(0, |(d, _)| *d);
+                if name == "type" && depth == owner_depth + 1 {
+                    match (type_spec.take(), items.last_mut()) {
+                        (Some(spec), Some((_, Item::Attribute(attr)))) => attr.value_type = spec.format(),
+                        (Some(spec), None) => object.value_type = spec.format(),
+                        _ => {}
+                    }
+                }
+                if items.last().is_none_or(|(d, _)| *d != depth) {
+                    continue;
+                }
+                match (items.pop().map(|(_, item)| item), items.last_mut()) {
+                    (Some(Item::Attribute(a)), _) if a.kind == "EnumValue" => object.enum_values.push(a),
+                    (Some(Item::Attribute(a)), Some((_, Item::TabularSection(ts)))) => ts.attributes.push(a),
+                    (Some(Item::Attribute(a)), _) => object.attributes.push(a),
+                    (Some(Item::TabularSection(ts)), _) => object.tabular_sections.push(ts),
+                    _ => {}
+                }
+            }
+            Event::Text(t) => {
+                let Ok(text) = t.unescape() else { continue };
+                let text = text.trim().to_string();
+                let n = stack.len();
+                let tail = |k: usize| stack.get(n.wrapping_sub(k)).map(String::as_str).unwrap_or("");
+
+                if let Some(spec) = type_spec.as_mut() {
+                    match (tail(2), tail(1)) {
+                        ("type", "types") => spec.types.push(edt_type(&text)),
+                        ("stringQualifiers", "length") => spec.length = Some(text),
+                        ("numberQualifiers", "precision") => spec.digits = Some(text),
+                        ("numberQualifiers", "scale") => spec.fraction_digits = Some(text),
+                        ("dateQualifiers", "dateFractions") => spec.date_fractions = Some(text),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // Forms and commands are listed by name only: <forms><name>ФормаЭлемента</name>...
+                if n == 3 && tail(1) == "name" && matches!(tail(2), "forms" | "commands") {
+                    match tail(2) {
+                        "forms" => object.forms.push(text),
+                        _ => object.commands.push(text),
+                    }
+                    continue;
+                }
+
+                // <urlTemplates><methods><handler>…</handler></methods></urlTemplates>,
+                // <operations><procedureName>…</procedureName></operations>
+                if matches!((tail(2), tail(1)), ("methods", "handler") | ("operations", "procedureName")) {
+                    if !object.service_handlers.contains(&text) {
+                        object.service_handlers.push(text);
+                    }
+                    continue;
+                }
+
+                // Field of the innermost item (or of the object at depth 0): `<name>`, or the
+                // `<value>` of a localized `<synonym>`
+                let owner_depth = items.last().map_or(0, |(d, _)| *d);
+                let field = if n == owner_depth + 2 {
+                    tail(1)
+                } else if n == owner_depth + 3 && tail(2) == "synonym" && tail(1) == "value" {
+                    "synonym"
+                } else {
+                    continue;
+                };
+                let set_once = |target: &mut String, value: String| {
+                    if target.is_empty() {
+                        *target = value;
+                    }
+                };
+                match items.last_mut() {
+                    Some((_, Item::Attribute(a))) => match field {
+                        "name" => a.name = text,
+                        "synonym" => set_once(&mut a.synonym, text),
+                        "comment" => a.comment = text,
+                        "indexing" => a.indexing = Some(text),
+                        "fillChecking" => a.fill_checking = Some(text),
+                        _ => {}
+                    },
+                    Some((_, Item::TabularSection(ts))) => match field {
+                        "name" => ts.name = text,
+                        "synonym" => set_once(&mut ts.synonym, text),
+                        "comment" => ts.comment = text,
+                        _ => {}
+                    },
+                    Some((_, Item::Command(_))) => {}
+                    None => match field {
+                        "name" => object.name = text,
+                        "synonym" => set_once(&mut object.synonym, text),
+                        "comment" => object.comment = text,
+                        "registerRecords" => object.register_records.push(text),
+                        "content" => object.content.push(text),
+                        // `Subsystem.Продажи.Subsystem.Опт` or a bare name
+                        "subsystems" => object.subsystems.push(text.rsplit('.').next().unwrap_or("").to_string()),
+                        f => {
+                            let f = pascal_case(f);
+                            if OBJECT_PROPERTIES.contains(&f.as_str()) {
+                                object.properties.push((f, text));
+                            } else if COMMON_MODULE_FLAGS.contains(&f.as_str()) {
+                                object.module_flags.push((f, text));
+                            }
+                        }
+                    },
+                }
+            }
+            _ => {}
+        }
+    }
+    object
+}
+
+/// Predefined items from the `<predefined>` section of an EDT object description, in document
+/// order, parents before their children (the same result as `parse_predefined_xml`).
+///
+/// ```xml
+/// <predefined xsi:type="mdclass:CatalogPredefined">
+///   <items id="..."><name>Рубль</name><code xsi:type="core:StringValue"><value>643</value></code>
+///     <description>руб.</description><isFolder>false</isFolder><content>...</content></items>
+/// </predefined>
+/// ```
+pub fn parse_edt_predefined(xml: &str) -> Vec<PredefinedItem> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut items: Vec<PredefinedItem> = Vec::new();
+    // Stack depth of the element and index into `items` of the open items; children of an
+    // item are its `<content>` elements
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(Event::Start(e)) => {
+                let name = local_name(&e);
+                let depth = stack.len();
+                let is_item = match open.last() {
+                    Some(&(d, _)) => depth == d + 1 && name == "content",
+                    None => depth == 2 && stack[1] == "predefined" && name == "items",
+                };
+                if is_item {
+                    let parent = open.last().map(|&(_, i)| items[i].name.clone());
+                    items.push(PredefinedItem { parent, ..Default::default() });
+                    open.push((depth, items.len() - 1));
+                }
+                stack.push(name);
+            }
+            Ok(Event::End(_)) => {
+                stack.pop();
+                if open.last().is_some_and(|&(d, _)| d == stack.len()) {
+                    open.pop();
+                }
+            }
+            Ok(Event::Text(t)) => {
+                let (Some(&(d, i)), Ok(text)) = (open.last(), t.unescape()) else { continue };
+                let text = text.trim().to_string();
+                // A field of the item, or the `<value>` of its typed `<code>`
+                let field = match &stack[d + 1..] {
+                    [field] => field.as_str(),
+                    [code, value] if code == "code" && value == "value" => "code",
+                    _ => continue,
+                };
+                match field {
+                    "name" => items[i].name = text,
+                    "code" => items[i].code = text,
+                    "description" => items[i].description = text,
+                    "isFolder" => items[i].is_folder = text == "true",
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    items.retain(|item| !item.name.is_empty());
+    items
+}
+
+/// `(type, name)` of every object listed in `Configuration/Configuration.mdo`
+/// (`<catalogs>Catalog.Валюты</catalogs>`), in document order, each once.
+pub fn parse_edt_configuration(xml: &str) -> Vec<(String, String)> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut children: Vec<(String, String)> = Vec::new();
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(Event::Start(_)) => depth += 1,
+            Ok(Event::End(_)) => depth = depth.saturating_sub(1),
+            // mdclass:Configuration / <catalogs>
+            Ok(Event::Text(t)) if depth == 2 => {
+                let Ok(text) = t.unescape() else { continue };
+                let Some((obj_type, name)) = text.trim().split_once('.') else { continue };
+                if name.contains('.') || children.iter().any(|(t, n)| t == obj_type && n == name) {
+                    continue;
+                }
+                children.push((obj_type.to_string(), name.to_string()));
+            }
+            _ => {}
+        }
+    }
+    children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<MetaDataObject xmlns="http://v8.1c.ru/8.3/MDClasses" xmlns:v8="http://v8.1c.ru/8.1/data/core" xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:cfg="http://v8.1c.ru/8.1/data/enterprise/current-config" version="2.16">
+	<Catalog uuid="1">
+		<Properties>
+			<Name>Валюты</Name>
+			<Synonym><v8:item><v8:lang>ru</v8:lang><v8:content>Валюты</v8:content></v8:item></Synonym>
+			<Comment/>
+		</Properties>
+		<ChildObjects>
+			<Attribute uuid="2">
+				<Properties>
+					<Name>Курс</Name>
+					<Synonym><v8:item><v8:lang>ru</v8:lang><v8:content>Курс &amp; кратность</v8:content></v8:item></Synonym>
+					<Comment>Текущий курс</Comment>
+					<Type>
+						<v8:Type>xs:decimal</v8:Type>
+						<v8:NumberQualifiers>
+							<v8:Digits>15</v8:Digits>
+							<v8:FractionDigits>4</v8:FractionDigits>
+							<v8:AllowedSign>Nonnegative</v8:AllowedSign>
+						</v8:NumberQualifiers>
+					</Type>
+					<ChoiceParameterLinks><xr:Link><xr:Name>Отбор.Владелец</xr:Name></xr:Link></ChoiceParameterLinks>
+					<FillChecking>ShowError</FillChecking>
+					<Indexing>DontIndex</Indexing>
+				</Properties>
+			</Attribute>
+			<Attribute uuid="3">
+				<Properties>
+					<Name>Основная</Name>
+					<Type>
+						<v8:Type>cfg:CatalogRef.Валюты</v8:Type>
+						<v8:Type>xs:string</v8:Type>
+						<v8:StringQualifiers><v8:Length>10</v8:Length></v8:StringQualifiers>
+					</Type>
+					<Indexing>Index</Indexing>
+				</Properties>
+			</Attribute>
+			<TabularSection uuid="4">
+				<Properties>
+					<Name>Курсы</Name>
+					<Synonym><v8:item><v8:lang>ru</v8:lang><v8:content>История курсов</v8:content></v8:item></Synonym>
+				</Properties>
+				<ChildObjects>
+					<Attribute uuid="5">
+						<Properties>
+							<Name>Период</Name>
+							<Type>
+								<v8:Type>xs:dateTime</v8:Type>
+								<v8:DateQualifiers><v8:DateFractions>Date</v8:DateFractions></v8:DateQualifiers>
+							</Type>
+						</Properties>
+					</Attribute>
+				</ChildObjects>
+			</TabularSection>
+			<Form>ФормаЭлемента</Form>
+			<Command uuid="6"><Properties><Name>Загрузить</Name></Properties></Command>
+		</ChildObjects>
+	</Catalog>
+</MetaDataObject>"#;
+
+    #[test]
+    fn test_parse_object_xml() {
+        let obj = parse_object_xml(CATALOG);
+        assert_eq!(obj.name, "Валюты");
+        assert_eq!(obj.synonym, "Валюты");
+
+        assert_eq!(obj.attributes.len(), 2);
+        let rate = &obj.attributes[0];
+        assert_eq!(rate.name, "Курс");
+        assert_eq!(rate.kind, "Attribute");
+        assert_eq!(rate.value_type, "Number(15,4)");
+        assert_eq!(rate.synonym, "Курс & кратность");
+        assert_eq!(rate.comment, "Текущий курс");
+        assert_eq!(rate.fill_checking.as_deref(), Some("ShowError"));
+        assert_eq!(rate.indexing.as_deref(), Some("DontIndex"));
+        assert_eq!(obj.attributes[1].value_type, "CatalogRef.Валюты, String(10)");
+
+        assert_eq!(obj.tabular_sections.len(), 1);
+        let ts = &obj.tabular_sections[0];
+        assert_eq!((ts.name.as_str(), ts.synonym.as_str()), ("Курсы", "История курсов"));
+        assert_eq!(ts.attributes.len(), 1);
+        assert_eq!((ts.attributes[0].name.as_str(), ts.attributes[0].value_type.as_str()), ("Период", "Date"));
+
+        assert_eq!(obj.forms, vec!["ФормаЭлемента".to_string()]);
+        assert_eq!(obj.commands, vec!["Загрузить".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_register_and_recorder() {
+        let register = r#"<MetaDataObject xmlns:v8="v8" xmlns:cfg="cfg" xmlns:xs="xs">
+	<AccumulationRegister uuid="1">
+		<Properties>
+			<Name>ТоварыНаСкладах</Name>
+			<RegisterType>Balance</RegisterType>
+		</Properties>
+		<ChildObjects>
+			<Resource uuid="2"><Properties><Name>Количество</Name>
+				<Type><v8:Type>xs:decimal</v8:Type><v8:NumberQualifiers><v8:Digits>15</v8:Digits><v8:FractionDigits>3</v8:FractionDigits></v8:NumberQualifiers></Type>
+			</Properties></Resource>
+			<Dimension uuid="3"><Properties><Name>Склад</Name>
+				<Type><v8:Type>cfg:CatalogRef.Склады</v8:Type></Type>
+				<Indexing>Index</Indexing>
+			</Properties></Dimension>
+		</ChildObjects>
+	</AccumulationRegister>
+</MetaDataObject>"#;
+        let obj = parse_object_xml(register);
+        assert_eq!(obj.properties, vec![("RegisterType".to_string(), "Balance".to_string())]);
+        let kinds: Vec<(&str, &str, &str)> = obj
+            .attributes
+            .iter()
+            .map(|a| (a.kind.as_str(), a.name.as_str(), a.value_type.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![("Resource", "Количество", "Number(15,3)"), ("Dimension", "Склад", "CatalogRef.Склады")]
+        );
+
+        let document = r#"<MetaDataObject xmlns:xr="xr" xmlns:xsi="xsi">
+	<Document uuid="1">
+		<Properties>
+			<Name>Поступление</Name>
+			<RegisterRecords>
+				<xr:Item xsi:type="xr:MDObjectRef">AccumulationRegister.ТоварыНаСкладах</xr:Item>
+				<xr:Item xsi:type="xr:MDObjectRef">InformationRegister.Цены</xr:Item>
+			</RegisterRecords>
+		</Properties>
+	</Document>
+</MetaDataObject>"#;
+        let obj = parse_object_xml(document);
+        assert_eq!(obj.register_records, vec!["AccumulationRegister.ТоварыНаСкладах", "InformationRegister.Цены"]);
+    }
+
+    #[test]
+    fn test_defined_type_and_references() {
+        let defined = r#"<MetaDataObject xmlns:v8="v8" xmlns:cfg="cfg">
+	<DefinedType uuid="1">
+		<Properties>
+			<Name>Организация</Name>
+			<Type><v8:Type>cfg:CatalogRef.Организации</v8:Type><v8:Type>cfg:CatalogRef.ФизическиеЛица</v8:Type></Type>
+		</Properties>
+	</DefinedType>
+</MetaDataObject>"#;
+        let obj = parse_object_xml(defined);
+        assert_eq!(obj.value_type, "CatalogRef.Организации, CatalogRef.ФизическиеЛица");
+        assert!(obj.attributes.is_empty());
+
+        assert_eq!(referenced_object("CatalogRef.Контрагенты").as_deref(), Some("Catalog.Контрагенты"));
+        assert_eq!(referenced_object("DefinedType.Организация").as_deref(), Some("DefinedType.Организация"));
+        assert_eq!(
+            referenced_object("Characteristic.ДополнительныеРеквизиты").as_deref(),
+            Some("ChartOfCharacteristicTypes.ДополнительныеРеквизиты")
+        );
+        assert_eq!(referenced_object("String(50)"), None);
+        assert_eq!(referenced_object("ValueStorage"), None);
+    }
+
+    #[test]
+    fn test_parse_enum_values() {
+        let xml = r#"<MetaDataObject>
+	<Enum uuid="1">
+		<Properties><Name>СтавкиНДС</Name></Properties>
+		<ChildObjects>
+			<EnumValue uuid="2"><Properties><Name>НДС20</Name>
+				<Synonym><v8:item><v8:lang>ru</v8:lang><v8:content>20%</v8:content></v8:item></Synonym>
+				<Comment/></Properties></EnumValue>
+			<EnumValue uuid="3"><Properties><Name>БезНДС</Name></Properties></EnumValue>
+		</ChildObjects>
+	</Enum>
+</MetaDataObject>"#;
+        let obj = parse_object_xml(xml);
+        assert!(obj.attributes.is_empty());
+        let values: Vec<(&str, &str)> = obj.enum_values.iter().map(|v| (v.name.as_str(), v.synonym.as_str())).collect();
+        assert_eq!(values, vec![("НДС20", "20%"), ("БезНДС", "")]);
+    }
+
+    #[test]
+    fn test_parse_subsystem() {
+        let xml = r#"<MetaDataObject xmlns:xr="xr" xmlns:xsi="xsi">
+	<Subsystem uuid="1">
+		<Properties>
+			<Name>Продажи</Name>
+			<Content>
+				<xr:Item xsi:type="xr:MDObjectRef">Catalog.Контрагенты</xr:Item>
+				<xr:Item xsi:type="xr:MDObjectRef">Document.РеализацияТоваров</xr:Item>
+			</Content>
+		</Properties>
+		<ChildObjects>
+			<Subsystem>ОптовыеПродажи</Subsystem>
+		</ChildObjects>
+	</Subsystem>
+</MetaDataObject>"#;
+        let obj = parse_object_xml(xml);
+        assert_eq!(obj.name, "Продажи");
+        assert_eq!(obj.content, vec!["Catalog.Контрагенты", "Document.РеализацияТоваров"]);
+        assert_eq!(obj.subsystems, vec!["ОптовыеПродажи"]);
+        assert!(obj.register_records.is_empty());
+    }
+
+    #[test]
+    fn test_parse_event_subscription_handler() {
+        let xml = r#"<MetaDataObject xmlns:v8="v8">
+	<EventSubscription uuid="1">
+		<Properties>
+			<Name>ПередЗаписьюДокумента</Name>
+			<Source>
+				<v8:Type>cfg:DocumentObject.РеализацияТоваров</v8:Type>
+			</Source>
+			<Event>BeforeWrite</Event>
+			<Handler>CommonModule.ОбменДаннымиСобытия.ПередЗаписьюДокумента</Handler>
+		</Properties>
+	</EventSubscription>
+</MetaDataObject>"#;
+        let obj = parse_object_xml(xml);
+        assert_eq!(obj.name, "ПередЗаписьюДокумента");
+        assert_eq!(
+            obj.properties,
+            vec![
+                ("Event".to_string(), "BeforeWrite".to_string()),
+                ("Handler".to_string(), "CommonModule.ОбменДаннымиСобытия.ПередЗаписьюДокумента".to_string()),
+            ]
+        );
+        assert!(obj.value_type.is_empty());
+    }
+
+    #[test]
+    fn test_parse_service_handlers() {
+        let xml = r#"<MetaDataObject>
+	<HTTPService uuid="1">
+		<Properties><Name>Обмен</Name><RootURL>exchange</RootURL></Properties>
+		<ChildObjects>
+			<URLTemplate uuid="2">
+				<Properties><Name>Заказы</Name><Template>/orders/{id}</Template></Properties>
+				<ChildObjects>
+					<Method uuid="3">
+						<Properties><Name>GET</Name><HTTPMethod>GET</HTTPMethod><Handler>ЗаказыGET</Handler></Properties>
+					</Method>
+					<Method uuid="4">
+						<Properties><Name>POST</Name><HTTPMethod>POST</HTTPMethod><Handler>ЗаказыPOST</Handler></Properties>
+					</Method>
+					<Method uuid="5">
+						<Properties><Name>PUT</Name><HTTPMethod>PUT</HTTPMethod><Handler>ЗаказыPOST</Handler></Properties>
+					</Method>
+				</ChildObjects>
+			</URLTemplate>
+		</ChildObjects>
+	</HTTPService>
+</MetaDataObject>"#;
+        let obj = parse_object_xml(xml);
+        assert_eq!(obj.service_handlers, vec!["ЗаказыGET", "ЗаказыPOST"]);
+        assert!(obj.properties.is_empty());
+
+        let mdo = r#"<mdclass:WebService xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass" uuid="1">
+  <name>Склад</name>
+  <namespace>http://example.com/stock</namespace>
+  <operations uuid="2">
+    <name>ПолучитьОстатки</name>
+    <xdtoReturningValueType>string</xdtoReturningValueType>
+    <procedureName>ПолучитьОстатки</procedureName>
+    <parameters uuid="3"><name>Склад</name></parameters>
+  </operations>
+</mdclass:WebService>"#;
+        let obj = parse_edt_object(mdo);
+        assert_eq!(obj.name, "Склад");
+        assert_eq!(obj.service_handlers, vec!["ПолучитьОстатки"]);
+    }
+
+    #[test]
+    fn test_parse_common_module_flags() {
+        let xml = r#"<MetaDataObject>
+	<CommonModule uuid="1">
+		<Properties>
+			<Name>ПродажиВызовСервера</Name>
+			<Global>false</Global>
+			<ClientManagedApplication>false</ClientManagedApplication>
+			<Server>true</Server>
+			<ExternalConnection>false</ExternalConnection>
+			<ClientOrdinaryApplication>false</ClientOrdinaryApplication>
+			<ServerCall>true</ServerCall>
+			<Privileged>false</Privileged>
+			<ReturnValuesReuse>DuringSession</ReturnValuesReuse>
+		</Properties>
+	</CommonModule>
+</MetaDataObject>"#;
+        let obj = parse_object_xml(xml);
+        assert_eq!(obj.name, "ПродажиВызовСервера");
+        assert!(obj.properties.is_empty());
+        let flag = |name: &str| obj.module_flags.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+        assert_eq!(obj.module_flags.len(), 8);
+        assert_eq!(flag("Server"), Some("true"));
+        assert_eq!(flag("ServerCall"), Some("true"));
+        assert_eq!(flag("ClientManagedApplication"), Some("false"));
+        assert_eq!(flag("ReturnValuesReuse"), Some("DuringSession"));
+    }
+
+    #[test]
+    fn test_parse_edt_object() {
+        let mdo = r#"<?xml version="1.0" encoding="UTF-8"?>
+<mdclass:Document xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass" uuid="1">
+  <producedTypes>
+    <objectType typeId="2" valueTypeId="3"/>
+  </producedTypes>
+  <name>Заказ</name>
+  <synonym>
+    <key>ru</key>
+    <value>Заказ покупателя</value>
+  </synonym>
+  <registerRecords>AccumulationRegister.ТоварыНаСкладах</registerRecords>
+  <attributes uuid="4">
+    <name>Контрагент</name>
+    <synonym><key>ru</key><value>Контрагент</value></synonym>
+    <type>
+      <types>CatalogRef.Контрагенты</types>
+    </type>
+    <fillChecking>ShowError</fillChecking>
+  </attributes>
+  <attributes uuid="5">
+    <name>Комментарий</name>
+    <type>
+      <types>String</types>
+      <stringQualifiers><length>100</length></stringQualifiers>
+    </type>
+  </attributes>
+  <forms uuid="6">
+    <name>ФормаДокумента</name>
+    <synonym><key>ru</key><value>Форма документа</value></synonym>
+  </forms>
+  <tabularSections uuid="7">
+    <name>Товары</name>
+    <standardAttributes><name>LineNumber</name></standardAttributes>
+    <attributes uuid="8">
+      <name>Сумма</name>
+      <type>
+        <types>Number</types>
+        <numberQualifiers><precision>15</precision><scale>2</scale></numberQualifiers>
+      </type>
+    </attributes>
+  </tabularSections>
+  <commands uuid="9">
+    <name>Печать</name>
+    <group>FormNavigationPanelImportant</group>
+  </commands>
+</mdclass:Document>"#;
+        let obj = parse_edt_object(mdo);
+        assert_eq!(obj.name, "Заказ");
+        assert_eq!(obj.synonym, "Заказ покупателя");
+        assert_eq!(obj.register_records, vec!["AccumulationRegister.ТоварыНаСкладах"]);
+        let attrs: Vec<(&str, &str)> =
+            obj.attributes.iter().map(|a| (a.name.as_str(), a.value_type.as_str())).collect();
+        assert_eq!(attrs, vec![("Контрагент", "CatalogRef.Контрагенты"), ("Комментарий", "String(100)")]);
+        assert_eq!(obj.attributes[0].fill_checking.as_deref(), Some("ShowError"));
+        assert_eq!(obj.tabular_sections.len(), 1);
+        assert_eq!(obj.tabular_sections[0].name, "Товары");
+        assert_eq!(obj.tabular_sections[0].attributes[0].name, "Сумма");
+        assert_eq!(obj.tabular_sections[0].attributes[0].value_type, "Number(15,2)");
+        assert_eq!(obj.forms, vec!["ФормаДокумента"]);
+        assert_eq!(obj.commands, vec!["Печать"]);
+
+        let module = parse_edt_object(
+            r#"<mdclass:CommonModule xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass">
+  <name>ПродажиВызовСервера</name>
+  <server>true</server>
+  <serverCall>true</serverCall>
+  <returnValuesReuse>DuringRequest</returnValuesReuse>
+</mdclass:CommonModule>"#,
+        );
+        assert_eq!(
+            module.module_flags,
+            vec![
+                ("Server".to_string(), "true".to_string()),
+                ("ServerCall".to_string(), "true".to_string()),
+                ("ReturnValuesReuse".to_string(), "DuringRequest".to_string()),
+            ]
+        );
+
+        let config = r#"<mdclass:Configuration xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass">
+  <name>Торговля</name>
+  <synonym><key>ru</key><value>Торговля</value></synonym>
+  <defaultRoles>Role.ПолныеПрава</defaultRoles>
+  <languages>Language.Русский</languages>
+  <subsystems>Subsystem.Продажи</subsystems>
+  <roles>Role.ПолныеПрава</roles>
+  <commonModules>CommonModule.ПродажиВызовСервера</commonModules>
+  <catalogs>Catalog.Контрагенты</catalogs>
+</mdclass:Configuration>"#;
+        let children = parse_edt_configuration(config);
+        let names: Vec<String> = children.iter().map(|(t, n)| format!("{}.{}", t, n)).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Role.ПолныеПрава", "Language.Русский", "Subsystem.Продажи",
+                "CommonModule.ПродажиВызовСервера", "Catalog.Контрагенты",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_predefined_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<PredefinedData xmlns="http://v8.1c.ru/8.3/xcf/predef" xsi:type="ChartOfAccountsPredefinedItems">
+	<Item id="1">
+		<Name>Касса</Name>
+		<Code>50</Code>
+		<Description>Касса</Description>
+		<IsFolder>false</IsFolder>
+		<ExtDimensionTypes><ExtDimensionType><Name>Ignored</Name></ExtDimensionType></ExtDimensionTypes>
+		<ChildItems>
+			<Item id="2">
+				<Name>КассаОрганизации</Name>
+				<Code>50.01</Code>
+				<Description>Касса организации</Description>
+			</Item>
+		</ChildItems>
+	</Item>
+	<Item id="3"><Name>Рубль</Name><Code>643</Code><Description>руб.</Description></Item>
+</PredefinedData>"#;
+        let items = parse_predefined_xml(xml);
+        let flat: Vec<(&str, &str, Option<&str>)> = items
+            .iter()
+            .map(|i| (i.name.as_str(), i.code.as_str(), i.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            flat,
+            vec![("Касса", "50", None), ("КассаОрганизации", "50.01", Some("Касса")), ("Рубль", "643", None)]
+        );
+        assert_eq!(items[1].description, "Касса организации");
+    }
+
+    #[test]
+    fn test_parse_edt_predefined() {
+        let mdo = r#"<mdclass:ChartOfAccounts xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass">
+  <name>Хозрасчетный</name>
+  <predefined xsi:type="mdclass:ChartOfAccountsPredefined">
+    <items id="1">
+      <name>Касса</name>
+      <description>Касса</description>
+      <code xsi:type="core:StringValue"><value>50</value></code>
+      <extDimensionTypes><name>Ignored</name></extDimensionTypes>
+      <content id="2">
+        <name>КассаОрганизации</name>
+        <description>Касса организации</description>
+        <code xsi:type="core:StringValue"><value>50.01</value></code>
+      </content>
+    </items>
+    <items id="3">
+      <name>Группа</name>
+      <isFolder>true</isFolder>
+    </items>
+  </predefined>
+</mdclass:ChartOfAccounts>"#;
+        let items = parse_edt_predefined(mdo);
+        let flat: Vec<(&str, &str, Option<&str>, bool)> = items
+            .iter()
+            .map(|i| (i.name.as_str(), i.code.as_str(), i.parent.as_deref(), i.is_folder))
+            .collect();
+        assert_eq!(
+            flat,
+            vec![
+                ("Касса", "50", None, false),
+                ("КассаОрганизации", "50.01", Some("Касса"), false),
+                ("Группа", "", None, true),
+            ]
+        );
+        assert_eq!(items[1].description, "Касса организации");
+        assert!(parse_edt_object(mdo).attributes.is_empty());
+    }
+
+    #[test]
+    fn test_parse_configuration_and_dump_info() {
+        let cfg = "<MetaDataObject><Configuration><Properties><Name>Торговля</Name></Properties>\
+                   <ChildObjects><Language>Русский</Language><Catalog>Валюты</Catalog>\
+                   <Document>Заказ</Document></ChildObjects></Configuration></MetaDataObject>";
+        assert_eq!(
+            parse_configuration_children(cfg),
+            vec![
+                ("Language".to_string(), "Русский".to_string()),
+                ("Catalog".to_string(), "Валюты".to_string()),
+                ("Document".to_string(), "Заказ".to_string()),
+            ]
+        );
+
+        let dump = r#"<ConfigDumpInfo><ConfigVersions>
+            <Metadata name="Catalog.Валюты" id="1"><Metadata name="Catalog.Валюты.Attribute.Курс" id="2"/></Metadata>
+            </ConfigVersions></ConfigDumpInfo>"#;
+        assert_eq!(parse_dump_info_names(dump), vec!["Catalog.Валюты", "Catalog.Валюты.Attribute.Курс"]);
+    }
+}
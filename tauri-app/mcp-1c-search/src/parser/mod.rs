@@ -1 +1,7 @@
 pub mod bsl_ast;
+pub mod bsl_doc;
+pub mod bsl_layout;
+pub mod bsl_query;
+pub mod form_xml;
+pub mod metadata_xml;
+pub mod rights_xml;
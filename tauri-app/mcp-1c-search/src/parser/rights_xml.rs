@@ -0,0 +1,183 @@
+//! Role rights (`Roles/<Имя>/Ext/Rights.xml`): per-object rights with their row-level
+//! security conditions and the role's restriction templates.
+//!
+//! ```xml
+//! <Rights>
+//!   <setForNewObjects>false</setForNewObjects>
+//!   <object>
+//!     <name>Document.Заказ</name>
+//!     <right>
+//!       <name>Read</name>
+//!       <value>true</value>
+//!       <restrictionByCondition><condition>#ПоЗначениям("Документ.Заказ", ...)</condition></restrictionByCondition>
+//!     </right>
+//!   </object>
+//!   <restrictionTemplate><name>ПоЗначениям(...)</name><condition>...</condition></restrictionTemplate>
+//! </Rights>
+//! ```
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Right {
+    /// Right name as written: `Read`, `Posting`, `InteractiveDelete`.
+    pub name: String,
+    pub value: bool,
+    /// RLS condition text, if the right is restricted.
+    pub condition: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectRights {
+    /// `Document.Заказ`, or a field of it: `Document.Заказ.Attribute.Сумма`.
+    pub object: String,
+    pub rights: Vec<Right>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoleRights {
+    /// Rights on new objects are granted by default ("Устанавливать права для новых объектов").
+    pub set_for_new_objects: bool,
+    pub objects: Vec<ObjectRights>,
+    /// Restriction templates as (name with parameters, template text).
+    pub templates: Vec<(String, String)>,
+}
+
+/// Parse a role's `Rights.xml`. A malformed document yields whatever was read before the error.
+pub fn parse_rights_xml(xml: &str) -> RoleRights {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut role = RoleRights::default();
+    let mut stack: Vec<String> = Vec::new();
+    let mut template: Option<(String, String)> = None;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(Event::Start(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                match (stack.last().map(String::as_str), name.as_str()) {
+                    (Some("Rights"), "object") => role.objects.push(ObjectRights::default()),
+                    (Some("object"), "right") => {
+                        if let Some(object) = role.objects.last_mut() {
+                            object.rights.push(Right::default());
+                        }
+                    }
+                    (Some("Rights"), "restrictionTemplate") => template = Some(Default::default()),
+                    _ => {}
+                }
+                stack.push(name);
+            }
+            Ok(Event::End(_)) => {
+                let closed = stack.pop();
+                if closed.as_deref() == Some("restrictionTemplate") {
+                    if let Some(t) = template.take().filter(|(name, _)| !name.is_empty()) {
+                        role.templates.push(t);
+                    }
+                }
+            }
+            Ok(Event::Text(t)) => {
+                let Ok(text) = t.unescape() else { continue };
+                let text = text.trim().to_string();
+                let n = stack.len();
+                let tail = |k: usize| stack.get(n.wrapping_sub(k)).map(String::as_str).unwrap_or("");
+                match (tail(2), tail(1)) {
+                    ("Rights", "setForNewObjects") => role.set_for_new_objects = text == "true",
+                    ("object", "name") => {
+                        if let Some(object) = role.objects.last_mut() {
+                            object.object = text;
+                        }
+                    }
+                    ("right", field) => {
+                        let Some(right) = role.objects.last_mut().and_then(|o| o.rights.last_mut()) else {
+                            continue;
+                        };
+                        match field {
+                            "name" => right.name = text,
+                            "value" => right.value = text == "true",
+                            _ => {}
+                        }
+                    }
+                    ("restrictionByCondition", "condition") => {
+                        if let Some(right) = role.objects.last_mut().and_then(|o| o.rights.last_mut()) {
+                            right.condition = Some(text).filter(|c| !c.is_empty());
+                        }
+                    }
+                    ("restrictionTemplate", field) => {
+                        if let Some((name, condition)) = template.as_mut() {
+                            match field {
+                                "name" => *name = text,
+                                "condition" => *condition = text,
+                                _ => {}
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    role.objects.retain(|o| !o.object.is_empty());
+    role
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rights_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Rights xmlns="http://v8.1c.ru/8.2/roles" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Rights" version="2.17">
+	<setForNewObjects>false</setForNewObjects>
+	<setForAttributesByDefault>true</setForAttributesByDefault>
+	<object>
+		<name>Document.РеализацияТоваров</name>
+		<right>
+			<name>Read</name>
+			<value>true</value>
+			<restrictionByCondition>
+				<condition>#ПоЗначениям("Документ.РеализацияТоваров", "Чтение", "Организация", "Организация")</condition>
+			</restrictionByCondition>
+		</right>
+		<right>
+			<name>Posting</name>
+			<value>true</value>
+		</right>
+		<right>
+			<name>InteractiveDelete</name>
+			<value>false</value>
+		</right>
+	</object>
+	<object>
+		<name>Document.РеализацияТоваров.Attribute.Сумма</name>
+		<right><name>View</name><value>false</value></right>
+	</object>
+	<restrictionTemplate>
+		<name>ПоЗначениям(Таблица, Право, Вид, Поле)</name>
+		<condition>ГДЕ ИСТИНА</condition>
+	</restrictionTemplate>
+</Rights>"#;
+        let role = parse_rights_xml(xml);
+        assert!(!role.set_for_new_objects);
+        assert_eq!(role.objects.len(), 2);
+
+        let doc = &role.objects[0];
+        assert_eq!(doc.object, "Document.РеализацияТоваров");
+        let rights: Vec<(&str, bool, bool)> = doc
+            .rights
+            .iter()
+            .map(|r| (r.name.as_str(), r.value, r.condition.is_some()))
+            .collect();
+        assert_eq!(rights, vec![("Read", true, true), ("Posting", true, false), ("InteractiveDelete", false, false)]);
+        assert!(doc.rights[0].condition.as_deref().unwrap().starts_with("#ПоЗначениям"));
+
+        assert_eq!(role.objects[1].rights, vec![Right { name: "View".into(), value: false, condition: None }]);
+        assert_eq!(
+            role.templates,
+            vec![("ПоЗначениям(Таблица, Право, Вид, Поле)".to_string(), "ГДЕ ИСТИНА".to_string())]
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use crate::index;
 use ignore::WalkBuilder;
+use rayon::prelude::*;
 use regex::Regex;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 pub struct SearchResult {
@@ -33,9 +34,11 @@ fn compile_pattern(query: &str, use_regex: bool) -> Option<Regex> {
     }
 }
 
-/// Search for `query` in .bsl and .xml files under `root` (or `root/sub_path` if given).
+/// Search for `query` in .bsl and .xml files under `root` and the extension roots
+/// (or under `sub_path` only, if given).
 /// `use_regex` — treat query as regex; otherwise literal case-insensitive.
-/// `sub_path` — optional relative sub-directory to restrict the search scope.
+/// `sub_path` — optional index-relative sub-directory (`@<extension>/...` for an extension)
+/// to restrict the search scope.
 /// `max_ms` — optional time budget in milliseconds; if exceeded, returns partial results early.
 ///
 /// Returns `(results, timed_out)`.
@@ -47,6 +50,8 @@ fn compile_pattern(query: &str, use_regex: bool) -> Option<Regex> {
 /// Critical: does NOT collect all file paths upfront. On large configs (25K+ files)
 /// on a cold HDD, collecting all metadata first would take 5-10 minutes.
 /// Two-pass streaming means we stop reading as soon as we have enough results.
+///
+/// Used while the code index is not built yet; see `search_modules_indexed`.
 pub fn search_code(
     root: &Path,
     sub_path: Option<&Path>,
@@ -54,75 +59,82 @@ pub fn search_code(
     use_regex: bool,
     limit: usize,
     max_ms: Option<u64>,
+) -> (Vec<SearchResult>, bool) {
+    stream_search(root, sub_path, query, use_regex, &["bsl", "xml"], limit, max_ms)
+}
+
+/// `search_code` over `.xml` files only: metadata descriptions are not in the code index.
+pub fn search_xml(
+    root: &Path,
+    sub_path: Option<&Path>,
+    query: &str,
+    use_regex: bool,
+    limit: usize,
+    max_ms: Option<u64>,
+) -> (Vec<SearchResult>, bool) {
+    stream_search(root, sub_path, query, use_regex, &["xml"], limit, max_ms)
+}
+
+/// One streaming pass per extension, in order, sharing `limit` and the deadline.
+fn stream_search(
+    root: &Path,
+    sub_path: Option<&Path>,
+    query: &str,
+    use_regex: bool,
+    extensions: &[&str],
+    limit: usize,
+    max_ms: Option<u64>,
 ) -> (Vec<SearchResult>, bool) {
     let pattern = match compile_pattern(query, use_regex) {
         Some(p) => p,
         None => return (vec![], false),
     };
 
-    let search_root = match sub_path {
+    // The base root skips extension roots nested inside it: they are walked on their own
+    let search_roots: Vec<(PathBuf, bool)> = match sub_path {
         Some(sub) => {
-            let p = root.join(sub);
+            let p = index::source_path(root, &sub.to_string_lossy().replace('\\', "/"));
             if !p.exists() {
                 eprintln!("[1c-search] Scope path not found: {}", p.display());
                 return (vec![], false);
             }
-            p
+            vec![(p, false)]
         }
-        None => root.to_path_buf(),
+        None => std::iter::once((root.to_path_buf(), true))
+            .chain(index::extension_roots().iter().map(|ext| (ext.path.clone(), false)))
+            .collect(),
     };
 
     let deadline = max_ms.map(|ms| Instant::now() + Duration::from_millis(ms));
     let mut results = Vec::new();
     let mut file_count = 0usize;
 
-    // Pass 1: BSL only — streaming, early exit at limit or deadline
-    'bsl: for entry in WalkBuilder::new(&search_root)
-        .standard_filters(true)
-        .follow_links(false)
-        .build()
-        .flatten()
-    {
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
-        }
-        if path.extension().and_then(|e| e.to_str()) != Some("bsl") {
-            continue;
-        }
-        file_count += 1;
-        // Check deadline every 200 files (avoids clock overhead on each file)
-        if file_count % 200 == 0 {
-            if let Some(dl) = deadline {
-                if Instant::now() >= dl {
-                    return (results, true);
-                }
-            }
-        }
-        for r in search_file(path, &pattern, root) {
-            results.push(r);
-            if results.len() >= limit {
-                break 'bsl;
-            }
+    // Streaming, early exit at limit or deadline; a pass is skipped once the limit is filled
+    for extension in extensions {
+        if results.len() >= limit {
+            break;
         }
-    }
-
-    // Pass 2: XML only — skipped entirely if BSL already filled the limit
-    if results.len() < limit {
-        'xml: for entry in WalkBuilder::new(&search_root)
-            .standard_filters(true)
-            .follow_links(false)
-            .build()
-            .flatten()
-        {
+        let entries = search_roots.iter().flat_map(|(search_root, skip_extensions)| {
+            let skip_extensions = *skip_extensions;
+            WalkBuilder::new(search_root)
+                .standard_filters(true)
+                .follow_links(false)
+                .filter_entry(move |e| {
+                    !(skip_extensions && index::extension_roots().iter().any(|ext| e.path() == ext.path))
+                })
+                .build()
+                .flatten()
+        });
+        'files: for entry in entries {
             let path = entry.path();
             if !path.is_file() {
                 continue;
             }
-            if path.extension().and_then(|e| e.to_str()) != Some("xml") {
+            if path.extension().and_then(|e| e.to_str()) != Some(*extension) {
                 continue;
             }
             file_count += 1;
+            // Check deadline every 200 files (avoids clock overhead on each file)
             if file_count % 200 == 0 {
                 if let Some(dl) = deadline {
                     if Instant::now() >= dl {
@@ -133,7 +145,7 @@ pub fn search_code(
             for r in search_file(path, &pattern, root) {
                 results.push(r);
                 if results.len() >= limit {
-                    break 'xml;
+                    break 'files;
                 }
             }
         }
@@ -142,6 +154,107 @@ pub fn search_code(
     (results, false)
 }
 
+/// Modules scanned in parallel per step of `search_modules_indexed`.
+const INDEXED_SCAN_CHUNK: usize = 256;
+
+/// Search `query` in BSL modules narrowed down by the trigram code index
+/// (`index::code_candidate_files`). Complete, with no time budget: only modules containing
+/// every required literal are read, in parallel. Results are ordered by file and line.
+///
+/// `None` if the code index is not built yet — fall back to `search_code`.
+pub fn search_modules_indexed(
+    root: &Path,
+    db_path: &Path,
+    sub_path: Option<&Path>,
+    query: &str,
+    use_regex: bool,
+    limit: usize,
+) -> Option<Vec<SearchResult>> {
+    let scope = sub_path.map(|p| p.to_string_lossy().into_owned());
+    let files = index::code_candidate_files(db_path, &required_literals(query, use_regex), scope.as_deref())?;
+    let Some(pattern) = compile_pattern(query, use_regex) else {
+        return Some(vec![]);
+    };
+
+    let mut results = Vec::new();
+    for chunk in files.chunks(INDEXED_SCAN_CHUNK) {
+        let hits: Vec<Vec<SearchResult>> = chunk
+            .par_iter()
+            .map(|rel| match index::read_file_to_string_lossy(&index::source_path(root, rel)) {
+                Ok(content) => matching_lines(&content, rel, &pattern),
+                Err(_) => vec![],
+            })
+            .collect();
+        results.extend(hits.into_iter().flatten());
+        if results.len() >= limit {
+            results.truncate(limit);
+            break;
+        }
+    }
+    Some(results)
+}
+
+/// Strings every match of `query` contains, to narrow the search by trigrams. A literal query
+/// is one such string. For a regex only top-level text outside groups, classes and escapes
+/// counts (a character followed by `?`, `*` or `{..}` is optional and dropped); a top-level
+/// `|` or the `x` flag (whitespace is ignored) gives nothing, i.e. no narrowing.
+fn required_literals(query: &str, use_regex: bool) -> Vec<String> {
+    if !use_regex {
+        return vec![query.to_string()];
+    }
+    if query.match_indices("(?").any(|(i, _)| query[i + 2..].chars().take_while(|c| *c != ')' && *c != ':').any(|c| c == 'x')) {
+        return vec![];
+    }
+
+    let mut literals = Vec::new();
+    let mut run = String::new();
+    let mut depth = 0usize;
+    let mut chars = query.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped) if depth == 0 && escaped.is_ascii_punctuation() => run.push(escaped),
+                _ => literals.push(std::mem::take(&mut run)),
+            },
+            '[' => {
+                literals.push(std::mem::take(&mut run));
+                // `[]a]` and `[^]a]` start with a literal `]`
+                let mut first = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '^' if first => continue,
+                        ']' if !first => break,
+                        _ => {}
+                    }
+                    first = false;
+                }
+            }
+            '(' => {
+                literals.push(std::mem::take(&mut run));
+                depth += 1;
+            }
+            ')' => depth = depth.saturating_sub(1),
+            '|' if depth == 0 => return vec![],
+            _ if depth > 0 => {}
+            '?' | '*' | '{' => {
+                run.pop();
+                literals.push(std::mem::take(&mut run));
+                if c == '{' {
+                    chars.by_ref().find(|c| *c == '}');
+                }
+            }
+            '+' | '.' | '^' | '$' => literals.push(std::mem::take(&mut run)),
+            _ => run.push(c),
+        }
+    }
+    literals.push(run);
+    literals.retain(|l| l.chars().count() >= 3);
+    literals
+}
+
 /// Search for `query` only in the specified set of files (given as relative paths from `root`).
 /// Used by index-guided search: SQLite provides candidate files, we grep only those.
 pub fn search_code_in_file_set(
@@ -158,7 +271,7 @@ pub fn search_code_in_file_set(
 
     let mut results = Vec::new();
     for rel_file in rel_files {
-        let abs_path = root.join(rel_file);
+        let abs_path = index::source_path(root, rel_file);
         if !abs_path.is_file() {
             continue;
         }
@@ -178,16 +291,15 @@ fn search_file(path: &Path, pattern: &Regex, root: &Path) -> Vec<SearchResult> {
         Err(_) => return vec![],
     };
 
-    let rel_path = path
-        .strip_prefix(root)
-        .map(|p| p.to_string_lossy().replace('\\', "/"))
-        .unwrap_or_else(|_| path.to_string_lossy().replace('\\', "/"));
+    matching_lines(&content, &index::index_rel_path(root, path), pattern)
+}
 
+fn matching_lines(content: &str, rel_path: &str, pattern: &Regex) -> Vec<SearchResult> {
     let mut results = Vec::new();
     for (idx, line) in content.lines().enumerate() {
         if pattern.is_match(line) {
             results.push(SearchResult {
-                file: rel_path.clone(),
+                file: rel_path.to_string(),
                 line: (idx + 1) as u32,
                 snippet: line.to_string(),
             });
@@ -405,3 +517,52 @@ pub fn count_files_and_size(root: &Path) -> (usize, f64) {
     let size_mb = (size_bytes as f64) / 1024.0 / 1024.0;
     (count, size_mb)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literals(query: &str) -> Vec<String> {
+        required_literals(query, true)
+    }
+
+    #[test]
+    fn literal_query_is_required_as_is() {
+        assert_eq!(required_literals("Найти(Строку|", false), vec!["Найти(Строку|"]);
+    }
+
+    #[test]
+    fn alternation_narrows_only_inside_a_group() {
+        assert!(literals("Провести|Записать").is_empty());
+        assert_eq!(literals("Документ(Провести|Записать)Объект"), vec!["Документ", "Объект"]);
+    }
+
+    #[test]
+    fn character_classes_split_literals() {
+        assert_eq!(literals("Заказ[0-9]+Покупателя"), vec!["Заказ", "Покупателя"]);
+        assert_eq!(literals("[]abc]xyzw"), vec!["xyzw"], "`]` first in a class is literal");
+        assert_eq!(literals("abc[^]\\]]def"), vec!["abc", "def"]);
+    }
+
+    #[test]
+    fn escaped_punctuation_is_literal_and_classes_split() {
+        assert_eq!(literals("Справочники\\.Валюты"), vec!["Справочники.Валюты"]);
+        assert_eq!(literals("\\bНайти\\s+Строку"), vec!["Найти", "Строку"]);
+    }
+
+    #[test]
+    fn optional_and_repeated_characters() {
+        assert_eq!(literals("Записатьс?Ошибкой"), vec!["Записать", "Ошибкой"]);
+        assert_eq!(literals("Колонки*Итог"), vec!["Колонк", "Итог"]);
+        assert_eq!(literals("ab{0,2}cdef"), vec!["cdef"]);
+        assert_eq!(literals("Ааа+Ббб"), vec!["Ааа", "Ббб"], "`+` keeps one occurrence");
+        assert!(literals("ab.cd").is_empty(), "fragments shorter than a trigram");
+    }
+
+    #[test]
+    fn extended_mode_gives_no_literals() {
+        assert!(literals("(?x) Найти Строку").is_empty());
+        assert!(literals("(?ix:Найти Строку)").is_empty());
+        assert_eq!(literals("(?i)НайтиСтроку"), vec!["НайтиСтроку"]);
+    }
+}
@@ -304,13 +304,16 @@ pub const INITIAL_ALIASES: &[DomainAlias] = &[
 
 /// Create semantic tables if they don't exist.
 pub fn ensure_semantic_schema(conn: &Connection) {
-    // FTS5 virtual table for tokenized symbol names + comments
+    // FTS5 virtual table for tokenized symbol names, comment summary, parameters
+    // and doc-comment descriptions (parameters / return value ranked as separate columns)
     let _ = conn.execute_batch(
         "CREATE VIRTUAL TABLE IF NOT EXISTS symbol_terms USING fts5(
             symbol_id UNINDEXED,
             name_tokens,
             comment_head,
             param_tokens,
+            param_docs,
+            return_docs,
             tokenize='unicode61 remove_diacritics 1'
         );
         CREATE TABLE IF NOT EXISTS symbol_weights (
@@ -408,6 +411,7 @@ pub fn rebuild_symbol_weights(conn: &Connection) {
                 COUNT(DISTINCT c.caller_file)
          FROM symbols s
          LEFT JOIN calls c ON c.callee_name_lower = s.name_lower
+                          AND (c.callee_file IS NULL OR c.callee_file = s.file)
          GROUP BY s.id;"
     );
 }
@@ -461,6 +465,13 @@ pub struct SemanticResult {
     pub start_line: i64,
     pub end_line: i64,
     pub is_export: bool,
+    /// Parameter list as written: `Знач Объект, Отказ = Ложь`.
+    pub params: String,
+    /// Summary line(s) of the doc comment, empty if none.
+    pub summary: String,
+    /// Which parts matched the query: "имя", "описание", "параметры", "описание параметров",
+    /// "возвращаемое значение".
+    pub matched_in: Vec<&'static str>,
     pub bm25_score: f64,
     pub call_count: i64,
     pub caller_diversity: i64,
@@ -508,10 +519,14 @@ pub fn tokenize_query(query: &str) -> Vec<String> {
 }
 
 /// Run semantic search: tokenize query → expand with aliases → FTS5 → rank.
+/// `subsystem` (see `index::normalize_subsystem`) keeps only methods of objects in that subsystem,
+/// `module_flag` (see `index::MODULE_FLAG_CONDITIONS`) only methods of common modules with that flag.
 pub fn semantic_search(
     conn: &Connection,
     query: &str,
     context_object_names: &[String],
+    subsystem: Option<&str>,
+    module_flag: Option<&str>,
     limit: usize,
 ) -> Vec<SemanticResult> {
     // 1. Tokenize query (split on spaces + expand CamelCase for each word)
@@ -560,20 +575,57 @@ pub fn semantic_search(
              s.name, s.kind, s.file, s.start_line, s.end_line, s.is_export,
              bm25(symbol_terms) AS bm25,
              COALESCE(sw.call_count, 0) AS call_count,
-             COALESCE(sw.caller_diversity, 0) AS caller_diversity
+             COALESCE(sw.caller_diversity, 0) AS caller_diversity,
+             {} AS params,
+             COALESCE(d.summary, ''),
+             st.name_tokens, st.comment_head, st.param_tokens, st.param_docs, st.return_docs
          FROM symbol_terms st
          JOIN symbols s ON s.id = CAST(st.symbol_id AS INTEGER)
          LEFT JOIN symbol_weights sw ON sw.symbol_id = s.id
+         LEFT JOIN symbol_docs d ON d.symbol_id = s.id
          WHERE symbol_terms MATCH ?1
+           AND (?2 IS NULL OR EXISTS (SELECT 1 FROM indexed_files f WHERE f.filepath = s.file AND {}))
+           AND {}
          ORDER BY bm25(symbol_terms)
          LIMIT {}",
+        crate::index::params_list_sql("s"),
+        crate::index::subsystem_member_sql("f.object_type", "f.object_name", "?2"),
+        module_flag
+            .and_then(|f| crate::index::module_flag_sql(f, "s.file"))
+            .unwrap_or_else(|| "1".to_string()),
         limit * 5  // fetch more, rerank below
     );
 
+    // Query tokens without the FTS prefix marker — used to tell which columns matched
+    let match_tokens: Vec<String> = fts_terms
+        .iter()
+        .map(|t| t.trim_end_matches('*').to_lowercase())
+        .collect();
+    let column_matches = |column: &str| {
+        column
+            .split_whitespace()
+            .any(|w| {
+                let w = w.to_lowercase();
+                match_tokens.iter().any(|t| w.starts_with(t.as_str()))
+            })
+    };
+
     let mut candidates: Vec<SemanticResult> = Vec::new();
 
     if let Ok(mut stmt) = conn.prepare(&sql) {
-        let _ = stmt.query_map(params![fts_query], |row| {
+        let _ = stmt.query_map(params![fts_query, subsystem], |row| {
+            let columns: [(String, &'static str); 5] = [
+                (row.get::<_, Option<String>>(12)?.unwrap_or_default(), "имя"),
+                (row.get::<_, Option<String>>(13)?.unwrap_or_default(), "описание"),
+                (row.get::<_, Option<String>>(14)?.unwrap_or_default(), "параметры"),
+                (row.get::<_, Option<String>>(15)?.unwrap_or_default(), "описание параметров"),
+                (row.get::<_, Option<String>>(16)?.unwrap_or_default(), "возвращаемое значение"),
+            ];
+            let matched_in = columns
+                .iter()
+                .filter(|(text, _)| column_matches(text))
+                .map(|(_, label)| *label)
+                .collect();
             Ok(SemanticResult {
                 symbol_id:       row.get(0)?,
                 name:            row.get(1)?,
@@ -585,6 +637,9 @@ pub fn semantic_search(
                 bm25_score:      row.get(7)?,
                 call_count:      row.get(8)?,
                 caller_diversity:row.get(9)?,
+                params:          row.get(10)?,
+                summary:         row.get(11)?,
+                matched_in,
                 final_score:     0.0,
             })
         }).map(|rows| {
@@ -617,7 +672,15 @@ pub fn semantic_search(
             1.0 + matches as f64 * 0.3
         };
 
-        c.final_score = bm25_norm * call_weight * diversity_weight * domain_weight;
+        // Field weight: a name hit is the strongest signal; doc-comment hits are ranked
+        // separately — summary > return value > parameter descriptions.
+        let hit = |label: &str| c.matched_in.contains(&label);
+        let field_weight = if hit("имя") { 1.0 } else if hit("параметры") { 0.7 } else { 0.6 }
+            + if hit("описание") { 0.2 } else { 0.0 }
+            + if hit("возвращаемое значение") { 0.15 } else { 0.0 }
+            + if hit("описание параметров") { 0.1 } else { 0.0 };
+
+        c.final_score = bm25_norm * call_weight * diversity_weight * domain_weight * field_weight;
     }
 
     // Sort by final_score descending
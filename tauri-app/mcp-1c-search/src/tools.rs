@@ -2,6 +2,8 @@ use std::path::{Path, PathBuf};
 use serde_json::{json, Value};
 use crate::search;
 use crate::index;
+use crate::parser::bsl_ast::normalize_directive;
+use crate::parser::form_xml::{self, FormStructure};
 
 /// Maps a 1C object type to its plural folder name in the config dump.
 fn object_type_to_folder(obj_type: &str) -> Option<&'static str> {
@@ -82,6 +84,14 @@ pub fn list_tools() -> Vec<Value> {
                         "items": { "type": "string" },
                         "description": "Объекты 1С для усиления релевантности. Форматы: 'Catalog.СтавкиНДС', 'Document.РеализацияТоваров'. Функции из этих объектов получают дополнительный вес."
                     },
+                    "subsystem": {
+                        "type": "string",
+                        "description": "Искать только среди функций объектов подсистемы (включая вложенные). Имя или путь: 'Продажи', 'Продажи/ОптовыеПродажи'."
+                    },
+                    "module_flag": {
+                        "type": "string",
+                        "description": "Только методы общих модулей с флагом: server (Сервер), server_call (Вызов сервера), client (Клиент), external_connection (Внешнее соединение), privileged (Привилегированный), global (Глобальный), cached (Повторное использование возвращаемых значений). Можно по-русски: ВызовСервера, Привилегированный…"
+                    },
                     "limit": {
                         "type": "integer",
                         "description": "Максимум результатов (по умолчанию 5, максимум 20).",
@@ -97,7 +107,7 @@ pub fn list_tools() -> Vec<Value> {
         }),
         json!({
             "name": "search_code",
-            "description": "Поиск по тексту кода 1С (BSL/XML). ⚠️ СТОП: если ищешь функцию по описанию задачи и не знаешь её имя — сначала вызови semantic_find (он специально создан для этого и работает за 1 вызов). search_code используй только когда semantic_find не помог или ты знаешь конкретный текстовый паттерн для поиска.",
+            "description": "Поиск по тексту кода 1С: модули BSL по индексу кода (полностью, без таймаута), затем XML-описания в пределах timeout_ms (отключается include_xml=false). ⚠️ СТОП: если ищешь функцию по описанию задачи и не знаешь её имя — сначала вызови semantic_find (он специально создан для этого и работает за 1 вызов). search_code используй только когда semantic_find не помог или ты знаешь конкретный текстовый паттерн для поиска.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -128,7 +138,12 @@ pub fn list_tools() -> Vec<Value> {
                     },
                     "timeout_ms": {
                         "type": "integer",
-                        "description": "Бюджет времени в мс (по умолчанию 8000). Уменьшите для быстрого count/preview."
+                        "description": "Бюджет времени в мс (по умолчанию 8000) для просмотра XML (include_xml) и для поиска, пока индекс кода строится."
+                    },
+                    "include_xml": {
+                        "type": "boolean",
+                        "description": "Искать также в XML-описаниях метаданных и форм (по умолчанию true). XML не индексируется и просматривается после модулей в пределах timeout_ms; false — только модули BSL.",
+                        "default": true
                     },
                     "include_summary": {
                         "type": "boolean",
@@ -140,6 +155,10 @@ pub fn list_tools() -> Vec<Value> {
                         "description": "Использовать регулярное выражение (по умолчанию false — регистронезависимый литеральный поиск)",
                         "default": false
                     },
+                    "subsystem": {
+                        "type": "string",
+                        "description": "Искать только в модулях объектов подсистемы (включая вложенные): 'Продажи' или 'Продажи/ОптовыеПродажи'. Игнорируется, если указан scope."
+                    },
                     "scope": {
                         "type": "string",
                         "description": "Ограничить поиск конкретным объектом 1С. Форматы: 'CommonModule.МодульИмя', 'Catalog.СправочникИмя', 'Document.ДокументИмя' и т.д. Можно также передать относительный путь: 'CommonModules/МодульИмя'. Если не указан — поиск по всей конфигурации."
@@ -186,6 +205,18 @@ pub fn list_tools() -> Vec<Value> {
                         "description": "Точное совпадение имени (по умолчанию false — поиск по подстроке)",
                         "default": false
                     },
+                    "directive": {
+                        "type": "string",
+                        "description": "Фильтр по директиве компиляции: НаСервере, НаКлиенте, НаСервереБезКонтекста, НаКлиентеНаСервереБезКонтекста (можно по-английски: AtServer, AtClient...)"
+                    },
+                    "subsystem": {
+                        "type": "string",
+                        "description": "Только символы из модулей объектов подсистемы (включая вложенные): 'Продажи' или 'Продажи/ОптовыеПродажи'."
+                    },
+                    "module_flag": {
+                        "type": "string",
+                        "description": "Только методы общих модулей с флагом: server (Сервер), server_call (Вызов сервера), client (Клиент), external_connection (Внешнее соединение), privileged (Привилегированный), global (Глобальный), cached (Повторное использование возвращаемых значений). Можно по-русски: ВызовСервера, Привилегированный…"
+                    },
                     "limit": {
                         "type": "integer",
                         "description": "Максимум результатов (по умолчанию 20)",
@@ -227,6 +258,10 @@ pub fn list_tools() -> Vec<Value> {
                         "type": "string",
                         "description": "Фильтр по части имени объекта (регистронезависимый). Например: 'файл' найдёт РаботаСФайлами, ФайлыСервер и т.д."
                     },
+                    "subsystem": {
+                        "type": "string",
+                        "description": "Только объекты, входящие в подсистему (включая вложенные): 'Продажи' или 'Продажи/ОптовыеПродажи'."
+                    },
                     "limit": {
                         "type": "integer",
                         "description": "Максимум результатов (по умолчанию 100, максимум 500)",
@@ -237,7 +272,7 @@ pub fn list_tools() -> Vec<Value> {
         }),
         json!({
             "name": "get_object_structure",
-            "description": "Получить полную структуру объекта конфигурации 1С: реквизиты, табличные части, формы, команды, модули.",
+            "description": "Получить полную структуру объекта конфигурации 1С: реквизиты и колонки табличных частей с типами (CatalogRef.Номенклатура, Number(15,2)), синонимами, признаками индексирования и проверки заполнения; формы, команды, модули.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -249,9 +284,119 @@ pub fn list_tools() -> Vec<Value> {
                 "required": ["object"]
             }
         }),
+        json!({
+            "name": "get_form_structure",
+            "description": "Структура управляемой формы из Form.xml: элементы с путями к данным, реквизиты формы, команды и обработчики событий (какая процедура модуля формы вызывается на ПриИзменении и т.п.).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "object": {
+                        "type": "string",
+                        "description": "Объект-владелец формы: Document.РеализацияТоваров, РеализацияТоваров или CommonForm.ИмяФормы"
+                    },
+                    "form": {
+                        "type": "string",
+                        "description": "Имя формы (например: ФормаДокумента). Если не указано и форм несколько — будет выведен их список"
+                    }
+                },
+                "required": ["object"]
+            }
+        }),
+        json!({
+            "name": "get_metadata_dependencies",
+            "description": "Связи объекта на уровне метаданных (из типов реквизитов, движений документов и связанных планов): какие объекты он использует и какие объекты ссылаются на него, в том числе через определяемые типы. Отвечает на вопросы «где используется справочник» и «от чего зависит документ» без поиска по коду.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "object": {
+                        "type": "string",
+                        "description": "Объект метаданных: Catalog.Контрагенты или просто Контрагенты"
+                    },
+                    "direction": {
+                        "type": "string",
+                        "enum": ["uses", "used_by", "both"],
+                        "description": "uses — от чего зависит объект, used_by — кто ссылается на объект, both — оба направления (по умолчанию)"
+                    }
+                },
+                "required": ["object"]
+            }
+        }),
+        json!({
+            "name": "find_predefined",
+            "description": "Проверить существование значения перечисления или предопределённого элемента справочника/плана счетов/плана видов характеристик. Принимает ссылку в любой форме: Перечисления.СтавкиНДС.НДС20, ПредопределенноеЗначение(\"Справочник.Валюты.Рубль\"), Перечисление.СтавкиНДС (список значений) или просто имя НДС20. Используйте перед тем как писать такие ссылки в коде.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "reference": {
+                        "type": "string",
+                        "description": "Ссылка на значение: Перечисления.СтавкиНДС.НДС20, ПредопределенноеЗначение(\"ПланСчетов.Хозрасчетный.Касса\"), Справочник.Валюты или НДС20"
+                    }
+                },
+                "required": ["reference"]
+            }
+        }),
+        json!({
+            "name": "get_object_rights",
+            "description": "Какие роли дают права на объект метаданных (Чтение, Добавление, Изменение, Удаление, Проведение, интерактивные права) и с какими ограничениями RLS. Отвечает на вопросы «почему пользователь не может провести документ» и «у кого есть доступ к справочнику» по выгрузке ролей.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "object": {
+                        "type": "string",
+                        "description": "Объект метаданных: Document.РеализацияТоваров или просто РеализацияТоваров"
+                    },
+                    "right": {
+                        "type": "string",
+                        "description": "Показать только одно право: Posting или Проведение, Read или Чтение, InteractiveDelete..."
+                    }
+                },
+                "required": ["object"]
+            }
+        }),
+        json!({
+            "name": "get_role_rights",
+            "description": "Все права роли по объектам метаданных: разрешённые права, ограничения RLS, права на отдельные поля и шаблоны ограничений роли.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "role": {
+                        "type": "string",
+                        "description": "Имя роли: ДобавлениеИзменениеДокументовПродаж или Role.ПолныеПрава"
+                    },
+                    "object_type": {
+                        "type": "string",
+                        "description": "Только объекты одного типа: Catalog, Document, InformationRegister..."
+                    }
+                },
+                "required": ["role"]
+            }
+        }),
+        json!({
+            "name": "find_queries_using",
+            "description": "Найти тексты запросов в коде, которые читают таблицу объекта метаданных: РегистрНакопления.ТоварыНаСкладах, в том числе через виртуальные таблицы (.Остатки, .Обороты) и табличные части. Показывает процедуру, строки запроса и поля, которые запрос берёт из таблицы. Тексты запросов разбираются при индексации, поиск не сканирует файлы.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "table": {
+                        "type": "string",
+                        "description": "Таблица: РегистрНакопления.ТоварыНаСкладах, AccumulationRegister.ТоварыНаСкладах, РегистрНакопления.ТоварыНаСкладах.Остатки или просто ТоварыНаСкладах"
+                    },
+                    "field": {
+                        "type": "string",
+                        "description": "Только запросы, читающие это поле таблицы (например: Номенклатура)"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Максимум запросов (по умолчанию 50)",
+                        "default": 50
+                    }
+                },
+                "required": ["table"]
+            }
+        }),
         json!({
             "name": "find_references",
-            "description": "Найти все вхождения символа (процедуры, функции, переменной) в коде конфигурации. Показывает где и как используется символ.",
+            "description": "Найти все вхождения символа (процедуры, функции, переменной) в модулях конфигурации — полностью, по индексу кода. Показывает где и как используется символ.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -284,13 +429,117 @@ pub fn list_tools() -> Vec<Value> {
         }),
         json!({
             "name": "get_function_context",
-            "description": "Граф вызовов функции или процедуры: что она вызывает и кто её вызывает. Помогает понять зависимости и контекст использования.",
+            "description": "Граф вызовов функции или процедуры: что она вызывает и кто её вызывает, с группировкой по модулям. Квалифицированные вызовы (ОбщийМодуль.Метод, Справочники.Имя.Метод) связываются с конкретным модулем.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "function_name": {
+                        "type": "string",
+                        "description": "Имя функции или процедуры, можно с модулем (например: ПровестиДокумент, ОбщегоНазначения.ЗначениеРеквизитаОбъекта, Справочники.Валюты.НайтиПоКоду)"
+                    }
+                },
+                "required": ["function_name"]
+            }
+        }),
+        json!({
+            "name": "get_call_tree",
+            "description": "Транзитивное дерево вызовов метода: что он вызывает (и что вызывают те методы) или кто его вызывает, на заданную глубину. Учитываются разрешённые вызовы (свой модуль, общие модули, модули менеджеров), подписки на события, регламентные задания, общие команды и обработчики форм. Циклы и повторно встреченные методы помечаются.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "function_name": {
+                        "type": "string",
+                        "description": "Имя функции или процедуры, можно с модулем (например: ОбменСБанком.ОтправитьПлатежи, Document.Заказ.ObjectModule.ОбработкаПроведения)"
+                    },
+                    "direction": {
+                        "type": "string",
+                        "enum": ["callees", "callers"],
+                        "description": "callees — что вызывает метод (по умолчанию), callers — кто вызывает метод",
+                        "default": "callees"
+                    },
+                    "depth": {
+                        "type": "integer",
+                        "description": "Глубина дерева (по умолчанию 3, максимум 6)",
+                        "default": 3
+                    },
+                    "max_children": {
+                        "type": "integer",
+                        "description": "Максимум вызовов, раскрываемых у одного метода (по умолчанию 20, максимум 100)",
+                        "default": 20
+                    }
+                },
+                "required": ["function_name"]
+            }
+        }),
+        json!({
+            "name": "find_call_path",
+            "description": "Кратчайшие цепочки вызовов от метода A к методу B: как, например, проведение документа приводит к вызову функции обмена с банком. Поиск в ширину по разрешённым вызовам, с защитой от циклов и ограничением ветвления.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "description": "Начальный метод, можно с модулем (например: Document.РеализацияТоваров.ObjectModule.ОбработкаПроведения)"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "Конечный метод, можно с модулем (например: ОбменСБанком.ОтправитьПлатежи)"
+                    },
+                    "max_depth": {
+                        "type": "integer",
+                        "description": "Максимальная длина цепочки в вызовах (по умолчанию 8, максимум 12)",
+                        "default": 8
+                    },
+                    "max_paths": {
+                        "type": "integer",
+                        "description": "Максимум цепочек в ответе (по умолчанию 3, максимум 10)",
+                        "default": 3
+                    },
+                    "max_fan_out": {
+                        "type": "integer",
+                        "description": "Сколько вызовов одного метода просматривать (по умолчанию 200, максимум 1000)",
+                        "default": 200
+                    }
+                },
+                "required": ["from", "to"]
+            }
+        }),
+        json!({
+            "name": "find_unused_symbols",
+            "description": "Поиск мёртвого кода: неэкспортные методы, которые не вызываются в своём модуле, и экспортные, которые не вызываются нигде. Точки входа не попадают в результат: обработчики событий форм и модулей объектов (ПередЗаписью, ОбработкаПроведения…), подписок на события, регламентных заданий, HTTP- и веб-сервисов, команд, перехватчики расширений и методы, имена которых встречаются в строках (Выполнить, ОписаниеОповещения). Результат сгруппирован по объектам.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "module_path": {
+                        "type": "string",
+                        "description": "Ограничить модулями объекта или пути (например: CommonModule.ОбменСБанком, Documents/Заказ). По умолчанию вся конфигурация."
+                    },
+                    "subsystem": {
+                        "type": "string",
+                        "description": "Только модули объектов подсистемы (включая вложенные). Имя или путь: 'Продажи', 'Продажи/ОптовыеПродажи'."
+                    },
+                    "include_exported": {
+                        "type": "boolean",
+                        "description": "Проверять и экспортные методы (по умолчанию true). false — только неэкспортные, вызовы которых видны целиком.",
+                        "default": true
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Максимум методов в ответе; объекты выводятся целиком (по умолчанию 200, максимум 1000)",
+                        "default": 200
+                    }
+                }
+            }
+        }),
+        json!({
+            "name": "get_symbol_doc",
+            "description": "Описание процедуры или функции из комментария над ней: назначение, параметры с типами, возвращаемое значение, пример вызова. Сверяет описанные параметры с сигнатурой.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
                     "function_name": {
                         "type": "string",
-                        "description": "Имя функции или процедуры (например: ПровестиДокумент, РассчитатьСумму)"
+                        "description": "Имя функции или процедуры, можно с модулем (например: ОбщегоНазначения.ЗначениеРеквизитаОбъекта)"
                     }
                 },
                 "required": ["function_name"]
@@ -306,6 +555,18 @@ pub fn list_tools() -> Vec<Value> {
                         "type": "string",
                         "description": "Путь к модулю или его имя. Форматы: 'CommonModule.МодульИмя', 'CommonModules/МодульИмя/Module.bsl', просто 'МодульИмя'"
                     },
+                    "directive": {
+                        "type": "string",
+                        "description": "Фильтр по директиве компиляции: НаСервере, НаКлиенте, НаСервереБезКонтекста, НаКлиентеНаСервереБезКонтекста (можно по-английски: AtServer, AtClient...)"
+                    },
+                    "region": {
+                        "type": "string",
+                        "description": "Только методы из области #Область (включая вложенные), например: ПрограммныйИнтерфейс, СлужебныйПрограммныйИнтерфейс, СлужебныеПроцедурыИФункции"
+                    },
+                    "module_flag": {
+                        "type": "string",
+                        "description": "Только общие модули с флагом (server, server_call, client, external_connection, privileged, global, cached), например module_path='CommonModules' + module_flag='server_call' — все методы модулей с вызовом сервера"
+                    },
                     "limit": {
                         "type": "integer",
                         "description": "Максимум результатов (по умолчанию 200)",
@@ -315,6 +576,38 @@ pub fn list_tools() -> Vec<Value> {
                 "required": ["module_path"]
             }
         }),
+        json!({
+            "name": "get_module_outline",
+            "description": "Структура модуля BSL: дерево областей #Область с методами в каждой, переменные модуля (Перем). Используй перед добавлением кода, чтобы поместить метод в нужную область (ПрограммныйИнтерфейс, СлужебныйПрограммныйИнтерфейс, СлужебныеПроцедурыИФункции).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "module_path": {
+                        "type": "string",
+                        "description": "Путь к модулю или его имя. Форматы: 'CommonModule.МодульИмя', 'CommonModules/МодульИмя/Ext/Module.bsl', 'Catalogs/Валюты/Ext/ManagerModule.bsl' (в проекте EDT — без 'Ext/')"
+                    }
+                },
+                "required": ["module_path"]
+            }
+        }),
+        json!({
+            "name": "check_call_contexts",
+            "description": "Проверка контекстов вызовов по графу вызовов: клиентский метод (&НаКлиенте) напрямую вызывает серверный модуль без «Вызова сервера», серверный код вызывает клиентский, &НаСервереБезКонтекста вызывает контекстный метод формы и т.п.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "scope": {
+                        "type": "string",
+                        "description": "Ограничить проверку объектом или папкой: 'Document.РеализацияТоваров', 'CommonModules/МойМодуль'. По умолчанию — вся конфигурация"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Максимум нарушений (по умолчанию 50)",
+                        "default": 50
+                    }
+                }
+            }
+        }),
         json!({
             "name": "smart_find",
             "description": "Умный поиск функции/процедуры по имени: находит символ в индексе (1 мс) и возвращает полный код за один вызов. Используй ВМЕСТО search_code когда знаешь имя функции.",
@@ -374,6 +667,10 @@ pub fn list_tools() -> Vec<Value> {
                         "type": "string",
                         "description": "Фильтр по типу объекта 1С: CommonModule, Catalog, Document, Report, DataProcessor и т.д."
                     },
+                    "subsystem": {
+                        "type": "string",
+                        "description": "Только файлы объектов подсистемы (включая вложенные): 'Продажи' или 'Продажи/ОптовыеПродажи'."
+                    },
                     "extension": {
                         "type": "string",
                         "description": "Фильтр по расширению файла: bsl, xml."
@@ -430,10 +727,22 @@ pub async fn call_tool(
         "get_symbol_context" => handle_get_symbol_context(args, config_path, db_path).await,
         "list_objects" => handle_list_objects(args, db_path).await,
         "get_object_structure" => handle_get_object_structure(args, db_path, config_path).await,
-        "find_references" => handle_find_references(args, config_path).await,
+        "find_references" => handle_find_references(args, config_path, db_path).await,
+        "find_queries_using" => handle_find_queries_using(args, db_path).await,
         "impact_analysis" => handle_impact_analysis(args, config_path, db_path).await,
         "get_function_context" => handle_get_function_context(args, db_path).await,
+        "get_call_tree" => handle_get_call_tree(args, db_path).await,
+        "find_call_path" => handle_find_call_path(args, db_path).await,
+        "find_unused_symbols" => handle_find_unused_symbols(args, db_path).await,
+        "get_symbol_doc" => handle_get_symbol_doc(args, db_path).await,
+        "get_form_structure" => handle_get_form_structure(args, db_path).await,
+        "get_metadata_dependencies" => handle_get_metadata_dependencies(args, db_path).await,
+        "find_predefined" => handle_find_predefined(args, db_path).await,
+        "get_object_rights" => handle_get_object_rights(args, db_path).await,
+        "get_role_rights" => handle_get_role_rights(args, db_path).await,
         "get_module_functions" => handle_get_module_functions(args, db_path).await,
+        "get_module_outline" => handle_get_module_outline(args, db_path).await,
+        "check_call_contexts" => handle_check_call_contexts(args, db_path).await,
         "smart_find" => handle_smart_find(args, config_path, db_path).await,
         "semantic_find" => handle_semantic_find(args, config_path, db_path).await,
         "find_function_in_object" => handle_find_function_in_object(args, config_path, db_path).await,
@@ -459,6 +768,7 @@ async fn handle_search_code(
     if query.trim().is_empty() {
         return Err("Параметр 'query' не может быть пустым".to_string());
     }
+    let subsystem = subsystem_from_args(args, db_path)?;
 
     // Auto-semantic: if query looks like natural language (function search by description),
     // prepend semantic_find results before the text search results.
@@ -474,7 +784,7 @@ async fn handle_search_code(
         db_path.as_ref()
             .and_then(|db| rusqlite::Connection::open(db).ok())
             .map(|conn| {
-                let results = crate::semantic::semantic_search(&conn, query, &[], 3);
+                let results = crate::semantic::semantic_search(&conn, query, &[], subsystem.as_deref(), None, 3);
                 if results.is_empty() {
                     String::new()
                 } else {
@@ -506,6 +816,7 @@ async fn handle_search_code(
     let use_regex = args["regex"].as_bool().unwrap_or(false);
     let include_summary = args["include_summary"].as_bool().unwrap_or(true);
     let timeout_ms = args["timeout_ms"].as_u64().unwrap_or(8_000);
+    let include_xml = args["include_xml"].as_bool().unwrap_or(true);
 
     // output_mode: "content" | "files_with_matches" | "count"
     let output_mode = args["output_mode"].as_str().unwrap_or("content");
@@ -558,16 +869,19 @@ async fn handle_search_code(
         }
     }
 
-    let scope_label = args["scope"].as_str()
-        .filter(|s| !s.trim().is_empty())
-        .map(|s| format!(" в «{}»", s))
-        .unwrap_or_default();
+    // An explicit scope is narrower than any subsystem
+    let subsystem = subsystem.filter(|_| sub_path.is_none());
+    let scope_label = match (args["scope"].as_str().filter(|s| !s.trim().is_empty()), &subsystem) {
+        (Some(s), _) => format!(" в «{}»", s),
+        (None, Some(_)) => format!(" в подсистеме «{}»", args["subsystem"].as_str().unwrap_or("").trim()),
+        (None, None) => String::new(),
+    };
 
     let root_clone = root.clone();
     let db_clone = db_path.clone();
     let query_owned = query.to_string();
     let query_lower = query.to_lowercase();
-    let use_index_hint = sub_path.is_none() && !use_regex && !query.contains(' ');
+    let use_index_hint = sub_path.is_none() && subsystem.is_none() && !use_regex && !query.contains(' ');
     let sub_path_clone = sub_path.clone();
 
     let start_time = std::time::Instant::now();
@@ -578,9 +892,9 @@ async fn handle_search_code(
             let fetch_limit = 5000usize;
             let (results, timed_out) = tokio::task::spawn_blocking(move || {
                 execute_text_search(
-                    &root_clone, sub_path_clone.as_deref(),
+                    &root_clone, sub_path_clone.as_deref(), subsystem.as_deref(),
                     &db_clone, &query_owned, &query_lower,
-                    use_regex, use_index_hint, fetch_limit, timeout_ms,
+                    use_regex, use_index_hint, include_xml, fetch_limit, timeout_ms,
                 )
             })
             .await
@@ -634,9 +948,9 @@ async fn handle_search_code(
             let fetch_limit = (offset + head_limit * 10).max(200);
             let (results, timed_out) = tokio::task::spawn_blocking(move || {
                 execute_text_search(
-                    &root_clone, sub_path_clone.as_deref(),
+                    &root_clone, sub_path_clone.as_deref(), subsystem.as_deref(),
                     &db_clone, &query_owned, &query_lower,
-                    use_regex, use_index_hint, fetch_limit, timeout_ms,
+                    use_regex, use_index_hint, include_xml, fetch_limit, timeout_ms,
                 )
             })
             .await
@@ -721,9 +1035,9 @@ async fn handle_search_code(
             let fetch_limit = offset + head_limit;
             let (results, timed_out) = tokio::task::spawn_blocking(move || {
                 execute_text_search(
-                    &root_clone, sub_path_clone.as_deref(),
+                    &root_clone, sub_path_clone.as_deref(), subsystem.as_deref(),
                     &db_clone, &query_owned, &query_lower,
-                    use_regex, use_index_hint, fetch_limit, timeout_ms,
+                    use_regex, use_index_hint, include_xml, fetch_limit, timeout_ms,
                 )
             })
             .await
@@ -876,19 +1190,38 @@ fn build_files_with_matches_summary(
     summary_text
 }
 
-/// Shared execution core: index-guided or streaming scan.
-/// Returns (results, timed_out). Caller handles output_mode / pagination.
+/// Shared execution core: modules through the code index (plus streamed XML if `include_xml`),
+/// a scan of the files of the objects in `subsystem`, or — while the code index is not built —
+/// an index-guided or streaming scan. Returns (results, timed_out). Caller handles output_mode / pagination.
 fn execute_text_search(
     root: &PathBuf,
     sub_path: Option<&std::path::Path>,
+    subsystem: Option<&str>,
     db_path: &Option<PathBuf>,
     query: &str,
     query_lower: &str,
     use_regex: bool,
     use_index_hint: bool,
+    include_xml: bool,
     limit: usize,
     timeout_ms: u64,
 ) -> (Vec<search::SearchResult>, bool) {
+    if let (Some(sub), Some(db)) = (subsystem, db_path.as_deref()) {
+        let files = index::subsystem_files(db, sub);
+        return (search::search_code_in_file_set(root, &files, query, use_regex, limit), false);
+    }
+    if let Some(db) = db_path.as_deref() {
+        if let Some(mut results) = search::search_modules_indexed(root, db, sub_path, query, use_regex, limit) {
+            if !include_xml || results.len() >= limit {
+                return (results, false);
+            }
+            // Only the XML pass has a time budget
+            let (xml, timed_out) =
+                search::search_xml(root, sub_path, query, use_regex, limit - results.len(), Some(timeout_ms));
+            results.extend(xml);
+            return (results, timed_out);
+        }
+    }
     if use_index_hint {
         if let Some(db) = db_path.as_deref() {
             let hint_query = if query_lower.contains('.') {
@@ -925,7 +1258,7 @@ async fn handle_get_file_context(
         if p.is_absolute() {
             p.to_path_buf()
         } else if let Some(root) = config_path {
-            root.join(file_str)
+            index::source_path(root, &file_str.replace('\\', "/"))
         } else {
             p.to_path_buf()
         }
@@ -952,12 +1285,14 @@ async fn handle_find_symbol(args: &Value, db_path: &Option<PathBuf>) -> Result<V
 
     let exact = args["exact"].as_bool().unwrap_or(false);
     let limit = args["limit"].as_u64().unwrap_or(20).clamp(1, 100) as usize;
+    let mut filter = symbol_filter_from_args(args)?;
+    filter.subsystem = subsystem_from_args(args, db_path)?;
 
     let db_clone = db.clone();
     let query_owned = query.to_string();
 
     let results = tokio::task::spawn_blocking(move || {
-        index::find_symbols(&db_clone, &query_owned, exact, limit)
+        index::find_symbols(&db_clone, &query_owned, exact, limit, &filter)
     })
     .await
     .map_err(|e| format!("Ошибка поиска: {}", e))??;
@@ -977,8 +1312,8 @@ async fn handle_find_symbol(args: &Value, db_path: &Option<PathBuf>) -> Result<V
     for r in &results {
         let export_mark = if r.is_export { " Экспорт" } else { "" };
         text.push_str(&format!(
-            "**{}** ({}{}) — `{}` строки {}-{}\n",
-            r.name, r.kind, export_mark, r.file, r.start_line, r.end_line
+            "**{}** ({}{}{}) — `{}` строки {}-{}\n",
+            r.signature(), r.kind, export_mark, directive_suffix(&r.directive), r.file, r.start_line, r.end_line
         ));
     }
     text.push_str("\nИспользуйте get_symbol_context для получения полного кода.");
@@ -1008,7 +1343,7 @@ async fn handle_get_symbol_context(
     let result = tokio::task::spawn_blocking(move || {
         // Normalize path separators to forward slash (stored in index as /)
         let file_normalized = file_owned.replace('\\', "/");
-        let file_path = root_clone.join(file_normalized.replace('/', std::path::MAIN_SEPARATOR_STR));
+        let file_path = index::source_path(&root_clone, &file_normalized);
 
         // Try to find the enclosing symbol in the index
         if let Some(sym) = index::find_symbol_at_line(&db_clone, &file_normalized, line) {
@@ -1055,11 +1390,12 @@ async fn handle_list_objects(args: &Value, db_path: &Option<PathBuf>) -> Result<
 
     let type_filter = args["type"].as_str().map(|s| s.to_string());
     let name_filter = args["name_filter"].as_str().map(|s| s.to_string());
+    let subsystem = subsystem_from_args(args, db_path)?;
     let limit = args["limit"].as_u64().unwrap_or(100).clamp(1, 500) as usize;
     let db_clone = db.clone();
 
     let objects = tokio::task::spawn_blocking(move || {
-        index::list_objects(&db_clone, type_filter.as_deref(), name_filter.as_deref(), limit)
+        index::list_objects(&db_clone, type_filter.as_deref(), name_filter.as_deref(), subsystem.as_deref(), limit)
     })
     .await
     .map_err(|e| format!("Ошибка выполнения: {}", e))??;
@@ -1068,7 +1404,7 @@ async fn handle_list_objects(args: &Value, db_path: &Option<PathBuf>) -> Result<
         let hint = if args["type"].is_string() {
             "Проверьте правильность типа объекта (Catalog, Document, CommonModule и т.д.) или запустите переиндексацию."
         } else {
-            "Метаданные не проиндексированы. Убедитесь, что в директории конфигурации есть Configuration.xml и индексация завершена."
+            "Метаданные не проиндексированы. Убедитесь, что в директории конфигурации есть Configuration.xml (в проекте EDT — src/Configuration/Configuration.mdo) и индексация завершена."
         };
         return Ok(json!({
             "content": [{ "type": "text", "text": format!("Объекты не найдены. {}", hint) }]
@@ -1098,6 +1434,128 @@ async fn handle_list_objects(args: &Value, db_path: &Option<PathBuf>) -> Result<
     Ok(json!({ "content": [{ "type": "text", "text": text }] }))
 }
 
+/// `Курс: Number(15,4) — «Курс валюты» [индекс, проверка заполнения] // комментарий`
+fn format_object_attribute(attr: &index::ObjectAttribute) -> String {
+    let mut line = attr.name.clone();
+    if let Some(value_type) = &attr.value_type {
+        line.push_str(&format!(": {}", value_type));
+    }
+    if let Some(synonym) = attr.synonym.as_deref().filter(|s| *s != attr.name) {
+        line.push_str(&format!(" — «{}»", synonym));
+    }
+    let mut flags = Vec::new();
+    if matches!(attr.indexing.as_deref(), Some("Index") | Some("IndexWithAdditionalOrder")) {
+        flags.push("индекс");
+    }
+    if attr.fill_checking.as_deref() == Some("ShowError") {
+        flags.push("проверка заполнения");
+    }
+    if !flags.is_empty() {
+        line.push_str(&format!(" [{}]", flags.join(", ")));
+    }
+    if let Some(comment) = &attr.comment {
+        line.push_str(&format!(" // {}", comment));
+    }
+    line
+}
+
+/// Query-language name of a register type: `AccumulationRegister` → `РегистрНакопления`.
+fn register_query_prefix(obj_type: &str) -> Option<&'static str> {
+    match obj_type {
+        "AccumulationRegister" => Some("РегистрНакопления"),
+        "InformationRegister" => Some("РегистрСведений"),
+        "AccountingRegister" => Some("РегистрБухгалтерии"),
+        "CalculationRegister" => Some("РегистрРасчета"),
+        _ => None,
+    }
+}
+
+/// Russian label and value for an object property (register settings, handlers) as stored in the dump.
+fn register_property_label(name: &str, value: &str) -> (&'static str, String) {
+    let flag = |v: &str| if v == "true" { "да" } else { "нет" }.to_string();
+    match name {
+        "RegisterType" => ("Вид регистра", match value {
+            "Balance" => "Остатки".to_string(),
+            "Turnovers" => "Обороты".to_string(),
+            other => other.to_string(),
+        }),
+        "InformationRegisterPeriodicity" => ("Периодичность", match value {
+            "Nonperiodical" => "Непериодический".to_string(),
+            "Second" => "В пределах секунды".to_string(),
+            "Day" => "День".to_string(),
+            "Month" => "Месяц".to_string(),
+            "Quarter" => "Квартал".to_string(),
+            "Year" => "Год".to_string(),
+            "RecorderPosition" => "Позиция регистратора".to_string(),
+            other => other.to_string(),
+        }),
+        "WriteMode" => ("Режим записи", match value {
+            "Independent" => "Независимый".to_string(),
+            "RecorderSubordinate" => "Подчинение регистратору".to_string(),
+            other => other.to_string(),
+        }),
+        "Periodicity" => ("Периодичность", value.to_string()),
+        "ChartOfAccounts" => ("План счетов", value.to_string()),
+        "ChartOfCalculationTypes" => ("План видов расчета", value.to_string()),
+        "Correspondence" => ("Корреспонденция", flag(value)),
+        "Event" => ("Событие", value.to_string()),
+        "Handler" => ("Обработчик", value.to_string()),
+        "MethodName" => ("Имя метода", value.to_string()),
+        "ActionPeriod" => ("Период действия", flag(value)),
+        "BasePeriod" => ("Базовый период", flag(value)),
+        _ => ("", value.to_string()),
+    }
+}
+
+/// Russian label and value for a role flag stored as an object property by `build_metadata`.
+fn role_property_label(name: &str, value: &str) -> (&'static str, String) {
+    let flag = if value == "true" { "да" } else { "нет" };
+    match name {
+        "SetForNewObjects" => ("Права для новых объектов", flag.to_string()),
+        _ => ("", value.to_string()),
+    }
+}
+
+/// Virtual tables available for a register in queries, with their parameters.
+fn register_virtual_tables(obj_type: &str, name: &str, properties: &[(String, String)]) -> Vec<String> {
+    let Some(prefix) = register_query_prefix(obj_type) else {
+        return Vec::new();
+    };
+    let prop = |key: &str| properties.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+    let tables: Vec<&str> = match obj_type {
+        "AccumulationRegister" if prop("RegisterType") == Some("Turnovers") => vec![
+            "Обороты(&НачалоПериода, &КонецПериода, Периодичность, Условие)",
+        ],
+        "AccumulationRegister" => vec![
+            "Остатки(&Период, Условие)",
+            "Обороты(&НачалоПериода, &КонецПериода, Периодичность, Условие)",
+            "ОстаткиИОбороты(&НачалоПериода, &КонецПериода, Периодичность, МетодДополнения, Условие)",
+        ],
+        "InformationRegister" => match prop("InformationRegisterPeriodicity") {
+            None | Some("Nonperiodical") => Vec::new(),
+            Some(_) => vec!["СрезПоследних(&Период, Условие)", "СрезПервых(&Период, Условие)"],
+        },
+        "AccountingRegister" => {
+            let mut tables = vec![
+                "Остатки(&Период, УсловиеСчета, Субконто, Условие)",
+                "Обороты(&НачалоПериода, &КонецПериода, Периодичность, УсловиеСчета, Субконто, Условие, УсловиеКорСчета, КорСубконто)",
+                "ОстаткиИОбороты(&НачалоПериода, &КонецПериода, Периодичность, МетодДополнения, УсловиеСчета, Субконто, Условие)",
+            ];
+            if prop("Correspondence") == Some("true") {
+                tables.push("ОборотыДтКт(&НачалоПериода, &КонецПериода, Периодичность, УсловиеСчетаДт, СубконтоДт, УсловиеСчетаКт, СубконтоКт, Условие)");
+            }
+            tables.push("ДвиженияССубконто(&НачалоПериода, &КонецПериода, Условие, Порядок, Первые)");
+            tables
+        }
+        "CalculationRegister" if prop("ActionPeriod") == Some("true") => vec!["ФактическийПериодДействия(Условие)"],
+        _ => Vec::new(),
+    };
+    tables
+        .into_iter()
+        .map(|t| format!("{}.{}.{}", prefix, name, t))
+        .collect()
+}
+
 async fn handle_get_object_structure(
     args: &Value,
     db_path: &Option<PathBuf>,
@@ -1123,22 +1581,78 @@ async fn handle_get_object_structure(
         Some(d) => {
             let mut text = format!("## {}.{}\n\n", d.obj_type, d.name);
 
+            if !d.properties.is_empty() {
+                for (name, value) in &d.properties {
+                    let (label, value) = if d.obj_type == "Role" {
+                        role_property_label(name, value)
+                    } else {
+                        register_property_label(name, value)
+                    };
+                    let label = if label.is_empty() { name.as_str() } else { label };
+                    text.push_str(&format!("**{}**: {}\n", label, value));
+                }
+                text.push('\n');
+            }
+            if let Some(flags) = &d.module_flags {
+                text.push_str(&format!("**Флаги модуля**: {}\n\n", module_flag_labels(flags).join(", ")));
+            }
+            if !d.dimensions.is_empty() {
+                text.push_str(&format!("### Измерения ({})\n", d.dimensions.len()));
+                for attr in &d.dimensions {
+                    text.push_str(&format!("- {}\n", format_object_attribute(attr)));
+                }
+                text.push('\n');
+            }
+            if !d.resources.is_empty() {
+                text.push_str(&format!("### Ресурсы ({})\n", d.resources.len()));
+                for attr in &d.resources {
+                    text.push_str(&format!("- {}\n", format_object_attribute(attr)));
+                }
+                text.push('\n');
+            }
             if !d.attributes.is_empty() {
                 text.push_str(&format!("### Реквизиты ({})\n", d.attributes.len()));
-                for attr in &d.attributes { text.push_str(&format!("- {}\n", attr)); }
+                for attr in &d.attributes {
+                    text.push_str(&format!("- {}\n", format_object_attribute(attr)));
+                }
                 text.push('\n');
             }
             if !d.tabular_sections.is_empty() {
                 text.push_str(&format!("### Табличные части ({})\n", d.tabular_sections.len()));
-                for (section, attrs) in &d.tabular_sections {
-                    if attrs.is_empty() {
-                        text.push_str(&format!("- **{}**\n", section));
-                    } else {
-                        text.push_str(&format!("- **{}**: {}\n", section, attrs.join(", ")));
+                for section in &d.tabular_sections {
+                    match section.synonym.as_deref().filter(|s| *s != section.name) {
+                        Some(synonym) => text.push_str(&format!("- **{}** — «{}»\n", section.name, synonym)),
+                        None => text.push_str(&format!("- **{}**\n", section.name)),
+                    }
+                    for attr in &section.attributes {
+                        text.push_str(&format!("  - {}\n", format_object_attribute(attr)));
                     }
                 }
                 text.push('\n');
             }
+            if !d.predefined.is_empty() {
+                let title = if d.obj_type == "Enum" { "Значения" } else { "Предопределённые элементы" };
+                text.push_str(&format!("### {} ({})\n", title, d.predefined.len()));
+                text.push_str(&format_predefined_values(&d.predefined, OBJECT_MAX_PREDEFINED));
+                text.push('\n');
+            }
+            if !d.register_records.is_empty() {
+                text.push_str(&format!("### Движения ({})\n", d.register_records.len()));
+                for register in &d.register_records { text.push_str(&format!("- {}\n", register)); }
+                text.push('\n');
+            }
+            if !d.recorders.is_empty() {
+                text.push_str(&format!("### Регистраторы ({})\n", d.recorders.len()));
+                for recorder in &d.recorders { text.push_str(&format!("- {}\n", recorder)); }
+                text.push('\n');
+            }
+            let virtual_tables = register_virtual_tables(&d.obj_type, &d.name, &d.properties);
+            if !virtual_tables.is_empty() {
+                text.push_str("### Виртуальные таблицы\n");
+                for table in &virtual_tables { text.push_str(&format!("- `{}`\n", table)); }
+                text.push('\n');
+            }
+            let layout = config_path.as_deref().map(index::SourceLayout::detect).unwrap_or_default();
             if !d.forms.is_empty() {
                 // Build folder prefix to check for form modules in filesystem
                 let folder_prefix = object_type_to_folder(d.obj_type.as_str())
@@ -1146,18 +1660,10 @@ async fn handle_get_object_structure(
                 text.push_str(&format!("### Формы ({})\n", d.forms.len()));
                 for form in &d.forms {
                     // Check if form module file exists on disk
-                    let form_has_module = folder_prefix.as_deref()
-                        .and_then(|prefix| config_path.as_ref().map(|root| {
-                            root.join(prefix).join("Forms").join(form)
-                                .join("Ext").join("Form").join("Module.bsl").exists()
-                        }))
-                        .unwrap_or(false);
-                    if form_has_module {
-                        let mod_path = format!(
-                            "{}/Forms/{}/Ext/Form/Module.bsl",
-                            folder_prefix.as_deref().unwrap_or(""),
-                            form
-                        );
+                    let form_module = folder_prefix.as_deref()
+                        .map(|prefix| layout.form_files(&format!("{}/Forms/{}", prefix, form)).1)
+                        .filter(|module| config_path.as_ref().is_some_and(|root| root.join(module).exists()));
+                    if let Some(mod_path) = form_module {
                         text.push_str(&format!("- **{}** — есть модуль: `get_module_functions` с `module_path=\"{}\"`\n", form, mod_path));
                     } else {
                         text.push_str(&format!("- {}\n", form));
@@ -1173,12 +1679,12 @@ async fn handle_get_object_structure(
             if !d.modules.is_empty() {
                 // d.modules contains bare names like "ObjectModule", "ManagerModule" from XML metadata.
                 // Build full path: Documents/БольничныйЛист/Ext/ObjectModule.bsl
-                let folder_prefix = object_type_to_folder(d.obj_type.as_str())
-                    .map(|f| format!("{}/{}", f, d.name));
+                let module_dir = object_type_to_folder(d.obj_type.as_str())
+                    .map(|f| layout.module_dir(f, &d.name));
                 text.push_str(&format!("### Модули ({})\n", d.modules.len()));
                 for m in &d.modules {
-                    let full_path = folder_prefix.as_deref()
-                        .map(|prefix| format!("{}/Ext/{}.bsl", prefix, m))
+                    let full_path = module_dir.as_deref()
+                        .map(|dir| format!("{}/{}.bsl", dir, m))
                         .unwrap_or_else(|| m.clone());
                     text.push_str(&format!("- `{}` → `get_module_functions` с `module_path=\"{}\"`\n", m, full_path));
                 }
@@ -1196,6 +1702,9 @@ async fn handle_get_object_structure(
             }
 
             if d.attributes.is_empty()
+                && d.predefined.is_empty()
+                && d.dimensions.is_empty()
+                && d.resources.is_empty()
                 && d.tabular_sections.is_empty()
                 && d.forms.is_empty()
                 && d.commands.is_empty()
@@ -1374,9 +1883,9 @@ fn scan_object_folder_fallback(
             .map(|e| e.path())?
     };
 
-    // forms: Vec<(name, has_module)>
-    let mut forms: Vec<(String, bool)> = Vec::new();
-    // modules: Vec<full relative path like "Ext/ObjectModule.bsl">
+    // forms: Vec<(name, module path inside the form folder)>
+    let mut forms: Vec<(String, Option<&str>)> = Vec::new();
+    // modules: Vec<relative path like "Ext/ObjectModule.bsl" (EDT: "ObjectModule.bsl")>
     let mut modules: Vec<String> = Vec::new();
     let mut templates: Vec<String> = Vec::new();
     let mut commands: Vec<String> = Vec::new();
@@ -1396,9 +1905,11 @@ fn scan_object_folder_fallback(
                                 let form_name = e.file_name().to_string_lossy().to_string();
                                 if form_name.starts_with('.') { continue; }
                                 // Check if form has a module: Forms/<FormName>/Ext/Form/Module.bsl
-                                let form_module = e.path()
-                                    .join("Ext").join("Form").join("Module.bsl");
-                                forms.push((form_name, form_module.exists()));
+                                // (EDT: Forms/<FormName>/Module.bsl)
+                                let form_module = ["Ext/Form/Module.bsl", "Module.bsl"]
+                                    .into_iter()
+                                    .find(|m| e.path().join(m).exists());
+                                forms.push((form_name, form_module));
                             }
                         }
                     }
@@ -1419,6 +1930,9 @@ fn scan_object_folder_fallback(
                 }
             } else if name == "Module.bsl" {
                 has_module = true;
+            } else if name.ends_with("Module.bsl") {
+                // EDT keeps object modules beside the object description
+                modules.push(name);
             }
         }
     }
@@ -1441,9 +1955,9 @@ fn scan_object_folder_fallback(
     }
     if !forms.is_empty() {
         out.push_str(&format!("### Формы ({})\n", forms.len()));
-        for (f, has_mod) in &forms {
-            if *has_mod {
-                let mod_path = format!("{}/{}/Forms/{}/Ext/Form/Module.bsl", folder_type, obj_name, f);
+        for (f, form_module) in &forms {
+            if let Some(m) = form_module {
+                let mod_path = format!("{}/{}/Forms/{}/{}", folder_type, obj_name, f, m);
                 out.push_str(&format!("- **{}** — есть модуль: `get_module_functions` с `module_path=\"{}\"`\n", f, mod_path));
             } else {
                 out.push_str(&format!("- {}\n", f));
@@ -1474,6 +1988,7 @@ fn scan_object_folder_fallback(
 async fn handle_find_references(
     args: &Value,
     config_path: &Option<PathBuf>,
+    db_path: &Option<PathBuf>,
 ) -> Result<Value, String> {
     let root = config_path
         .as_ref()
@@ -1486,24 +2001,27 @@ async fn handle_find_references(
 
     let limit = args["limit"].as_u64().unwrap_or(50).clamp(1, 200) as usize;
     let root_clone = root.clone();
+    let db_clone = db_path.clone();
     let symbol_owned = symbol.to_string();
 
+    // Complete in both cases: the code index narrows the modules to read; until it is built,
+    // the whole tree is scanned without a time budget.
     let start = std::time::Instant::now();
-    let (results, timed_out) = tokio::task::spawn_blocking(move || {
-        search::search_code(&root_clone, None, &symbol_owned, false, limit, Some(8_000))
+    let results = tokio::task::spawn_blocking(move || {
+        db_clone
+            .as_deref()
+            .and_then(|db| search::search_modules_indexed(&root_clone, db, None, &symbol_owned, false, limit))
+            .unwrap_or_else(|| search::search_code(&root_clone, None, &symbol_owned, false, limit, None).0)
     })
     .await
     .map_err(|e| format!("Ошибка поиска: {}", e))?;
     let elapsed = start.elapsed().as_millis();
 
     if results.is_empty() {
-        let note = if timed_out {
-            " Поиск прерван по таймауту (8с) — символ мог не встретиться в первых просмотренных файлах."
-        } else { "" };
         return Ok(json!({
             "content": [{
                 "type": "text",
-                "text": format!("Символ \"{}\" не найден в коде конфигурации. ({}мс){}", symbol, elapsed, note)
+                "text": format!("Символ \"{}\" не найден в коде конфигурации. ({}мс)", symbol, elapsed)
             }]
         }));
     }
@@ -1541,12 +2059,7 @@ async fn handle_find_references(
         }
         text.push('\n');
     }
-    if timed_out {
-        text.push_str(&format!(
-            "\n⚠️ *Поиск ограничен по времени (8с) — показаны первые {} результатов. Для полного поиска уточните область через `scope`.*",
-            results.len()
-        ));
-    } else if results.len() >= limit {
+    if results.len() >= limit {
         text.push_str(&format!(
             "*Показано {} результатов. Увеличьте `limit` для большего количества.*",
             limit
@@ -1589,11 +2102,33 @@ async fn handle_impact_analysis(
     const MAX_FILES: usize = 50;
     const EXAMPLES_PER_FILE: usize = 3;
 
-    let (details, hits, timed_out): (Option<index::ObjectDetails>, Vec<search::FileHits>, bool) =
+    let (details, md_deps, call_ctx, hits, timed_out): (
+        Option<index::ObjectDetails>,
+        Option<index::MetadataDependencies>,
+        Option<index::FunctionContext>,
+        Vec<search::FileHits>,
+        bool,
+    ) =
         tokio::task::spawn_blocking(move || {
             let details = db_clone
                 .as_deref()
                 .and_then(|db| index::get_object_details(db, &object_name_owned));
+            // Schema-level fallout: attributes and register records of other objects pointing here
+            let md_deps = details.as_ref().and_then(|d| {
+                let qualified = format!("{}.{}", d.obj_type, d.name);
+                db_clone.as_deref().and_then(|db| index::get_metadata_dependencies(db, &qualified))
+            });
+            // Call graph callers — only when the argument names a method exactly (prefix matches are noise here)
+            let method_lower = object_name_owned
+                .rsplit('.')
+                .next()
+                .unwrap_or("")
+                .trim()
+                .to_lowercase();
+            let call_ctx = db_clone
+                .as_deref()
+                .and_then(|db| index::get_function_context(db, &object_name_owned).ok())
+                .filter(|ctx| ctx.function.name.to_lowercase() == method_lower);
             let (hits, timed_out) = search::search_files_summary(
                 &root_clone,
                 &search_term_clone,
@@ -1602,7 +2137,7 @@ async fn handle_impact_analysis(
                 EXAMPLES_PER_FILE,
                 Some(8_000),
             );
-            (details, hits, timed_out)
+            (details, md_deps, call_ctx, hits, timed_out)
         })
         .await
         .map_err(|e| format!("Ошибка выполнения: {}", e))?;
@@ -1620,6 +2155,62 @@ async fn handle_impact_analysis(
         text.push('\n');
     }
 
+    if let Some(deps) = md_deps.as_ref().filter(|d| !d.used_by.is_empty()) {
+        let used_by = group_metadata_references(&deps.used_by, |r| r.object.as_str());
+        text.push_str(&format!("**Ссылки в метаданных** — {} объектов:\n", used_by.len()));
+        for (object, labels) in used_by.iter().take(20) {
+            text.push_str(&format!("- `{}` — {}\n", object, labels.join(", ")));
+        }
+        if used_by.len() > 20 {
+            text.push_str(&format!(
+                "- *...ещё {} объектов (см. get_metadata_dependencies)*\n",
+                used_by.len() - 20
+            ));
+        }
+        text.push('\n');
+    }
+
+    if let Some(ctx) = &call_ctx {
+        text.push_str(&format!(
+            "**Граф вызовов** — `{}` ({}:{}):\n",
+            index::module_label(&ctx.function.file), ctx.function.file, ctx.function.start_line
+        ));
+        let resolved: Vec<&index::CallerInfo> = ctx.called_by.iter().filter(|c| c.resolved).collect();
+        if resolved.is_empty() && ctx.form_bindings.is_empty() {
+            text.push_str("- *вызовов из других методов не найдено*\n");
+        } else {
+            let mut by_module: std::collections::BTreeMap<&str, Vec<&str>> = std::collections::BTreeMap::new();
+            for c in &resolved {
+                match entry_point_label(&c.edge_kind) {
+                    Some(label) => text.push_str(&format!("- `{}` — {}\n", c.name, label)),
+                    None => by_module.entry(c.file.as_str()).or_default().push(c.name.as_str()),
+                }
+            }
+            for (file, callers) in &by_module {
+                text.push_str(&format!(
+                    "- `{}` — {}\n",
+                    index::module_label(file),
+                    callers.join(", ")
+                ));
+            }
+        }
+        let unresolved = ctx.called_by.len() - resolved.len();
+        if unresolved > 0 {
+            text.push_str(&format!(
+                "- *ещё {} возможных вызовов через переменные (см. get_function_context)*\n",
+                unresolved
+            ));
+        }
+        if !ctx.form_bindings.is_empty() {
+            // Handlers are invoked by the platform: no callers in code does not mean unused
+            text.push_str("- *обработчик формы, вызывается платформой:*\n");
+            for line in format_form_bindings(&ctx.form_bindings).lines() {
+                text.push_str(&format!("  {}\n", line));
+            }
+        }
+        text.push('\n');
+    }
+
     if hits.is_empty() {
         text.push_str(&format!(
             "Ссылок на \"{}\" в коде конфигурации не найдено.\n",
@@ -1664,143 +2255,1418 @@ async fn handle_impact_analysis(
             );
         }
     }
-
-    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
+
+async fn handle_sync_index(
+    config_path: &Option<PathBuf>,
+    db_path: &Option<PathBuf>,
+) -> Result<Value, String> {
+    let root = config_path
+        .as_ref()
+        .ok_or("Конфигурация не настроена. Укажите путь в настройках MCP сервера.")?;
+    let db = db_path
+        .as_ref()
+        .ok_or("Нет пути к базе данных индекса")?;
+
+    let root = root.clone();
+    let db = db.clone();
+
+    let stats = tokio::task::spawn_blocking(move || index::sync_index(&root, &db))
+        .await
+        .map_err(|e| format!("Паника spawn_blocking: {}", e))?
+        .map_err(|e| format!("Ошибка синхронизации: {}", e))?;
+
+    let db_for_index = db_path.as_ref().unwrap();
+    let size = crate::db_size_mb(db_for_index);
+    // Use current time directly — avoids SQLite WAL caching issues when reading back built_at
+    let built_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    eprintln!("SEARCH_STATUS:ready:{}:{:.2}:{}", stats.total_symbols, size, built_at);
+
+    let text = if stats.added == 0 && stats.updated == 0 && stats.removed == 0 {
+        if stats.unchanged > 0 {
+            format!(
+                "✅ Индекс актуален. Изменённых BSL файлов не обнаружено (у {} файлов изменилась только дата, содержимое прежнее).",
+                stats.unchanged
+            )
+        } else {
+            "✅ Индекс актуален. Изменённых BSL файлов не обнаружено.".to_string()
+        }
+    } else {
+        format!(
+            "✅ Синхронизация завершена:\n- Новых файлов: {}\n- Изменённых: {}\n- Удалённых: {}\n- Затронутых без изменений (дата изменилась, содержимое нет): {}\n- Итого символов в индексе: {}",
+            stats.added, stats.updated, stats.removed, stats.unchanged, stats.total_symbols
+        )
+    };
+
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
+
+// ─── get_function_context ────────────────────────────────────────────────────
+
+async fn handle_get_function_context(
+    args: &Value,
+    db_path: &Option<PathBuf>,
+) -> Result<Value, String> {
+    let function_name = args["function_name"].as_str().ok_or("Параметр 'function_name' обязателен")?;
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
+
+    let ctx = index::get_function_context(db, function_name).map_err(|e| e.message(function_name))?;
+
+    let kind_label = if ctx.function.kind == "function" { "Функция" } else { "Процедура" };
+    let export_label = if ctx.function.is_export { " Экспорт" } else { "" };
+
+    let mut text = format!(
+        "## {}{} ({}, {}:{})\n\n",
+        ctx.function.name, export_label, kind_label,
+        ctx.function.file, ctx.function.start_line
+    );
+
+    if let Some(i) = &ctx.intercepts {
+        text.push_str(&format!(
+            "**Расширение {}:** &{}(\"{}\") — перехватывает `{}` из `{}`\n\n",
+            i.origin, i.kind, i.target, i.target,
+            index::module_label(index::split_origin(&i.file).1)
+        ));
+    }
+    if !ctx.overridden_by.is_empty() {
+        text.push_str(&format!("**Переопределён в расширениях ({}):**\n", ctx.overridden_by.len()));
+        for i in &ctx.overridden_by {
+            text.push_str(&format!(
+                "- {}: &{} → {} ({}:{})\n",
+                i.origin, i.kind, i.method, i.file, i.start_line
+            ));
+        }
+        text.push('\n');
+    }
+    if !ctx.form_bindings.is_empty() {
+        text.push_str(&format!("**Обработчик формы — вызывается платформой ({}):**\n", ctx.form_bindings.len()));
+        text.push_str(&format_form_bindings(&ctx.form_bindings));
+        text.push('\n');
+    }
+
+    if ctx.calls.is_empty() {
+        text.push_str("**Вызывает:** *(нет вызовов в индексе)*\n\n");
+    } else {
+        text.push_str(&format!("**Вызывает ({}):**\n", ctx.calls.len()));
+        // Grouped by resolved module; unresolved (platform methods, calls on variables) go last
+        let mut current: Option<Option<&str>> = None;
+        for callee in &ctx.calls {
+            let module = callee.file.as_deref();
+            if current != Some(module) {
+                match module {
+                    Some(f) => text.push_str(&format!("- `{}` ({})\n", index::module_label(f), f)),
+                    None => text.push_str("- *не разрешены (платформенные методы, вызовы через переменные):*\n"),
+                }
+                current = Some(module);
+            }
+            match &callee.receiver {
+                Some(r) => text.push_str(&format!("  - {}.{}\n", r, callee.name)),
+                None => text.push_str(&format!("  - {}\n", callee.name)),
+            }
+        }
+        text.push('\n');
+    }
+
+    if ctx.called_by.is_empty() {
+        text.push_str("**Вызывается из:** *(нет вызывающих в индексе)*\n");
+    } else {
+        text.push_str(&format!("**Вызывается из ({}):**\n", ctx.called_by.len()));
+        let mut current: Option<(&str, bool)> = None;
+        for caller in &ctx.called_by {
+            if let Some(label) = entry_point_label(&caller.edge_kind) {
+                text.push_str(&format!("- `{}` — {} ({})\n", caller.name, label, caller.file));
+                current = None;
+                continue;
+            }
+            let key = (caller.file.as_str(), caller.resolved);
+            if current != Some(key) {
+                let note = if caller.resolved { "" } else { " — *возможно, вызов через переменную*" };
+                text.push_str(&format!("- `{}` ({}){}\n", index::module_label(&caller.file), caller.file, note));
+                current = Some(key);
+            }
+            if caller.start_line > 0 {
+                text.push_str(&format!("  - {} (строка {})\n", caller.name, caller.start_line));
+            } else {
+                text.push_str(&format!("  - {}\n", caller.name));
+            }
+        }
+    }
+
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
+
+/// What invokes a method when the caller is a metadata object rather than code (see `CallerInfo::edge_kind`).
+fn entry_point_label(edge_kind: &str) -> Option<&'static str> {
+    match edge_kind {
+        "event_subscription" => Some("подписка на событие, вызывается платформой"),
+        "scheduled_job" => Some("регламентное задание, вызывается платформой"),
+        "http_service" => Some("обработчик метода HTTP-сервиса"),
+        "web_service" => Some("операция веб-сервиса"),
+        "common_command" => Some("общая команда, обработчик выполнения команды"),
+        _ => None,
+    }
+}
+
+/// One line per form event/command binding: `- Document.Заказ, форма ФормаДокумента: элемент Контрагент, ПриИзменении (OnChange)`.
+fn format_form_bindings(bindings: &[index::FormBinding]) -> String {
+    let mut text = String::new();
+    for b in bindings {
+        let what = if b.is_command {
+            format!("команда {}", b.event)
+        } else {
+            match &b.element {
+                Some(el) => format!("элемент {}, {}", el, form_event_label(&b.event)),
+                None => format!("событие формы {}", form_event_label(&b.event)),
+            }
+        };
+        text.push_str(&format!("- {}, форма {}: {}\n", b.owner, b.form, what));
+    }
+    text
+}
+
+// ─── get_call_tree / find_call_path ──────────────────────────────────────────
+
+async fn handle_get_call_tree(args: &Value, db_path: &Option<PathBuf>) -> Result<Value, String> {
+    let function_name = args["function_name"].as_str().ok_or("Параметр 'function_name' обязателен")?;
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
+    let direction = match args["direction"].as_str().unwrap_or("callees") {
+        "callees" => index::CallDirection::Callees,
+        "callers" => index::CallDirection::Callers,
+        other => return Err(format!("Неизвестное направление '{}': ожидается callees или callers", other)),
+    };
+    let depth = args["depth"].as_u64().unwrap_or(3).clamp(1, 6) as usize;
+    let max_children = args["max_children"].as_u64().unwrap_or(20).clamp(1, 100) as usize;
+
+    let db = db.clone();
+    let name = function_name.to_string();
+    let tree = tokio::task::spawn_blocking(move || index::get_call_tree(&db, &name, direction, depth, max_children))
+        .await
+        .map_err(|e| format!("Ошибка построения дерева вызовов: {}", e))?
+        .map_err(|e| e.message(function_name))?;
+
+    Ok(json!({ "content": [{ "type": "text", "text": format_call_tree(&tree, direction, depth) }] }))
+}
+
+async fn handle_find_call_path(args: &Value, db_path: &Option<PathBuf>) -> Result<Value, String> {
+    let from = args["from"].as_str().ok_or("Параметр 'from' обязателен")?.to_string();
+    let to = args["to"].as_str().ok_or("Параметр 'to' обязателен")?.to_string();
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?.clone();
+    let max_depth = args["max_depth"].as_u64().unwrap_or(8).clamp(1, 12) as usize;
+    let max_paths = args["max_paths"].as_u64().unwrap_or(3).clamp(1, 10) as usize;
+    let max_fan_out = args["max_fan_out"].as_u64().unwrap_or(200).clamp(1, 1000) as usize;
+
+    let found = tokio::task::spawn_blocking(move || {
+        index::find_call_paths(&db, &from, &to, max_depth, max_paths, max_fan_out)
+    })
+    .await
+    .map_err(|e| format!("Ошибка поиска цепочек вызовов: {}", e))??;
+
+    Ok(json!({ "content": [{ "type": "text", "text": format_call_paths(&found, max_depth, max_fan_out) }] }))
+}
+
+/// `Имя` — `CommonModule.Модуль` (file:line); a metadata caller is shown with its entry point kind.
+fn call_node_label(node: &index::CallNode, edge_kind: &str) -> String {
+    match entry_point_label(edge_kind) {
+        Some(label) => format!("`{}` — {} ({})", node.name, label, node.file),
+        None => format!(
+            "**{}** — `{}` ({}:{})",
+            node.name, index::module_label(&node.file), node.file, node.start_line
+        ),
+    }
+}
+
+/// Nested list of the tree, one method per line, children indented under their parent.
+fn format_call_tree(tree: &index::CallTreeNode, direction: index::CallDirection, depth: usize) -> String {
+    fn push_node(node: &index::CallTreeNode, level: usize, text: &mut String) {
+        let pad = "  ".repeat(level);
+        let mark = if node.cycle {
+            " ↻ *цикл*"
+        } else if node.repeated {
+            " *(раскрыт выше)*"
+        } else {
+            ""
+        };
+        text.push_str(&format!("{}- {}{}\n", pad, call_node_label(&node.method, &node.edge_kind), mark));
+        for line in format_form_bindings(&node.form_bindings).lines() {
+            text.push_str(&format!("{}  - обработчик формы: {}\n", pad, line.trim_start_matches("- ")));
+        }
+        for child in &node.children {
+            push_node(child, level + 1, text);
+        }
+        if node.omitted > 0 {
+            text.push_str(&format!("{}  - *…ещё {}*\n", pad, node.omitted));
+        }
+    }
+
+    let title = match direction {
+        index::CallDirection::Callees => "Что вызывает",
+        index::CallDirection::Callers => "Кто вызывает",
+    };
+    let mut text = format!("## {} {} (глубина {})\n\n", title, tree.method.name, depth);
+    push_node(tree, 0, &mut text);
+    if tree.children.is_empty() && tree.form_bindings.is_empty() {
+        text.push_str("\n*(нет разрешённых вызовов в индексе)*\n");
+    }
+    text
+}
+
+/// Numbered chains, one method per line.
+fn format_call_paths(found: &index::CallPaths, max_depth: usize, max_fan_out: usize) -> String {
+    let mut text = format!("## Цепочки вызовов: {} → {}\n\n", found.from.name, found.to.name);
+    if found.paths.is_empty() {
+        text.push_str(&format!(
+            "Цепочка не найдена: длина до {} вызовов, просмотрено методов: {}.\n",
+            max_depth, found.explored
+        ));
+    } else {
+        let calls = found.paths[0].len() - 1;
+        text.push_str(&format!(
+            "Кратчайших цепочек: {} (вызовов в цепочке: {}, просмотрено методов: {})\n\n",
+            found.paths.len(), calls, found.explored
+        ));
+        for (i, path) in found.paths.iter().enumerate() {
+            text.push_str(&format!("**{}.**\n", i + 1));
+            for (step, node) in path.iter().enumerate() {
+                text.push_str(&format!("{}. {}\n", step + 1, call_node_label(node, "call")));
+            }
+            text.push('\n');
+        }
+    }
+    if found.capped {
+        text.push_str(&format!(
+            "\n⚠️ *Поиск ограничен: у части методов больше {} вызовов или достигнут лимит просмотра — часть цепочек могла быть пропущена.*\n",
+            max_fan_out
+        ));
+    }
+    text.push_str("\n*Учитываются только разрешённые вызовы; вызовы через переменные не прослеживаются.*\n");
+    text
+}
+
+// ─── find_unused_symbols ─────────────────────────────────────────────────────
+
+async fn handle_find_unused_symbols(args: &Value, db_path: &Option<PathBuf>) -> Result<Value, String> {
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?.clone();
+    let module_path = args["module_path"].as_str().map(resolve_module_path).unwrap_or_default();
+    let mut filter = symbol_filter_from_args(args)?;
+    filter.subsystem = subsystem_from_args(args, db_path)?;
+    let include_exported = args["include_exported"].as_bool().unwrap_or(true);
+    let limit = args["limit"].as_u64().unwrap_or(200).clamp(1, 1000) as usize;
+
+    let unused = tokio::task::spawn_blocking(move || {
+        index::find_unused_symbols(&db, &module_path, &filter, include_exported, limit)
+    })
+    .await
+    .map_err(|e| format!("Ошибка поиска неиспользуемых методов: {}", e))?
+    .ok_or("Ошибка чтения индекса символов")?;
+
+    Ok(json!({ "content": [{ "type": "text", "text": format_unused_symbols(&unused) }] }))
+}
+
+/// Unused methods under a heading per object, with the module before its methods.
+fn format_unused_symbols(unused: &index::UnusedSymbols) -> String {
+    let mut text = String::from("## Неиспользуемые методы\n\n");
+    if unused.total == 0 {
+        text.push_str(&format!("Проверено методов: {}. Неиспользуемых не найдено.\n", unused.checked));
+        return text;
+    }
+    text.push_str(&format!(
+        "Найдено: {} из {} проверенных методов, объектов: {}.\n",
+        unused.total, unused.checked, unused.total_objects
+    ));
+    for (object, methods) in &unused.objects {
+        text.push_str(&format!("\n### {} ({})\n", object, methods.len()));
+        let mut module = "";
+        for sym in methods {
+            if sym.file != module {
+                module = &sym.file;
+                text.push_str(&format!("\n`{}` ({})\n", index::module_label(&sym.file), sym.file));
+            }
+            let kind = if sym.kind == "function" { "Функция" } else { "Процедура" };
+            let export = if sym.is_export { " Экспорт" } else { "" };
+            text.push_str(&format!(
+                "- **{}**{} — {}{} (строка {})\n",
+                sym.signature(), export, kind, directive_suffix(&sym.directive), sym.start_line
+            ));
+        }
+    }
+    if unused.objects.len() < unused.total_objects {
+        let listed: usize = unused.objects.iter().map(|(_, methods)| methods.len()).sum();
+        text.push_str(&format!(
+            "\n*Показаны объекты: {} из {} ({} методов) — уточните module_path или subsystem.*\n",
+            unused.objects.len(), unused.total_objects, listed
+        ));
+    }
+    text.push_str(
+        "\n*Вызовы через Выполнить с собранным в коде текстом, из внешних обработок и по COM/HTTP извне не видны индексу — проверьте метод перед удалением.*\n",
+    );
+    text
+}
+
+// ─── get_symbol_doc ──────────────────────────────────────────────────────────
+
+async fn handle_get_symbol_doc(
+    args: &Value,
+    db_path: &Option<PathBuf>,
+) -> Result<Value, String> {
+    let function_name = args["function_name"].as_str().ok_or("Параметр 'function_name' обязателен")?;
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
+
+    let index::SymbolDoc { symbol: sym, param_names: signature_names, doc } =
+        index::get_symbol_doc(db, function_name).map_err(|e| e.message(function_name))?;
+
+    let kind_label = if sym.kind == "function" { "Функция" } else { "Процедура" };
+    let export_label = if sym.is_export { " Экспорт" } else { "" };
+    let mut text = format!(
+        "## {}{} ({}, {}:{})\n\n",
+        sym.signature(), export_label, kind_label, sym.file, sym.start_line
+    );
+
+    let Some(doc) = doc else {
+        text.push_str("*Описание над методом отсутствует.*\n");
+        return Ok(json!({ "content": [{ "type": "text", "text": text }] }));
+    };
+
+    if !doc.summary.is_empty() {
+        text.push_str(&format!("{}\n\n", doc.summary));
+    }
+
+    if !doc.params.is_empty() || !signature_names.is_empty() {
+        text.push_str("**Параметры:**\n");
+        for p in &doc.params {
+            let known = signature_names.iter().any(|n| n.to_lowercase() == p.name.to_lowercase());
+            let note = if known { "" } else { " ⚠️ *нет в сигнатуре*" };
+            text.push_str(&format!("- `{}` — {}{}", p.name, p.types, note));
+            if !p.description.is_empty() {
+                text.push_str(&format!(": {}", p.description.replace('\n', " ")));
+            }
+            text.push('\n');
+        }
+        for name in &signature_names {
+            if !doc.params.iter().any(|p| p.name.to_lowercase() == name.to_lowercase()) {
+                text.push_str(&format!("- `{}` ⚠️ *не описан*\n", name));
+            }
+        }
+        text.push('\n');
+    }
+
+    if let Some(ret) = &doc.returns {
+        text.push_str(&format!("**Возвращаемое значение:** {}", ret.types));
+        if !ret.description.is_empty() {
+            text.push_str(&format!(" — {}", ret.description.replace('\n', " ")));
+        }
+        text.push_str("\n\n");
+    }
+
+    if !doc.examples.is_empty() {
+        text.push_str(&format!("**Пример:**\n```bsl\n{}\n```\n", doc.examples));
+    }
+
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
+
+// ─── get_module_functions ────────────────────────────────────────────────────
+
+async fn handle_get_module_functions(
+    args: &Value,
+    db_path: &Option<PathBuf>,
+) -> Result<Value, String> {
+    let module_path = args["module_path"].as_str().ok_or("Параметр 'module_path' обязателен")?;
+    let limit = args["limit"].as_u64().unwrap_or(200).clamp(1, 500) as usize;
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
+
+    let resolved = resolve_module_path(module_path);
+    let filter = symbol_filter_from_args(args)?;
+    let symbols = index::get_module_functions(db, &resolved, limit, &filter);
+
+    if symbols.is_empty() {
+        return Ok(json!({ "content": [{ "type": "text", "text": format!(
+            "Модуль «{}» не найден в индексе или не содержит функций.", module_path
+        )}] }));
+    }
+
+    let first_file = &symbols[0].file;
+    let mut text = match &filter.region {
+        Some(region) => format!("## Функции модуля `{}` в области «{}»\n\n", first_file, region),
+        None => format!("## Функции модуля `{}`\n\n", first_file),
+    };
+    let one_module = symbols.iter().all(|s| s.file == *first_file);
+    if let Some(flags) = index::common_module_flags(db, first_file).filter(|_| one_module) {
+        text.push_str(&format!("**Флаги модуля**: {}\n\n", module_flag_labels(&flags).join(", ")));
+    }
+
+    let total = symbols.len();
+    for sym in &symbols {
+        let kind = if sym.kind == "function" { "Функция" } else { "Процедура" };
+        let export = if sym.is_export { " Экспорт" } else { "" };
+        text.push_str(&format!(
+            "- **{}**{} — {}{} (строка {})\n",
+            sym.signature(), export, kind, directive_suffix(&sym.directive), sym.start_line
+        ));
+    }
+
+    if total == limit {
+        text.push_str(&format!("\n*Показано первых {} — уточните путь для фильтрации.*", limit));
+    }
+
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
+
+/// Resolve "CommonModule.МодульИмя" → "CommonModules/МодульИмя"; other forms are used as is.
+fn resolve_module_path(module_path: &str) -> String {
+    if let Some((type_part, name_part)) = module_path.split_once('.') {
+        if let Some(folder) = object_type_to_folder(type_part) {
+            return format!("{}/{}", folder, name_part);
+        }
+    }
+    module_path.to_string()
+}
+
+/// Build symbol query filters from tool arguments (`directive`, `region`).
+fn symbol_filter_from_args(args: &Value) -> Result<index::SymbolFilter, String> {
+    let directive = match args["directive"].as_str().map(str::trim).filter(|d| !d.is_empty()) {
+        Some(raw) => Some(
+            normalize_directive(raw)
+                .ok_or_else(|| format!("Неизвестная директива компиляции: {}", raw))?
+                .to_string(),
+        ),
+        None => None,
+    };
+    let region = args["region"]
+        .as_str()
+        .map(|r| r.trim().trim_start_matches('#').to_string())
+        .filter(|r| !r.is_empty());
+    let module_flag = module_flag_from_args(args)?;
+    Ok(index::SymbolFilter { directive, region, subsystem: None, module_flag })
+}
+
+/// `module_flag` argument values (English and Russian) → `index::MODULE_FLAG_CONDITIONS` key.
+const MODULE_FLAG_NAMES: &[(&str, &str)] = &[
+    ("server", "server"),
+    ("сервер", "server"),
+    ("servercall", "server_call"),
+    ("вызовсервера", "server_call"),
+    ("client", "client"),
+    ("клиент", "client"),
+    ("externalconnection", "external_connection"),
+    ("внешнеесоединение", "external_connection"),
+    ("privileged", "privileged"),
+    ("привилегированный", "privileged"),
+    ("global", "global"),
+    ("глобальный", "global"),
+    ("cached", "cached"),
+    ("returnvaluesreuse", "cached"),
+    ("повторноеиспользование", "cached"),
+];
+
+/// Canonical `module_flag` argument (`ServerCall`, `вызов_сервера` → `server_call`); `None` when not given.
+fn module_flag_from_args(args: &Value) -> Result<Option<String>, String> {
+    let Some(raw) = args["module_flag"].as_str().map(str::trim).filter(|f| !f.is_empty()) else {
+        return Ok(None);
+    };
+    let key: String = raw
+        .chars()
+        .filter(|c| !matches!(c, '_' | ' ' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect();
+    MODULE_FLAG_NAMES
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, flag)| Some(flag.to_string()))
+        .ok_or_else(|| format!(
+            "Неизвестный флаг общего модуля: {}. Допустимые: server, server_call, client, external_connection, privileged, global, cached",
+            raw
+        ))
+}
+
+/// Flags of a common module as shown in the configurator: `Сервер`, `Вызов сервера`, ...
+fn module_flag_labels(flags: &index::CommonModuleFlags) -> Vec<String> {
+    let mut labels: Vec<String> = [
+        (flags.global, "Глобальный"),
+        (flags.client_managed, "Клиент (управляемое приложение)"),
+        (flags.server, "Сервер"),
+        (flags.external_connection, "Внешнее соединение"),
+        (flags.client_ordinary, "Клиент (обычное приложение)"),
+        (flags.server_call, "Вызов сервера"),
+        (flags.privileged, "Привилегированный"),
+    ]
+    .iter()
+    .filter(|(set, _)| *set)
+    .map(|(_, label)| label.to_string())
+    .collect();
+    match flags.return_values_reuse.as_deref() {
+        Some("DuringRequest") => labels.push("Повторное использование: на время вызова".to_string()),
+        Some("DuringSession") => labels.push("Повторное использование: на время сеанса".to_string()),
+        _ => {}
+    }
+    labels
+}
+
+/// Normalized `subsystem` argument, checked against the metadata index; `None` when not given.
+fn subsystem_from_args(args: &Value, db_path: &Option<PathBuf>) -> Result<Option<String>, String> {
+    let Some(raw) = args["subsystem"].as_str().filter(|s| !s.trim().is_empty()) else {
+        return Ok(None);
+    };
+    let db = db_path.as_ref().ok_or("Фильтр по подсистеме требует индекса метаданных")?;
+    let subsystem = index::normalize_subsystem(raw);
+    if index::find_subsystem(db, &subsystem).is_some() {
+        return Ok(Some(subsystem));
+    }
+    let top = index::top_subsystems(db);
+    Err(if top.is_empty() {
+        format!("Подсистема «{}» не найдена: в индексе метаданных нет подсистем.", raw)
+    } else {
+        format!("Подсистема «{}» не найдена. Подсистемы верхнего уровня: {}", raw, top.join(", "))
+    })
+}
+
+/// `, &НаСервере` for symbols with a compilation directive, empty otherwise.
+fn directive_suffix(directive: &Option<String>) -> String {
+    directive.as_ref().map(|d| format!(", &{}", d)).unwrap_or_default()
+}
+
+// ─── get_module_outline ──────────────────────────────────────────────────────
+
+/// Maximum number of modules outlined in one response.
+const OUTLINE_MAX_FILES: usize = 5;
+
+async fn handle_get_module_outline(
+    args: &Value,
+    db_path: &Option<PathBuf>,
+) -> Result<Value, String> {
+    let module_path = args["module_path"].as_str().ok_or("Параметр 'module_path' обязателен")?;
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
+
+    let resolved = resolve_module_path(module_path);
+    let (outlines, total) = index::get_module_outline(db, &resolved, OUTLINE_MAX_FILES);
+    if outlines.is_empty() {
+        return Ok(json!({ "content": [{ "type": "text", "text": format!(
+            "Модуль «{}» не найден в индексе.", module_path
+        )}] }));
+    }
+
+    let mut text = String::new();
+    for outline in &outlines {
+        text.push_str(&format_module_outline(outline));
+        text.push('\n');
+    }
+    if total > outlines.len() {
+        text.push_str(&format!(
+            "*Показано {} из {} модулей — уточните путь (например, `.../Ext/ManagerModule.bsl`).*\n",
+            outlines.len(), total
+        ));
+    }
+
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
+
+/// Render regions, variables and methods as one indented tree ordered by line.
+fn format_module_outline(outline: &index::ModuleOutline) -> String {
+    // Depth of the innermost region containing `line`, plus one; 0 outside regions
+    let indent_at = |line: u32| {
+        outline
+            .regions
+            .iter()
+            .filter(|r| r.start_line <= line && line <= r.end_line)
+            .map(|r| r.depth as usize + 1)
+            .max()
+            .unwrap_or(0)
+    };
+
+    let mut entries: Vec<(u32, usize, String)> = Vec::new();
+    for r in &outline.regions {
+        entries.push((r.start_line, r.depth as usize, format!(
+            "#Область **{}** (строки {}-{})", r.name, r.start_line, r.end_line
+        )));
+    }
+    for v in &outline.variables {
+        let export = if v.is_export { " Экспорт" } else { "" };
+        entries.push((v.line, indent_at(v.line), format!(
+            "Перем {}{}{} (строка {})", v.name, export, directive_suffix(&v.directive), v.line
+        )));
+    }
+    for m in &outline.methods {
+        let kind = if m.kind == "function" { "Функция" } else { "Процедура" };
+        let export = if m.is_export { " Экспорт" } else { "" };
+        entries.push((m.start_line, indent_at(m.start_line), format!(
+            "{} {}{}{} (строка {})", kind, m.signature(), export, directive_suffix(&m.directive), m.start_line
+        )));
+    }
+    entries.sort_by_key(|(line, _, _)| *line);
+
+    let mut text = format!(
+        "## Структура модуля `{}`\n\nОбластей: {}, переменных: {}, методов: {}\n\n",
+        outline.file, outline.regions.len(), outline.variables.len(), outline.methods.len()
+    );
+    for (_, indent, line) in &entries {
+        text.push_str(&format!("{}- {}\n", "  ".repeat(*indent), line));
+    }
+    text
+}
+
+// ─── get_form_structure ──────────────────────────────────────────────────────
+
+/// Form items rendered beyond this are summarized (large forms have hundreds of decorations).
+const FORM_MAX_ELEMENTS: usize = 300;
+
+/// `OnChange` → `ПриИзменении (OnChange)`; unknown events are shown as stored.
+fn form_event_label(event: &str) -> String {
+    match form_xml::event_name_ru(event) {
+        Some(ru) => format!("{} ({})", ru, event),
+        None => event.to_string(),
+    }
+}
+
+async fn handle_get_form_structure(
+    args: &Value,
+    db_path: &Option<PathBuf>,
+) -> Result<Value, String> {
+    let object = args["object"].as_str().ok_or("Параметр 'object' обязателен")?;
+    if object.trim().is_empty() {
+        return Err("Параметр 'object' не может быть пустым".to_string());
+    }
+    let form = args["form"].as_str().filter(|f| !f.trim().is_empty());
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
+
+    let db_clone = db.clone();
+    let object_owned = object.to_string();
+    let form_owned = form.map(|f| f.to_string());
+    let (forms, structure) = tokio::task::spawn_blocking(move || {
+        let forms = index::find_forms(&db_clone, &object_owned, form_owned.as_deref());
+        let structure = match forms.as_slice() {
+            [single] => index::get_form_structure(&db_clone, single.id),
+            _ => None,
+        };
+        (forms, structure)
+    })
+    .await
+    .map_err(|e| format!("Ошибка выполнения: {}", e))?;
+
+    let text = match (forms.as_slice(), structure) {
+        ([], _) => match form {
+            Some(f) => format!("Форма «{}» объекта «{}» не найдена в индексе метаданных.", f, object),
+            None => format!(
+                "Управляемые формы объекта «{}» не найдены в индексе метаданных (Form.xml отсутствует в выгрузке).",
+                object
+            ),
+        },
+        ([info], Some(structure)) => format_form_structure(info, &structure),
+        _ => {
+            let mut text = format!("Найдено форм: {}. Укажите параметр `form`:\n\n", forms.len());
+            for f in &forms {
+                text.push_str(&format!("- {} — **{}**\n", f.owner, f.name));
+            }
+            text
+        }
+    };
+
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
+
+/// Render form events, attributes, commands and the item tree with data paths and handlers.
+fn format_form_structure(info: &index::FormInfo, form: &FormStructure) -> String {
+    let mut text = format!("## {} — форма {}\n\nМодуль: `{}`\n\n", info.owner, info.name, info.module_file);
+
+    let form_events: Vec<&form_xml::FormEvent> = form.events.iter().filter(|e| e.element.is_none()).collect();
+    if !form_events.is_empty() {
+        text.push_str(&format!("### События формы ({})\n", form_events.len()));
+        for e in &form_events {
+            text.push_str(&format!("- {} → `{}`\n", form_event_label(&e.event), e.handler));
+        }
+        text.push('\n');
+    }
+
+    if !form.attributes.is_empty() {
+        text.push_str(&format!("### Реквизиты формы ({})\n", form.attributes.len()));
+        for a in &form.attributes {
+            let main = if a.is_main { " (основной)" } else { "" };
+            if a.types.is_empty() {
+                text.push_str(&format!("- **{}**{}\n", a.name, main));
+            } else {
+                text.push_str(&format!("- **{}**{} — {}\n", a.name, main, a.types));
+            }
+        }
+        text.push('\n');
+    }
+
+    if !form.commands.is_empty() {
+        text.push_str(&format!("### Команды формы ({})\n", form.commands.len()));
+        for c in &form.commands {
+            match &c.action {
+                Some(action) => text.push_str(&format!("- {} → `{}`\n", c.name, action)),
+                None => text.push_str(&format!("- {}\n", c.name)),
+            }
+        }
+        text.push('\n');
+    }
+
+    if !form.elements.is_empty() {
+        text.push_str(&format!("### Элементы ({})\n", form.elements.len()));
+        let mut depth: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+        for el in form.elements.iter().take(FORM_MAX_ELEMENTS) {
+            let level = el.parent.as_deref().and_then(|p| depth.get(p)).map_or(0, |d| d + 1);
+            depth.insert(el.name.as_str(), level);
+
+            let mut line = format!("{}- {} ({})", "  ".repeat(level), el.name, el.kind);
+            if let Some(path) = &el.data_path {
+                line.push_str(&format!(" — `{}`", path));
+            }
+            let handlers: Vec<String> = form
+                .events
+                .iter()
+                .filter(|e| e.element.as_deref() == Some(el.name.as_str()))
+                .map(|e| format!("{} → `{}`", form_event_label(&e.event), e.handler))
+                .collect();
+            if !handlers.is_empty() {
+                line.push_str(&format!("; {}", handlers.join(", ")));
+            }
+            text.push_str(&line);
+            text.push('\n');
+        }
+        if form.elements.len() > FORM_MAX_ELEMENTS {
+            text.push_str(&format!("- *… ещё {} элементов*\n", form.elements.len() - FORM_MAX_ELEMENTS));
+        }
+    }
+    text
+}
+
+// ─── find_predefined ─────────────────────────────────────────────────────────
+
+/// Enum values / predefined items listed by `get_object_structure` and `find_predefined`.
+const OBJECT_MAX_PREDEFINED: usize = 300;
+
+/// Managers whose predefined values can be referenced from code, by Russian and English
+/// singular and plural names: `Перечисления.СтавкиНДС.НДС20`, `Справочник.Валюты.Рубль`.
+const PREDEFINED_OWNERS: &[(&[&str], &str)] = &[
+    (&["перечисление", "перечисления", "enum", "enums"], "Enum"),
+    (&["справочник", "справочники", "catalog", "catalogs"], "Catalog"),
+    (&["плансчетов", "планысчетов", "chartofaccounts", "chartsofaccounts"], "ChartOfAccounts"),
+    (
+        &["планвидовхарактеристик", "планывидовхарактеристик", "chartofcharacteristictypes", "chartsofcharacteristictypes"],
+        "ChartOfCharacteristicTypes",
+    ),
+    (
+        &["планвидоврасчета", "планывидоврасчета", "chartofcalculationtypes", "chartsofcalculationtypes"],
+        "ChartOfCalculationTypes",
+    ),
+];
+
+/// Reference to a predefined value split into (owner type, object name, value name).
+/// Strips `ПредопределенноеЗначение("…")`; parts that are absent are `None`.
+fn parse_predefined_reference(reference: &str) -> (Option<&'static str>, Option<String>, Option<String>) {
+    let mut text = reference.trim();
+    for wrapper in ["ПредопределенноеЗначение(", "PredefinedValue("] {
+        if let Some(rest) = text.strip_prefix(wrapper) {
+            text = rest.trim_end().trim_end_matches(')');
+        }
+    }
+    let text = text.trim().trim_matches(|c| c == '"' || c == '\'');
+    let parts: Vec<&str> = text.split('.').map(str::trim).filter(|p| !p.is_empty()).collect();
+    let owner = parts.first().and_then(|first| {
+        let lower = first.to_lowercase();
+        PREDEFINED_OWNERS
+            .iter()
+            .find(|(names, _)| names.contains(&lower.as_str()))
+            .map(|(_, obj_type)| *obj_type)
+    });
+    let rest = if owner.is_some() { &parts[1..] } else { &parts[..] };
+    match rest {
+        [] => (owner, None, None),
+        [value] if owner.is_none() => (None, None, Some(value.to_string())),
+        [object] => (owner, Some(object.to_string()), None),
+        [object, value, ..] => (owner, Some(object.to_string()), Some(value.to_string())),
+    }
+}
+
+/// `- Рубль (643) — «руб.»`, children indented under their predefined parent.
+fn format_predefined_values(values: &[index::PredefinedValue], limit: usize) -> String {
+    let mut depth: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    let mut text = String::new();
+    for v in values.iter().take(limit) {
+        let level = v.parent.as_deref().and_then(|p| depth.get(p)).map_or(0, |d| d + 1);
+        depth.insert(v.name.as_str(), level);
+        let mut line = format!("{}- {}", "  ".repeat(level), v.name);
+        if let Some(code) = &v.code {
+            line.push_str(&format!(" ({})", code));
+        }
+        if let Some(synonym) = v.synonym.as_deref().filter(|s| *s != v.name) {
+            line.push_str(&format!(" — «{}»", synonym));
+        }
+        if v.is_folder {
+            line.push_str(" [группа]");
+        }
+        text.push_str(&line);
+        text.push('\n');
+    }
+    if values.len() > limit {
+        text.push_str(&format!("- *...ещё {}*\n", values.len() - limit));
+    }
+    text
+}
+
+async fn handle_find_predefined(
+    args: &Value,
+    db_path: &Option<PathBuf>,
+) -> Result<Value, String> {
+    let reference = args["reference"].as_str().ok_or("Параметр 'reference' обязателен")?;
+    let (owner, object, value) = parse_predefined_reference(reference);
+    if object.is_none() && value.is_none() {
+        return Err(format!("Не удалось разобрать ссылку «{}». Пример: Перечисления.СтавкиНДС.НДС20", reference));
+    }
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
+
+    let db_clone = db.clone();
+    let (object_owned, value_owned) = (object.clone(), value.clone());
+    let (owned_values, by_name) = tokio::task::spawn_blocking(move || match (owner, object_owned) {
+        (Some(obj_type), Some(object)) => (Some(index::get_predefined_values(&db_clone, obj_type, &object)), Vec::new()),
+        // Bare value, or `Object.Value` without a manager prefix: narrow by object name
+        (_, object) => (
+            None,
+            value_owned
+                .map(|v| index::find_predefined_by_name(&db_clone, &v, object.as_deref()))
+                .unwrap_or_default(),
+        ),
+    })
+    .await
+    .map_err(|e| format!("Ошибка выполнения: {}", e))?;
+
+    let text = match owned_values {
+        Some(None) => format!(
+            "❌ Объект «{}.{}» не найден в конфигурации.",
+            owner.unwrap_or_default(),
+            object.unwrap_or_default()
+        ),
+        Some(Some((qualified, values))) => match value {
+            // Every reference type has an empty value that is not listed in the metadata
+            Some(v) if matches!(v.to_lowercase().as_str(), "пустаяссылка" | "emptyref") => {
+                format!("✅ **{}.{}** — пустая ссылка, допустима для любого объекта.", qualified, v)
+            }
+            Some(v) => match values.iter().find(|p| p.name.to_lowercase() == v.to_lowercase()) {
+                Some(found) => {
+                    let mut text = format!("✅ **{}.{}** существует", qualified, found.name);
+                    if let Some(synonym) = found.synonym.as_deref().filter(|s| *s != found.name) {
+                        text.push_str(&format!(" — «{}»", synonym));
+                    }
+                    if let Some(code) = &found.code {
+                        text.push_str(&format!(", код {}", code));
+                    }
+                    if let Some(parent) = &found.parent {
+                        text.push_str(&format!(", входит в {}", parent));
+                    }
+                    text.push('.');
+                    text
+                }
+                None if values.is_empty() => format!(
+                    "❌ У объекта **{}** нет значений и предопределённых элементов, «{}» не существует.",
+                    qualified, v
+                ),
+                None => format!(
+                    "❌ Значение «{}» не найдено в **{}**. Доступные значения ({}):\n\n{}",
+                    v,
+                    qualified,
+                    values.len(),
+                    format_predefined_values(&values, OBJECT_MAX_PREDEFINED)
+                ),
+            },
+            None if values.is_empty() => format!("У объекта **{}** нет значений и предопределённых элементов.", qualified),
+            None => format!(
+                "## {} — {} значений\n\n{}",
+                qualified,
+                values.len(),
+                format_predefined_values(&values, OBJECT_MAX_PREDEFINED)
+            ),
+        },
+        None => {
+            let name = value.unwrap_or_default();
+            if by_name.is_empty() {
+                format!("❌ Значение или предопределённый элемент «{}» не найден ни в одном объекте.", name)
+            } else {
+                let mut text = format!("Найдено «{}» в {} объектах:\n\n", name, by_name.len());
+                for p in &by_name {
+                    text.push_str(&format!("- **{}.{}**", p.object, p.name));
+                    if let Some(synonym) = p.synonym.as_deref().filter(|s| *s != p.name) {
+                        text.push_str(&format!(" — «{}»", synonym));
+                    }
+                    text.push('\n');
+                }
+                text
+            }
+        }
+    };
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
+
+// ─── get_metadata_dependencies ───────────────────────────────────────────────
+
+/// Referencing objects listed by `get_metadata_dependencies` before the rest is summarized.
+const METADATA_DEPS_MAX_OBJECTS: usize = 100;
+
+/// Where an edge starts inside its object: `Товары.Номенклатура (измерение)`, `движения`.
+fn metadata_reference_label(r: &index::MetadataReference) -> String {
+    let mut label = match (r.kind.as_str(), r.item.as_deref()) {
+        ("RegisterRecord", _) => "движения".to_string(),
+        ("Type", _) => "тип значения".to_string(),
+        (_, Some(item)) => item.to_string(),
+        (kind, None) => kind.to_string(),
+    };
+    match r.kind.as_str() {
+        "Dimension" => label.push_str(" (измерение)"),
+        "Resource" => label.push_str(" (ресурс)"),
+        _ => {}
+    }
+    if let Some(via) = &r.via {
+        label.push_str(&format!(" через {}", via));
+    }
+    label
+}
+
+/// Edges grouped by `key` (target or referencing object), labels deduplicated, in first-seen order.
+fn group_metadata_references<'a>(
+    refs: &'a [index::MetadataReference],
+    key: impl Fn(&'a index::MetadataReference) -> &'a str,
+) -> Vec<(&'a str, Vec<String>)> {
+    let mut groups: Vec<(&str, Vec<String>)> = Vec::new();
+    for r in refs {
+        let label = metadata_reference_label(r);
+        match groups.iter_mut().find(|(k, _)| *k == key(r)) {
+            Some((_, labels)) if !labels.contains(&label) => labels.push(label),
+            Some(_) => {}
+            None => groups.push((key(r), vec![label])),
+        }
+    }
+    groups
+}
+
+fn format_metadata_dependencies(deps: &index::MetadataDependencies, direction: &str) -> String {
+    let mut text = format!("## Зависимости метаданных: {}\n\n", deps.object);
+    if direction != "used_by" {
+        let uses = group_metadata_references(&deps.uses, |r| r.target.as_str());
+        text.push_str(&format!("### Использует ({})\n", uses.len()));
+        if uses.is_empty() {
+            text.push_str("- *ссылок на другие объекты в реквизитах нет*\n");
+        }
+        for (target, labels) in &uses {
+            text.push_str(&format!("- **{}** ← {}\n", target, labels.join(", ")));
+        }
+        text.push('\n');
+    }
+    if direction != "uses" {
+        let used_by = group_metadata_references(&deps.used_by, |r| r.object.as_str());
+        text.push_str(&format!("### Используется в ({})\n", used_by.len()));
+        if used_by.is_empty() {
+            text.push_str("- *ни один объект не ссылается на этот объект в метаданных*\n");
+        }
+        for (object, labels) in used_by.iter().take(METADATA_DEPS_MAX_OBJECTS) {
+            text.push_str(&format!("- **{}**: {}\n", object, labels.join(", ")));
+        }
+        if used_by.len() > METADATA_DEPS_MAX_OBJECTS {
+            text.push_str(&format!("- *...ещё {} объектов*\n", used_by.len() - METADATA_DEPS_MAX_OBJECTS));
+        }
+        text.push('\n');
+    }
+    text
 }
 
-async fn handle_sync_index(
-    config_path: &Option<PathBuf>,
+async fn handle_get_metadata_dependencies(
+    args: &Value,
     db_path: &Option<PathBuf>,
 ) -> Result<Value, String> {
-    let root = config_path
-        .as_ref()
-        .ok_or("Конфигурация не настроена. Укажите путь в настройках MCP сервера.")?;
-    let db = db_path
-        .as_ref()
-        .ok_or("Нет пути к базе данных индекса")?;
-
-    let root = root.clone();
-    let db = db.clone();
+    let object = args["object"].as_str().ok_or("Параметр 'object' обязателен")?;
+    if object.trim().is_empty() {
+        return Err("Параметр 'object' не может быть пустым".to_string());
+    }
+    let direction = args["direction"].as_str().unwrap_or("both");
+    if !matches!(direction, "uses" | "used_by" | "both") {
+        return Err("Параметр 'direction' должен быть uses, used_by или both".to_string());
+    }
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
 
-    let stats = tokio::task::spawn_blocking(move || index::sync_index(&root, &db))
+    let db_clone = db.clone();
+    let object_owned = object.to_string();
+    let deps = tokio::task::spawn_blocking(move || index::get_metadata_dependencies(&db_clone, &object_owned))
         .await
-        .map_err(|e| format!("Паника spawn_blocking: {}", e))?
-        .map_err(|e| format!("Ошибка синхронизации: {}", e))?;
-
-    let db_for_index = db_path.as_ref().unwrap();
-    let size = crate::db_size_mb(db_for_index);
-    // Use current time directly — avoids SQLite WAL caching issues when reading back built_at
-    let built_at = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-    eprintln!("SEARCH_STATUS:ready:{}:{:.2}:{}", stats.total_symbols, size, built_at);
+        .map_err(|e| format!("Ошибка выполнения: {}", e))?;
 
-    let text = if stats.added == 0 && stats.updated == 0 && stats.removed == 0 {
-        "✅ Индекс актуален. Изменённых BSL файлов не обнаружено.".to_string()
-    } else {
-        format!(
-            "✅ Синхронизация завершена:\n- Новых файлов: {}\n- Изменённых: {}\n- Удалённых: {}\n- Итого символов в индексе: {}",
-            stats.added, stats.updated, stats.removed, stats.total_symbols
-        )
+    let text = match deps {
+        Some(deps) => format_metadata_dependencies(&deps, direction),
+        None => format!("Объект «{}» не найден в индексе метаданных.", object),
     };
-
     Ok(json!({ "content": [{ "type": "text", "text": text }] }))
 }
 
-// ─── get_function_context ────────────────────────────────────────────────────
+// ─── find_queries_using ──────────────────────────────────────────────────────
+
+/// Query table reference split into (object, part): `РегистрНакопления.Товары.Остатки` →
+/// (`AccumulationRegister.Товары`, `Остатки`). A bare name is kept as is; `None` for an unknown type.
+fn parse_query_table(table: &str) -> Option<(String, Option<String>)> {
+    let parts: Vec<&str> = table.trim().split('.').map(str::trim).filter(|p| !p.is_empty()).collect();
+    match parts.as_slice() {
+        [name] => Some((name.to_string(), None)),
+        [obj_type, name, rest @ ..] => {
+            let obj_type = crate::parser::bsl_query::query_table_type(obj_type)?;
+            Some((format!("{}.{}", obj_type, name), rest.first().map(|p| p.to_string())))
+        }
+        [] => None,
+    }
+}
 
-async fn handle_get_function_context(
+fn format_query_usages(table: &str, usages: &[index::QueryUsage], total: usize) -> String {
+    let mut text = format!("## Запросы, читающие {} ({})\n", table, total);
+    let mut last_file = "";
+    for u in usages {
+        if u.file != last_file {
+            text.push_str(&format!("\n**{}**\n", u.file));
+            last_file = &u.file;
+        }
+        let lines = if u.end_line > u.start_line {
+            format!("строки {}–{}", u.start_line, u.end_line)
+        } else {
+            format!("строка {}", u.start_line)
+        };
+        let symbol = u.symbol.as_deref().map(|s| format!(", `{}`", s)).unwrap_or_default();
+        let fields = if u.fields.is_empty() { String::new() } else { format!(" — {}", u.fields.join(", ")) };
+        text.push_str(&format!("- {}{}: {}{}\n", lines, symbol, u.table, fields));
+    }
+    if total > usages.len() {
+        text.push_str(&format!("\n*...ещё {} запросов. Увеличьте `limit` или уточните `field`.*\n", total - usages.len()));
+    }
+    text
+}
+
+async fn handle_find_queries_using(
     args: &Value,
     db_path: &Option<PathBuf>,
 ) -> Result<Value, String> {
-    let function_name = args["function_name"].as_str().ok_or("Параметр 'function_name' обязателен")?;
+    let table = args["table"].as_str().ok_or("Параметр 'table' обязателен")?;
+    let (object, part) = parse_query_table(table).ok_or_else(|| {
+        format!("Не удалось разобрать таблицу «{}». Пример: РегистрНакопления.ТоварыНаСкладах", table)
+    })?;
+    let field = args["field"].as_str().map(str::trim).filter(|f| !f.is_empty()).map(str::to_string);
+    let limit = args["limit"].as_u64().unwrap_or(50).clamp(1, 500) as usize;
     let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
 
-    let ctx = index::get_function_context(db, function_name)
-        .ok_or_else(|| format!("Функция '{}' не найдена в индексе", function_name))?;
+    let db_clone = db.clone();
+    let object_owned = object.clone();
+    let (usages, total) = tokio::task::spawn_blocking(move || {
+        index::find_queries_using(&db_clone, &object_owned, part.as_deref(), field.as_deref(), limit)
+    })
+    .await
+    .map_err(|e| format!("Ошибка выполнения: {}", e))?;
 
-    let kind_label = if ctx.function.kind == "function" { "Функция" } else { "Процедура" };
-    let export_label = if ctx.function.is_export { " Экспорт" } else { "" };
+    let text = if usages.is_empty() {
+        format!("Запросы, читающие «{}», не найдены в индексе.", table.trim())
+    } else {
+        format_query_usages(table.trim(), &usages, total)
+    };
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
 
-    let mut text = format!(
-        "## {}{} ({}, {}:{})\n\n",
-        ctx.function.name, export_label, kind_label,
-        ctx.function.file, ctx.function.start_line
-    );
+// ─── Role rights ─────────────────────────────────────────────────────────────
+
+/// Right names used in `Rights.xml` and their names in the Russian configurator.
+const RIGHT_NAMES: &[(&str, &str)] = &[
+    ("Read", "Чтение"),
+    ("Insert", "Добавление"),
+    ("Update", "Изменение"),
+    ("Delete", "Удаление"),
+    ("View", "Просмотр"),
+    ("Edit", "Редактирование"),
+    ("InputByString", "ВводПоСтроке"),
+    ("Posting", "Проведение"),
+    ("UndoPosting", "ОтменаПроведения"),
+    ("InteractiveInsert", "ИнтерактивноеДобавление"),
+    ("InteractiveSetDeletionMark", "ИнтерактивнаяПометкаУдаления"),
+    ("InteractiveClearDeletionMark", "ИнтерактивноеСнятиеПометкиУдаления"),
+    ("InteractiveDelete", "ИнтерактивноеУдаление"),
+    ("InteractiveDeleteMarked", "ИнтерактивноеУдалениеПомеченных"),
+    ("InteractivePosting", "ИнтерактивноеПроведение"),
+    ("InteractivePostingRegular", "ИнтерактивноеПроведениеНеОперативное"),
+    ("InteractiveUndoPosting", "ИнтерактивнаяОтменаПроведения"),
+    ("InteractiveChangeOfPosted", "ИнтерактивноеИзменениеПроведенных"),
+    ("InteractiveDeletePredefinedData", "ИнтерактивноеУдалениеПредопределенныхДанных"),
+    ("InteractiveSetDeletionMarkPredefinedData", "ИнтерактивнаяПометкаУдаленияПредопределенныхДанных"),
+    ("InteractiveClearDeletionMarkPredefinedData", "ИнтерактивноеСнятиеПометкиУдаленияПредопределенныхДанных"),
+    ("InteractiveDeleteMarkedPredefinedData", "ИнтерактивноеУдалениеПомеченныхПредопределенныхДанных"),
+    ("TotalsControl", "УправлениеИтогами"),
+    ("Use", "Использование"),
+    ("Get", "Получение"),
+    ("Set", "Установка"),
+    ("Start", "Старт"),
+    ("InteractiveStart", "ИнтерактивныйСтарт"),
+    ("InteractiveActivate", "ИнтерактивнаяАктивация"),
+    ("Execute", "Выполнение"),
+    ("Administration", "Администрирование"),
+    ("DataAdministration", "АдминистрированиеДанных"),
+    ("ExclusiveMode", "МонопольныйРежим"),
+    ("ThinClient", "ТонкийКлиент"),
+    ("WebClient", "ВебКлиент"),
+    ("ThickClient", "ТолстыйКлиент"),
+    ("Output", "Вывод"),
+    ("SaveUserData", "СохранениеДанныхПользователя"),
+];
+
+/// Objects listed by `get_role_rights` before the rest is summarized.
+const ROLE_RIGHTS_MAX_OBJECTS: usize = 300;
+
+fn right_label(right: &str) -> &str {
+    RIGHT_NAMES.iter().find(|(en, _)| *en == right).map_or(right, |(_, ru)| ru)
+}
 
-    if ctx.calls.is_empty() {
-        text.push_str("**Вызывает:** *(нет вызовов в индексе)*\n\n");
-    } else {
-        text.push_str(&format!("**Вызывает ({}):**\n", ctx.calls.len()));
-        for callee in &ctx.calls {
-            text.push_str(&format!("- {}\n", callee));
+/// `Rights.xml` name of a right given in English or Russian, case-insensitive.
+fn right_from_arg(arg: &str) -> Option<&'static str> {
+    let lower = arg.trim().to_lowercase();
+    RIGHT_NAMES
+        .iter()
+        .find(|(en, ru)| en.to_lowercase() == lower || ru.to_lowercase() == lower)
+        .map(|(en, _)| *en)
+}
+
+/// Consecutive rights of the same role on the same object or field.
+fn group_role_rights<'a>(
+    rights: &'a [&'a index::RoleRight],
+) -> Vec<(&'a str, &'a str, Option<&'a str>, Vec<&'a index::RoleRight>)> {
+    let mut groups: Vec<(&str, &str, Option<&str>, Vec<&index::RoleRight>)> = Vec::new();
+    for &r in rights {
+        match groups.last_mut() {
+            Some((role, object, item, group))
+                if *role == r.role && *object == r.object && *item == r.item.as_deref() =>
+            {
+                group.push(r)
+            }
+            _ => groups.push((&r.role, &r.object, r.item.as_deref(), vec![r])),
         }
-        text.push('\n');
     }
+    groups
+}
 
-    if ctx.called_by.is_empty() {
-        text.push_str("**Вызывается из:** *(нет вызывающих в индексе)*\n");
-    } else {
-        text.push_str(&format!("**Вызывается из ({}):**\n", ctx.called_by.len()));
-        for caller in &ctx.called_by {
-            if caller.start_line > 0 {
-                text.push_str(&format!("- {} ({}:{})\n", caller.name, caller.file, caller.start_line));
-            } else {
-                text.push_str(&format!("- {} ({})\n", caller.name, caller.file));
+/// `**Роль**: Чтение, Проведение` with one nested line per distinct RLS condition.
+/// Empty when nothing in `rights` is granted.
+fn format_granted_rights(title: &str, rights: &[&index::RoleRight]) -> String {
+    let granted: Vec<&index::RoleRight> = rights.iter().copied().filter(|r| r.granted).collect();
+    if granted.is_empty() {
+        return String::new();
+    }
+    let names: Vec<&str> = granted
+        .iter()
+        .map(|r| if r.inherited { "все права (унаследованы: права для новых объектов)" } else { right_label(&r.right) })
+        .collect();
+    let mut text = format!("- **{}**: {}\n", title, names.join(", "));
+    let mut conditions: Vec<(&str, Vec<&str>)> = Vec::new();
+    for r in &granted {
+        let Some(condition) = r.condition.as_deref() else { continue };
+        match conditions.iter_mut().find(|(c, _)| *c == condition) {
+            Some((_, labels)) => labels.push(right_label(&r.right)),
+            None => conditions.push((condition, vec![right_label(&r.right)])),
+        }
+    }
+    for (condition, labels) in conditions {
+        let condition = condition.split_whitespace().collect::<Vec<_>>().join(" ");
+        text.push_str(&format!("  - RLS ({}): `{}`\n", labels.join(", "), condition));
+    }
+    text
+}
+
+/// `  - поле Attribute.Сумма: разрешено Просмотр; запрещено Редактирование`.
+fn format_field_rights(item: &str, rights: &[&index::RoleRight]) -> String {
+    let list = |granted: bool| {
+        rights.iter().filter(|r| r.granted == granted).map(|r| right_label(&r.right)).collect::<Vec<_>>().join(", ")
+    };
+    let parts: Vec<String> = [("разрешено", list(true)), ("запрещено", list(false))]
+        .into_iter()
+        .filter(|(_, names)| !names.is_empty())
+        .map(|(label, names)| format!("{} {}", label, names))
+        .collect();
+    format!("  - поле {}: {}\n", item, parts.join("; "))
+}
+
+fn format_object_rights(object: &str, rights: &[index::RoleRight], right: Option<&str>) -> String {
+    let rights: Vec<&index::RoleRight> =
+        rights.iter().filter(|r| r.inherited || right.is_none_or(|name| r.right == name)).collect();
+    let mut text = match right {
+        Some(name) => format!("## Право «{}» на {}\n\n", right_label(name), object),
+        None => format!("## Права на {}\n\n", object),
+    };
+
+    let groups = group_role_rights(&rights);
+    let roles: Vec<String> = groups
+        .iter()
+        .filter(|(_, _, item, _)| item.is_none())
+        .map(|(role, _, _, group)| format_granted_rights(role, group))
+        .filter(|line| !line.is_empty())
+        .collect();
+    text.push_str(&format!("### Роли ({})\n", roles.len()));
+    if roles.is_empty() {
+        text.push_str("- *ни одна роль не даёт таких прав на объект*\n");
+    }
+    roles.iter().for_each(|line| text.push_str(line));
+
+    let fields: Vec<_> = groups.iter().filter(|(_, _, item, _)| item.is_some()).collect();
+    if !fields.is_empty() {
+        text.push_str("\n### Права на поля\n");
+        let mut last_role = "";
+        for (role, _, item, group) in fields {
+            if *role != last_role {
+                text.push_str(&format!("- **{}**\n", role));
+                last_role = role;
             }
+            text.push_str(&format_field_rights(item.unwrap_or_default(), group));
         }
     }
+    text
+}
 
-    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+fn format_role_rights(details: &index::RoleRightsDetails) -> String {
+    let mut text = format!("## Роль {}\n\n", details.role);
+    text.push_str(&format!(
+        "**Права для новых объектов**: {}\n\n",
+        if details.set_for_new_objects { "да" } else { "нет" }
+    ));
+
+    let rights: Vec<&index::RoleRight> = details.rights.iter().collect();
+    // One entry per object: granted rights, then field rights nested under it
+    let mut objects: Vec<(&str, String)> = Vec::new();
+    for (_, object, item, group) in group_role_rights(&rights) {
+        let line = match item {
+            None => format_granted_rights(object, &group),
+            Some(item) => format_field_rights(item, &group),
+        };
+        match objects.last_mut() {
+            Some((last, text)) if *last == object => text.push_str(&line),
+            _ if item.is_none() && line.is_empty() => {}
+            _ if item.is_none() => objects.push((object, line)),
+            _ => objects.push((object, format!("- **{}**\n{}", object, line))),
+        }
+    }
+    text.push_str(&format!("### Объекты ({})\n", objects.len()));
+    if objects.is_empty() {
+        text.push_str("- *роль не даёт прав на объекты*\n");
+    }
+    objects.iter().take(ROLE_RIGHTS_MAX_OBJECTS).for_each(|(_, lines)| text.push_str(lines));
+    if objects.len() > ROLE_RIGHTS_MAX_OBJECTS {
+        text.push_str(&format!("- *...ещё {} объектов (уточните object_type)*\n", objects.len() - ROLE_RIGHTS_MAX_OBJECTS));
+    }
+
+    if !details.templates.is_empty() {
+        text.push_str(&format!("\n### Шаблоны ограничений ({})\n", details.templates.len()));
+        for (name, condition) in &details.templates {
+            text.push_str(&format!("#### {}\n```\n{}\n```\n", name, condition.trim_end()));
+        }
+    }
+    text
 }
 
-// ─── get_module_functions ────────────────────────────────────────────────────
+async fn handle_get_object_rights(
+    args: &Value,
+    db_path: &Option<PathBuf>,
+) -> Result<Value, String> {
+    let object = args["object"].as_str().ok_or("Параметр 'object' обязателен")?;
+    if object.trim().is_empty() {
+        return Err("Параметр 'object' не может быть пустым".to_string());
+    }
+    let right = match args["right"].as_str().filter(|r| !r.trim().is_empty()) {
+        Some(arg) => Some(right_from_arg(arg).ok_or_else(|| {
+            format!("Неизвестное право «{}». Примеры: Read (Чтение), Posting (Проведение), InteractiveDelete", arg)
+        })?),
+        None => None,
+    };
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
 
-async fn handle_get_module_functions(
+    let db_clone = db.clone();
+    let object_owned = object.to_string();
+    let rights = tokio::task::spawn_blocking(move || index::get_object_rights(&db_clone, &object_owned))
+        .await
+        .map_err(|e| format!("Ошибка выполнения: {}", e))?;
+
+    let text = match rights {
+        Some((object, rights)) => format_object_rights(&object, &rights, right),
+        None => format!(
+            "Объект «{}» не найден в индексе метаданных. Укажите точное имя, например Document.РеализацияТоваров.",
+            object
+        ),
+    };
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
+
+async fn handle_get_role_rights(
     args: &Value,
     db_path: &Option<PathBuf>,
 ) -> Result<Value, String> {
-    let module_path = args["module_path"].as_str().ok_or("Параметр 'module_path' обязателен")?;
-    let limit = args["limit"].as_u64().unwrap_or(200).clamp(1, 500) as usize;
+    let role = args["role"].as_str().ok_or("Параметр 'role' обязателен")?;
+    let role = role.trim();
+    let role = role.strip_prefix("Role.").or_else(|| role.strip_prefix("Роль.")).unwrap_or(role);
+    if role.is_empty() {
+        return Err("Параметр 'role' не может быть пустым".to_string());
+    }
+    let object_type = args["object_type"].as_str().filter(|t| !t.trim().is_empty()).map(|t| t.trim().to_string());
     let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
 
-    // Resolve "CommonModule.МодульИмя" → "CommonModules/МодульИмя"
-    let resolved = if let Some(dot) = module_path.find('.') {
-        let type_part = &module_path[..dot];
-        let name_part = &module_path[dot + 1..];
-        if let Some(folder) = object_type_to_folder(type_part) {
-            format!("{}/{}", folder, name_part)
-        } else {
-            module_path.to_string()
-        }
-    } else {
-        module_path.to_string()
+    let db_clone = db.clone();
+    let role_owned = role.to_string();
+    let details = tokio::task::spawn_blocking(move || {
+        index::get_role_rights(&db_clone, &role_owned, object_type.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Ошибка выполнения: {}", e))?;
+
+    let text = match details {
+        Some(details) => format_role_rights(&details),
+        None => format!("Роль «{}» не найдена в индексе метаданных.", role),
     };
+    Ok(json!({ "content": [{ "type": "text", "text": text }] }))
+}
 
-    let symbols = index::get_module_functions(db, &resolved, limit);
+// ─── check_call_contexts ─────────────────────────────────────────────────────
 
-    if symbols.is_empty() {
+async fn handle_check_call_contexts(
+    args: &Value,
+    db_path: &Option<PathBuf>,
+) -> Result<Value, String> {
+    let db = db_path.as_ref().ok_or("Индекс символов не настроен")?;
+    let limit = args["limit"].as_u64().unwrap_or(50).clamp(1, 500) as usize;
+    let scope = args["scope"]
+        .as_str()
+        .and_then(resolve_scope)
+        .map(|p| p.to_string_lossy().replace('\\', "/"));
+
+    let db_clone = db.clone();
+    let scope_clone = scope.clone();
+    let violations = tokio::task::spawn_blocking(move || {
+        index::find_context_violations(&db_clone, scope_clone.as_deref(), limit)
+    })
+    .await
+    .map_err(|e| format!("Ошибка выполнения: {}", e))??;
+
+    let scope_label = scope.as_deref().unwrap_or("вся конфигурация");
+    if violations.is_empty() {
         return Ok(json!({ "content": [{ "type": "text", "text": format!(
-            "Модуль «{}» не найден в индексе или не содержит функций.", module_path
+            "✅ Нарушений контекста вызовов не найдено ({}).\n\n*Проверяются только вызовы, разрешённые до конкретного модуля.*",
+            scope_label
         )}] }));
     }
 
-    let first_file = &symbols[0].file;
-    let mut text = format!("## Функции модуля `{}`\n\n", first_file);
-
-    let total = symbols.len();
-    for sym in &symbols {
-        let kind = if sym.kind == "function" { "Функция" } else { "Процедура" };
-        let export = if sym.is_export { " Экспорт" } else { "" };
+    let mut text = format!(
+        "## Нарушения контекста вызовов ({}): {}\n\n",
+        scope_label, violations.len()
+    );
+    let mut current_file: Option<&str> = None;
+    for v in &violations {
+        if current_file != Some(v.caller_file.as_str()) {
+            text.push_str(&format!("### `{}`\n", index::module_label(&v.caller_file)));
+            current_file = Some(v.caller_file.as_str());
+        }
         text.push_str(&format!(
-            "- **{}**{} — {} (строка {})\n",
-            sym.name, export, kind, sym.start_line
+            "- **{}** ({}, строка {}) → **{}** ({}, `{}`)\n  {}\n",
+            v.caller, v.caller_context, v.caller_line,
+            v.callee, v.callee_context, index::module_label(&v.callee_file),
+            v.reason
         ));
     }
-
-    if total == limit {
-        text.push_str(&format!("\n*Показано первых {} — уточните путь для фильтрации.*", limit));
+    if violations.len() == limit {
+        text.push_str(&format!("\n*Показано первых {} — сузьте scope.*", limit));
     }
 
     Ok(json!({ "content": [{ "type": "text", "text": text }] }))
@@ -1876,11 +3742,11 @@ async fn handle_smart_find(
 
     // Step 1: find by exact name, fallback to substring
     let results = tokio::task::spawn_blocking(move || {
-        let exact = index::find_symbols(&db_clone, &query_owned, true, 5)?;
+        let exact = index::find_symbols(&db_clone, &query_owned, true, 5, &index::SymbolFilter::default())?;
         if !exact.is_empty() {
             return Ok(exact);
         }
-        index::find_symbols(&db_clone, &query_owned, false, 10)
+        index::find_symbols(&db_clone, &query_owned, false, 10, &index::SymbolFilter::default())
     })
     .await
     .map_err(|e| format!("Ошибка поиска: {}", e))??;
@@ -1922,7 +3788,7 @@ async fn handle_smart_find(
     if with_code {
         if let Some(root) = config_path {
             let best = results.iter().find(|r| r.is_export).unwrap_or(&results[0]);
-            let file_path = root.join(best.file.replace('/', std::path::MAIN_SEPARATOR_STR));
+            let file_path = index::source_path(&root, &best.file);
             if let Ok(content) = std::fs::read_to_string(&file_path) {
                 let lines: Vec<&str> = content.lines().collect();
                 let start = (best.start_line as usize).saturating_sub(1);
@@ -1966,6 +3832,9 @@ async fn handle_semantic_find(
         .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
         .unwrap_or_default();
 
+    let subsystem = subsystem_from_args(args, db_path)?;
+    let module_flag = module_flag_from_args(args)?;
+
     let db_clone = db.clone();
     let query_owned = query.to_string();
     let ctx_clone = context_objects.clone();
@@ -1973,7 +3842,9 @@ async fn handle_semantic_find(
     let results = tokio::task::spawn_blocking(move || {
         let conn = rusqlite::Connection::open(&db_clone)
             .map_err(|e| format!("Ошибка БД: {}", e))?;
-        Ok::<_, String>(crate::semantic::semantic_search(&conn, &query_owned, &ctx_clone, limit))
+        Ok::<_, String>(crate::semantic::semantic_search(
+            &conn, &query_owned, &ctx_clone, subsystem.as_deref(), module_flag.as_deref(), limit,
+        ))
     })
     .await
     .map_err(|e| format!("Task error: {}", e))??;
@@ -2010,17 +3881,23 @@ async fn handle_semantic_find(
     for (i, r) in results.iter().enumerate() {
         let export_mark = if r.is_export { " Экспорт" } else { "" };
         text.push_str(&format!(
-            "{}. **{}** ({}{}) — `{}`  строки {}-{}\n   score={:.3}  calls={}\n",
-            i + 1, r.name, r.kind, export_mark,
+            "{}. **{}({})** ({}{}) — `{}`  строки {}-{}\n   score={:.3}  calls={}\n",
+            i + 1, r.name, r.params, r.kind, export_mark,
             r.file, r.start_line, r.end_line,
             r.final_score, r.call_count
         ));
+        if let Some(first) = r.summary.lines().next() {
+            text.push_str(&format!("   {}\n", first));
+        }
+        if !r.matched_in.is_empty() {
+            text.push_str(&format!("   совпадения: {}\n", r.matched_in.join(", ")));
+        }
     }
 
     // Include code for top result
     if include_code {
         if let (Some(root), Some(best)) = (config_path, results.first()) {
-            let file_path = root.join(best.file.replace('/', std::path::MAIN_SEPARATOR_STR));
+            let file_path = index::source_path(&root, &best.file);
             if let Ok(content) = std::fs::read_to_string(&file_path) {
                 let lines: Vec<&str> = content.lines().collect();
                 let start = (best.start_line as usize).saturating_sub(1);
@@ -2042,6 +3919,9 @@ async fn handle_semantic_find(
     let items: Vec<Value> = results.iter().enumerate().map(|(i, r)| json!({
         "rank": i + 1,
         "name": r.name,
+        "params": r.params,
+        "summary": r.summary,
+        "matched_in": r.matched_in,
         "kind": r.kind,
         "file": r.file,
         "start_line": r.start_line,
@@ -2087,7 +3967,7 @@ async fn handle_find_function_in_object(
         object.to_string()
     };
 
-    let symbols = index::get_module_functions(db, &path_prefix, 500);
+    let symbols = index::get_module_functions(db, &path_prefix, 500, &index::SymbolFilter::default());
 
     if symbols.is_empty() {
         return Ok(json!({ "content": [{ "type": "text", "text": format!(
@@ -2125,7 +4005,7 @@ async fn handle_find_function_in_object(
         // Code of best match (prefer export)
         let best = matched.iter().find(|s| s.is_export).copied().unwrap_or(matched[0]);
         if let Some(root) = config_path {
-            let file_path = root.join(best.file.replace('/', std::path::MAIN_SEPARATOR_STR));
+            let file_path = index::source_path(&root, &best.file);
             if let Ok(content) = std::fs::read_to_string(&file_path) {
                 let lines: Vec<&str> = content.lines().collect();
                 let start = (best.start_line as usize).saturating_sub(1);
@@ -2326,6 +4206,7 @@ async fn handle_search_files(
     let glob_pattern = args["glob"].as_str().unwrap_or("").trim().to_string();
     let extension_filter = args["extension"].as_str().map(|s| s.trim().to_lowercase());
     let object_type_filter = args["object_type"].as_str().map(|s| s.trim().to_string());
+    let subsystem = subsystem_from_args(args, db_path)?;
     let head_limit = args["head_limit"].as_u64().unwrap_or(50).clamp(1, 500) as usize;
     let offset = args["offset"].as_u64().unwrap_or(0) as usize;
 
@@ -2338,11 +4219,13 @@ async fn handle_search_files(
 
     // Try DB-backed search first (fast path)
     if let Some(db) = db_path.as_deref() {
-        if let Ok(items) = index::search_files_in_catalog(
-            db, &query, scope_prefix.as_deref(),
-            object_type_filter.as_deref(), extension_filter.as_deref(),
-            &glob_pattern, offset + head_limit,
-        ) {
+        let filter = index::FileCatalogFilter {
+            scope_prefix: scope_prefix.as_deref(),
+            object_type: object_type_filter.as_deref(),
+            extension: extension_filter.as_deref(),
+            subsystem: subsystem.as_deref(),
+        };
+        if let Ok(items) = index::search_files_in_catalog(db, &query, &filter, &glob_pattern, offset + head_limit) {
             let total = items.len();
             let page: Vec<_> = items.into_iter().skip(offset).take(head_limit).collect();
             let returned = page.len();
@@ -2359,12 +4242,13 @@ async fn handle_search_files(
             })).collect();
 
             let mut summary = format!(
-                "Найдено {} файлов{}{}{}{}{}\n\n",
+                "Найдено {} файлов{}{}{}{}{}{}\n\n",
                 total,
                 if !query.is_empty() { format!(" по «{}»", query) } else { String::new() },
                 object_type_filter.as_deref().map(|t| format!(", тип: {}", t)).unwrap_or_default(),
                 extension_filter.as_deref().map(|e| format!(", расширение: .{}", e)).unwrap_or_default(),
                 scope_prefix.as_deref().map(|s| format!(", в: {}", s)).unwrap_or_default(),
+                args["subsystem"].as_str().filter(|_| subsystem.is_some()).map(|s| format!(", подсистема: {}", s)).unwrap_or_default(),
                 if truncated { format!(" (показаны {}-{})", offset + 1, offset + returned) } else { String::new() }
             );
             for fi in &page {
@@ -2393,6 +4277,10 @@ async fn handle_search_files(
         }
     }
 
+    if subsystem.is_some() {
+        return Err("Каталог файлов ещё не построен — фильтр по подсистеме доступен после индексации.".to_string());
+    }
+
     // Fallback: filesystem walk (no file catalog yet)
     let root_clone = root.clone();
     let query_clone = query.clone();
@@ -2452,7 +4340,7 @@ async fn handle_search_files(
             if !query_clone.is_empty() && !rel.to_lowercase().contains(&query_clone) { continue; }
 
             // Infer object type / name from path
-            let (obj_type, obj_name, module_kind) = infer_object_from_path(&rel);
+            let (obj_type, obj_name, module_kind) = index::infer_object_from_path(&rel);
 
             // Object type filter
             if let Some(ref ot) = obj_type_clone {
@@ -2512,72 +4400,20 @@ async fn handle_search_files(
     }))
 }
 
-/// Infer (object_type, object_name, module_kind) from a 1С config dump relative path.
-/// E.g. "CommonModules/УчетНДС/Module.bsl" → (Some("CommonModule"), Some("УчетНДС"), Some("Module"))
-fn infer_object_from_path(rel: &str) -> (Option<String>, Option<String>, Option<String>) {
-    let parts: Vec<&str> = rel.splitn(3, '/').collect();
-    if parts.len() < 2 { return (None, None, None); }
-
-    let folder = parts[0];
-    let obj_name = parts[1];
-    let file_part = parts.get(2).copied().unwrap_or("");
-
-    let obj_type = match folder {
-        "CommonModules"              => Some("CommonModule"),
-        "Catalogs"                   => Some("Catalog"),
-        "Documents"                  => Some("Document"),
-        "InformationRegisters"       => Some("InformationRegister"),
-        "AccumulationRegisters"      => Some("AccumulationRegister"),
-        "AccountingRegisters"        => Some("AccountingRegister"),
-        "CalculationRegisters"       => Some("CalculationRegister"),
-        "ExchangePlans"              => Some("ExchangePlan"),
-        "BusinessProcesses"          => Some("BusinessProcess"),
-        "Tasks"                      => Some("Task"),
-        "ChartsOfCharacteristicTypes"=> Some("ChartOfCharacteristicTypes"),
-        "ChartsOfAccounts"           => Some("ChartOfAccounts"),
-        "ChartsOfCalculationTypes"   => Some("ChartOfCalculationTypes"),
-        "DataProcessors"             => Some("DataProcessor"),
-        "Reports"                    => Some("Report"),
-        "Enums"                      => Some("Enum"),
-        "Constants"                  => Some("Constant"),
-        "DocumentJournals"           => Some("DocumentJournal"),
-        "FilterCriteria"             => Some("FilterCriterion"),
-        "ScheduledJobs"              => Some("ScheduledJob"),
-        "WebServices"                => Some("WebService"),
-        "HTTPServices"               => Some("HTTPService"),
-        "CommonForms"                => Some("CommonForm"),
-        "CommonTemplates"            => Some("CommonTemplate"),
-        "CommonAttributes"           => Some("CommonAttribute"),
-        "CommonCommands"             => Some("CommonCommand"),
-        "Roles"                      => Some("Role"),
-        "Subsystems"                 => Some("Subsystem"),
-        _ => None,
-    };
-
-    let module_kind = if file_part.eq_ignore_ascii_case("module.bsl") {
-        Some("Module")
-    } else if file_part.eq_ignore_ascii_case("managermodule.bsl") {
-        Some("ManagerModule")
-    } else if file_part.eq_ignore_ascii_case("objectmodule.bsl") {
-        Some("ObjectModule")
-    } else if file_part.to_lowercase().starts_with("forms/") {
-        Some("FormModule")
-    } else if file_part.to_lowercase().ends_with(".xml") {
-        Some("XML")
-    } else {
-        None
-    };
-
-    (
-        obj_type.map(|s| s.to_string()),
-        if obj_name.is_empty() { None } else { Some(obj_name.to_string()) },
-        module_kind.map(|s| s.to_string()),
-    )
-}
-
 #[cfg(test)]
 mod tests {
-    use super::build_files_with_matches_summary;
+    use super::{
+        build_files_with_matches_summary, format_call_paths, format_call_tree, format_form_structure,
+        format_metadata_dependencies, format_unused_symbols,
+        format_module_outline, format_object_rights, format_predefined_values, module_flag_labels,
+        parse_predefined_reference, parse_query_table, register_virtual_tables, resolve_module_path, right_from_arg,
+        symbol_filter_from_args,
+    };
+    use crate::index;
+    use crate::index::tests::{
+        chart_of_accounts, counterparty_references, order_rights, TestIndex, ORDER_FORM_MODULE, ORDER_FORM_XML,
+    };
+    use serde_json::json;
 
     #[test]
     fn files_with_matches_summary_lists_files_and_examples() {
@@ -2625,4 +4461,280 @@ mod tests {
         assert!(summary.contains("ничего не найдено"));
         assert!(summary.contains("таймауту"));
     }
+
+    #[test]
+    fn symbol_filter_normalizes_directive() {
+        let filter = symbol_filter_from_args(&json!({"directive": "&AtServerNoContext"})).unwrap();
+        assert_eq!(filter.directive.as_deref(), Some("НаСервереБезКонтекста"));
+
+        assert!(symbol_filter_from_args(&json!({})).unwrap().directive.is_none());
+        assert!(symbol_filter_from_args(&json!({"directive": "НаЛуне"})).is_err());
+    }
+
+    #[test]
+    fn module_flag_accepts_english_and_russian_names() {
+        let flag = |raw: &str| symbol_filter_from_args(&json!({"module_flag": raw})).unwrap().module_flag;
+        assert_eq!(flag("server_call").as_deref(), Some("server_call"));
+        assert_eq!(flag("ServerCall").as_deref(), Some("server_call"));
+        assert_eq!(flag("Вызов сервера").as_deref(), Some("server_call"));
+        assert_eq!(flag("ReturnValuesReuse").as_deref(), Some("cached"));
+        assert!(flag("").is_none());
+        assert!(symbol_filter_from_args(&json!({"module_flag": "НаЛуне"})).is_err());
+
+        let flags = index::CommonModuleFlags {
+            server: true,
+            server_call: true,
+            return_values_reuse: Some("DuringSession".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            module_flag_labels(&flags),
+            vec!["Сервер", "Вызов сервера", "Повторное использование: на время сеанса"]
+        );
+    }
+
+    #[test]
+    fn form_structure_lists_items_with_their_handlers() {
+        let index = TestIndex::new();
+        let id = index.form("Document.Заказ", "ФормаДокумента", ORDER_FORM_MODULE, ORDER_FORM_XML);
+        let info = &index::find_forms(&index.path, "Document.Заказ", None)[0];
+        let form = index::get_form_structure(&index.path, id).unwrap();
+
+        let text = format_form_structure(info, &form);
+        assert!(text.contains("### События формы (1)\n- ПриОткрытии (OnOpen) → `ПриОткрытии`"));
+        assert!(text.contains(
+            "- Шапка (UsualGroup)\n  - Контрагент (InputField) — `Объект.Контрагент`; \
+             ПриИзменении (OnChange) → `КонтрагентПриИзменении`\n"
+        ));
+    }
+
+    #[test]
+    fn module_outline_nests_methods_under_innermost_region() {
+        let index = TestIndex::new();
+        let file = "CommonModules/М/Ext/Module.bsl";
+        index.method(file, "Верхний", 2);
+        index.method(file, "Вложенный", 6);
+        index.method(file, "ВнеОбластей", 12);
+        index.exec(
+            "INSERT INTO module_regions (file, name, name_lower, start_line, end_line, depth) VALUES
+                 ('CommonModules/М/Ext/Module.bsl', 'ПрограммныйИнтерфейс', 'программныйинтерфейс', 1, 10, 0),
+                 ('CommonModules/М/Ext/Module.bsl', 'Подраздел', 'подраздел', 5, 9, 1);",
+        );
+
+        let (outlines, _) = index::get_module_outline(&index.path, file, 1);
+        let text = format_module_outline(&outlines[0]);
+        assert!(text.contains("\n- #Область **ПрограммныйИнтерфейс**"));
+        assert!(text.contains("\n  - Процедура Верхний()"));
+        assert!(text.contains("\n  - #Область **Подраздел**"));
+        assert!(text.contains("\n    - Процедура Вложенный()"));
+        assert!(text.contains("\n- Процедура ВнеОбластей()"));
+    }
+
+    #[test]
+    fn metadata_dependencies_group_edges_by_object() {
+        let index = TestIndex::new();
+        counterparty_references(&index);
+        let deps = index::get_metadata_dependencies(&index.path, "Catalog.Контрагенты").unwrap();
+
+        let text = format_metadata_dependencies(&deps, "both");
+        assert!(text.contains("### Использует (1)\n- **Catalog.Договоры** ← ОсновнойДоговор\n"));
+        assert!(text.contains("### Используется в (4)\n"));
+        assert!(text.contains("- **Document.Реализация**: Контрагент, Товары.Получатель\n"));
+        assert!(text.contains("- **AccumulationRegister.Взаиморасчеты**: Контрагент (измерение)\n"));
+        assert!(text.contains("- **Document.Оплата**: Плательщик через DefinedType.Плательщик\n"));
+
+        let text = format_metadata_dependencies(&deps, "uses");
+        assert!(!text.contains("Используется в"));
+    }
+
+    #[test]
+    fn predefined_reference_accepts_code_forms() {
+        assert_eq!(
+            parse_predefined_reference("Перечисления.СтавкиНДС.НДС20"),
+            (Some("Enum"), Some("СтавкиНДС".to_string()), Some("НДС20".to_string()))
+        );
+        assert_eq!(
+            parse_predefined_reference("ПредопределенноеЗначение(\"Справочник.Валюты.Рубль\")"),
+            (Some("Catalog"), Some("Валюты".to_string()), Some("Рубль".to_string()))
+        );
+        assert_eq!(
+            parse_predefined_reference("ПланыСчетов.Хозрасчетный"),
+            (Some("ChartOfAccounts"), Some("Хозрасчетный".to_string()), None)
+        );
+        assert_eq!(parse_predefined_reference("НДС20"), (None, None, Some("НДС20".to_string())));
+        assert_eq!(
+            parse_predefined_reference("СтавкиНДС.НДС20"),
+            (None, Some("СтавкиНДС".to_string()), Some("НДС20".to_string()))
+        );
+    }
+
+    #[test]
+    fn predefined_values_nest_under_parents() {
+        let index = TestIndex::new();
+        chart_of_accounts(&index);
+        let (_, values) = index::get_predefined_values(&index.path, "ChartOfAccounts", "Хозрасчетный").unwrap();
+
+        assert_eq!(
+            format_predefined_values(&values, 10),
+            "- Касса (50)\n  - КассаОрганизации (50.01)\n- Товары (41)\n"
+        );
+        assert!(format_predefined_values(&values, 1).ends_with("- *...ещё 2*\n"));
+    }
+
+    #[test]
+    fn register_virtual_tables_follow_register_settings() {
+        let balance = vec![("RegisterType".to_string(), "Balance".to_string())];
+        let tables = register_virtual_tables("AccumulationRegister", "ТоварыНаСкладах", &balance);
+        assert_eq!(tables.len(), 3);
+        assert_eq!(tables[0], "РегистрНакопления.ТоварыНаСкладах.Остатки(&Период, Условие)");
+
+        let turnovers = vec![("RegisterType".to_string(), "Turnovers".to_string())];
+        let tables = register_virtual_tables("AccumulationRegister", "Продажи", &turnovers);
+        assert_eq!(tables.len(), 1);
+        assert!(tables[0].starts_with("РегистрНакопления.Продажи.Обороты("));
+
+        let periodic = vec![("InformationRegisterPeriodicity".to_string(), "Day".to_string())];
+        let tables = register_virtual_tables("InformationRegister", "КурсыВалют", &periodic);
+        assert_eq!(tables[0], "РегистрСведений.КурсыВалют.СрезПоследних(&Период, Условие)");
+
+        let flat = vec![("InformationRegisterPeriodicity".to_string(), "Nonperiodical".to_string())];
+        assert!(register_virtual_tables("InformationRegister", "Настройки", &flat).is_empty());
+        assert!(register_virtual_tables("Catalog", "Номенклатура", &[]).is_empty());
+    }
+
+    #[test]
+    fn resolve_module_path_maps_object_type() {
+        assert_eq!(resolve_module_path("CommonModule.ОбщегоНазначения"), "CommonModules/ОбщегоНазначения");
+        assert_eq!(resolve_module_path("ОбщегоНазначения"), "ОбщегоНазначения");
+    }
+
+    #[test]
+    fn object_rights_list_granting_roles_with_rls() {
+        let index = TestIndex::new();
+        order_rights(&index);
+        index.role("Администратор", true);
+        let (_, rights) = index::get_object_rights(&index.path, "Document.Заказ").unwrap();
+
+        let text = format_object_rights("Document.Заказ", &rights, None);
+        assert!(text.contains("### Роли (3)"));
+        assert!(text.contains("- **Менеджер**: Чтение, Изменение\n"));
+        assert!(text.contains("  - RLS (Чтение, Изменение): `#ПоЗначениям(\"Документ.Заказ\", \"Организация\")`\n"));
+        assert!(text.contains("- **ПолныеПрава**: Чтение, Проведение\n"));
+        assert!(!text.contains("Наблюдатель"));
+        assert!(text.contains("  - поле Attribute.Сумма: запрещено Просмотр\n"));
+        assert!(text.contains("- **Администратор**: все права (унаследованы: права для новых объектов)\n"));
+
+        assert_eq!(right_from_arg("проведение"), Some("Posting"));
+        assert_eq!(right_from_arg("InteractiveDelete"), Some("InteractiveDelete"));
+        assert_eq!(right_from_arg("Провести"), None);
+        let posting = format_object_rights("Document.Заказ", &rights, right_from_arg("Проведение"));
+        assert!(posting.starts_with("## Право «Проведение» на Document.Заказ"));
+        assert!(posting.contains("### Роли (2)\n- **ПолныеПрава**: Проведение\n- **Администратор**: все права"));
+    }
+
+    #[test]
+    fn query_table_accepts_russian_english_and_bare_names() {
+        assert_eq!(
+            parse_query_table("РегистрНакопления.ТоварыНаСкладах.Остатки"),
+            Some(("AccumulationRegister.ТоварыНаСкладах".to_string(), Some("Остатки".to_string())))
+        );
+        assert_eq!(
+            parse_query_table("Catalog.Валюты"),
+            Some(("Catalog.Валюты".to_string(), None))
+        );
+        assert_eq!(parse_query_table("ТоварыНаСкладах"), Some(("ТоварыНаСкладах".to_string(), None)));
+        assert_eq!(parse_query_table("Регистр.Товары"), None);
+    }
+
+    const ORDER_MODULE: &str = "Documents/Заказ/Ext/ObjectModule.bsl";
+    const BANK_MODULE: &str = "CommonModules/ОбменСБанком/Ext/Module.bsl";
+    const PAYMENTS_MODULE: &str = "CommonModules/Платежи/Ext/Module.bsl";
+
+    #[test]
+    fn call_tree_nests_callees_and_marks_cycles() {
+        let index = TestIndex::new();
+        index.method(ORDER_MODULE, "ОбработкаПроведения", 10);
+        index.method(BANK_MODULE, "ОтправитьПлатежи", 40);
+        index.method(PAYMENTS_MODULE, "ЗарегистрироватьОплату", 1);
+        index.method("CommonModules/Цены/Ext/Module.bsl", "ПересчитатьЦены", 1);
+        index.call(ORDER_MODULE, "ОбработкаПроведения", BANK_MODULE, "ОтправитьПлатежи");
+        index.call(ORDER_MODULE, "ОбработкаПроведения", PAYMENTS_MODULE, "ЗарегистрироватьОплату");
+        index.call(ORDER_MODULE, "ОбработкаПроведения", "CommonModules/Цены/Ext/Module.bsl", "ПересчитатьЦены");
+        index.call(BANK_MODULE, "ОтправитьПлатежи", ORDER_MODULE, "ОбработкаПроведения");
+        index.exec(
+            "INSERT INTO calls (caller_file, caller_name, caller_name_lower, callee_name, callee_name_lower, callee_file, edge_kind)
+             VALUES ('EventSubscriptions/ПриЗаписи.xml', 'EventSubscription.ПриЗаписи', 'eventsubscription.призаписи',
+                     'ОтправитьПлатежи', 'отправитьплатежи', 'CommonModules/ОбменСБанком/Ext/Module.bsl', 'event_subscription');",
+        );
+
+        let tree = index::get_call_tree(&index.path, "ОбработкаПроведения", index::CallDirection::Callees, 3, 1).unwrap();
+        let text = format_call_tree(&tree, index::CallDirection::Callees, 3);
+        assert!(text.starts_with("## Что вызывает ОбработкаПроведения (глубина 3)\n\n"));
+        assert!(text.contains(
+            "\n  - **ОтправитьПлатежи** — `CommonModule.ОбменСБанком` (CommonModules/ОбменСБанком/Ext/Module.bsl:40)\n"
+        ));
+        assert!(text.contains("\n    - **ОбработкаПроведения** — `Document.Заказ.ObjectModule` (Documents/Заказ/Ext/ObjectModule.bsl:10) ↻ *цикл*\n"));
+        assert!(text.contains("\n  - *…ещё 2*\n"));
+
+        let callers = index::get_call_tree(&index.path, "ОтправитьПлатежи", index::CallDirection::Callers, 2, 10).unwrap();
+        let text = format_call_tree(&callers, index::CallDirection::Callers, 2);
+        assert!(text.contains(
+            "\n  - `EventSubscription.ПриЗаписи` — подписка на событие, вызывается платформой (EventSubscriptions/ПриЗаписи.xml)\n"
+        ));
+    }
+
+    #[test]
+    fn call_paths_list_each_chain_in_order() {
+        let index = TestIndex::new();
+        index.method(ORDER_MODULE, "ОбработкаПроведения", 1);
+        index.method(PAYMENTS_MODULE, "ЗарегистрироватьОплату", 1);
+        index.method(BANK_MODULE, "ОтправитьПлатежи", 1);
+        index.method(BANK_MODULE, "ПроверитьВыписку", 20);
+        index.call(ORDER_MODULE, "ОбработкаПроведения", BANK_MODULE, "ПроверитьВыписку");
+        index.call(ORDER_MODULE, "ОбработкаПроведения", PAYMENTS_MODULE, "ЗарегистрироватьОплату");
+        index.call(PAYMENTS_MODULE, "ЗарегистрироватьОплату", BANK_MODULE, "ОтправитьПлатежи");
+
+        let found = index::find_call_paths(&index.path, "ОбработкаПроведения", "ОтправитьПлатежи", 8, 10, 200).unwrap();
+        let text = format_call_paths(&found, 8, 200);
+        assert!(text.contains("Кратчайших цепочек: 1 (вызовов в цепочке: 2, просмотрено методов: 4)"));
+        assert!(text.contains("\n2. **ЗарегистрироватьОплату** — `CommonModule.Платежи` (CommonModules/Платежи/Ext/Module.bsl:1)\n"));
+        assert!(!text.contains("Поиск ограничен"));
+
+        let capped = index::find_call_paths(&index.path, "ОбработкаПроведения", "ОтправитьПлатежи", 8, 10, 1).unwrap();
+        let text = format_call_paths(&capped, 8, 1);
+        assert!(text.contains("Цепочка не найдена: длина до 8 вызовов, просмотрено методов: 2."));
+        assert!(text.contains("больше 1 вызовов"));
+    }
+
+    #[test]
+    fn unused_symbols_grouped_by_object_and_module() {
+        let index = TestIndex::new();
+        let old_format = index.method(BANK_MODULE, "СтарыйФормат", 120);
+        index.export(old_format);
+        index.method("Documents/Заказ/Ext/ManagerModule.bsl", "ПересчитатьСкидки", 10);
+        index.method(ORDER_MODULE, "ЗаполнитьПоОснованию", 40);
+        index.method(ORDER_MODULE, "Проверить", 80);
+        index.method(ORDER_MODULE, "Пересчитать", 100);
+        index.call(ORDER_MODULE, "ЗаполнитьПоОснованию", ORDER_MODULE, "Пересчитать");
+
+        let unused = index::find_unused_symbols(&index.path, "", &index::SymbolFilter::default(), true, 4).unwrap();
+        let text = format_unused_symbols(&unused);
+        assert!(text.contains("Найдено: 4 из 5 проверенных методов, объектов: 2."));
+        assert!(text.contains(
+            "\n### CommonModule.ОбменСБанком (1)\n\n`CommonModule.ОбменСБанком` (CommonModules/ОбменСБанком/Ext/Module.bsl)\n- **СтарыйФормат()** Экспорт — Процедура (строка 120)\n"
+        ));
+        assert!(text.contains(
+            "\n`Document.Заказ.ObjectModule` (Documents/Заказ/Ext/ObjectModule.bsl)\n- **ЗаполнитьПоОснованию()** — Процедура (строка 40)\n- **Проверить()** — Процедура (строка 80)\n"
+        ));
+        assert_eq!(text.matches("ObjectModule` (").count(), 1);
+        assert!(!text.contains("Показаны объекты"));
+
+        let unused = index::find_unused_symbols(&index.path, "", &index::SymbolFilter::default(), true, 2).unwrap();
+        let text = format_unused_symbols(&unused);
+        assert!(!text.contains("Document.Заказ"));
+        assert!(text.contains("*Показаны объекты: 1 из 2 (1 методов)"));
+
+        let none = index::find_unused_symbols(&index.path, "Catalogs/", &index::SymbolFilter::default(), true, 200).unwrap();
+        assert!(format_unused_symbols(&none).contains("Проверено методов: 0. Неиспользуемых не найдено."));
+    }
 }
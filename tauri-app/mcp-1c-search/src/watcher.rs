@@ -0,0 +1,208 @@
+//! Live re-indexing: watches `ONEC_CONFIG_PATH` (and extension directories) and feeds
+//! debounced batches of changed paths to `index::sync_paths`, rebuilding metadata with
+//! `metadata::build_metadata` when a batch touches object descriptions, reporting progress via
+//! `SEARCH_STATUS_JSON`.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::{index, metadata};
+
+/// Quiet period after the last event before a batch is re-indexed.
+const DEBOUNCE: Duration = Duration::from_millis(1500);
+/// Upper bound on how long a continuous stream of events (a large dump) can delay re-indexing.
+const MAX_BATCH_DELAY: Duration = Duration::from_secs(10);
+
+/// Metadata files of the base configuration: object descriptions (`.xml`, EDT `.mdo`),
+/// forms (`Form.xml`, `Form.form`), role rights (`Rights.xml`, `Rights.rights`), subsystems.
+const METADATA_EXTENSIONS: &[&str] = &["xml", "mdo", "form", "rights"];
+
+/// What a changed path requires.
+#[derive(Debug, PartialEq)]
+enum Change {
+    /// Re-parse with `sync_paths`: a `.bsl` module or an extension-less path (a directory —
+    /// created, moved or removed with modules inside).
+    Module,
+    /// Rebuild metadata: a metadata file of the base configuration (extension metadata is not indexed).
+    Metadata,
+}
+
+/// Changed paths collected over one debounce period.
+#[derive(Default)]
+struct Batch {
+    modules: BTreeSet<PathBuf>,
+    metadata: bool,
+}
+
+/// What a changed path can affect in the index, `None` for nothing. Hidden entries (`.git`)
+/// and the index directory itself are skipped.
+fn classify(path: &Path, root: &Path, index_dir: Option<&Path>) -> Option<Change> {
+    if index_dir.is_some_and(|dir| path.starts_with(dir)) {
+        return None;
+    }
+    let in_extension = index::extension_roots().iter().find_map(|e| path.strip_prefix(&e.path).ok());
+    let rel = in_extension.or_else(|| path.strip_prefix(root).ok()).unwrap_or(path);
+    if rel.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.')) {
+        return None;
+    }
+    match path.extension().and_then(|x| x.to_str()) {
+        None => Some(Change::Module),
+        Some(ext) if ext.eq_ignore_ascii_case("bsl") => Some(Change::Module),
+        Some(ext) if in_extension.is_none() && METADATA_EXTENSIONS.iter().any(|m| ext.eq_ignore_ascii_case(m)) => {
+            Some(Change::Metadata)
+        }
+        Some(_) => None,
+    }
+}
+
+fn collect_paths(event: notify::Result<Event>, root: &Path, index_dir: Option<&Path>, batch: &mut Batch) {
+    let event = match event {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("[1c-search] Watcher error: {}", e);
+            return;
+        }
+    };
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+    for path in event.paths {
+        match classify(&path, root, index_dir) {
+            Some(Change::Module) => {
+                batch.modules.insert(path);
+            }
+            Some(Change::Metadata) => batch.metadata = true,
+            None => {}
+        }
+    }
+}
+
+fn rebuild_metadata(root: &Path, db_path: &Path) {
+    crate::emit_search_status_json(
+        "metadata_indexing",
+        3,
+        "Обновление метаданных...",
+        index::symbol_count(db_path),
+        crate::db_size_mb(db_path),
+        index::get_built_at(db_path).unwrap_or(0),
+    );
+    match metadata::build_metadata(root, db_path) {
+        Ok(n) => eprintln!("[1c-search] Watch metadata rebuild: {} objects", n),
+        Err(e) => eprintln!("[1c-search] Watch metadata rebuild error: {}", e),
+    }
+}
+
+fn reindex(root: &Path, db_path: &Path, paths: &[PathBuf]) {
+    crate::emit_search_status_json(
+        "syncing",
+        0,
+        &format!("Обновление индекса: {} изменений...", paths.len()),
+        index::symbol_count(db_path),
+        crate::db_size_mb(db_path),
+        index::get_built_at(db_path).unwrap_or(0),
+    );
+
+    match index::sync_paths(root, db_path, paths) {
+        Ok(stats) => {
+            let size = crate::db_size_mb(db_path);
+            let built_at = index::get_built_at(db_path).unwrap_or(0);
+            eprintln!(
+                "[1c-search] Watch sync: +{} ~{} -{} ={} total={}",
+                stats.added, stats.updated, stats.removed, stats.unchanged, stats.total_symbols
+            );
+            eprintln!("SEARCH_STATUS:ready:{}:{:.2}:{}", stats.total_symbols, size, built_at);
+            crate::emit_search_status_json("ready", 100, "Индекс готов", stats.total_symbols, size, built_at);
+        }
+        Err(e) => {
+            eprintln!("[1c-search] Watch sync error: {}", e);
+            crate::emit_search_status_json(
+                "degraded",
+                100,
+                &format!("Обновление индекса завершилось с ошибкой, используется существующий индекс: {}", e),
+                index::symbol_count(db_path),
+                crate::db_size_mb(db_path),
+                index::get_built_at(db_path).unwrap_or(0),
+            );
+        }
+    }
+}
+
+/// Watch `root` and the extension roots recursively and re-index changed modules. Blocks for
+/// the lifetime of the watcher; returns only if it cannot be started or its event channel closes.
+pub fn watch(root: &Path, db_path: &Path) -> Result<(), String> {
+    let (tx, rx) = channel::<notify::Result<Event>>();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| e.to_string())?;
+    watcher.watch(root, RecursiveMode::Recursive).map_err(|e| e.to_string())?;
+    eprintln!("[1c-search] Watching {} for changes", root.display());
+    for ext in index::extension_roots() {
+        // An extension dumped inside the base directory is already covered
+        if ext.path.starts_with(root) {
+            continue;
+        }
+        match watcher.watch(&ext.path, RecursiveMode::Recursive) {
+            Ok(()) => eprintln!("[1c-search] Watching extension {} ({})", ext.name, ext.path.display()),
+            Err(e) => eprintln!("[1c-search] Cannot watch extension {}: {}", ext.name, e),
+        }
+    }
+
+    let index_dir = db_path.parent();
+    loop {
+        let first = rx.recv().map_err(|_| "канал событий закрыт".to_string())?;
+        let mut batch = Batch::default();
+        collect_paths(first, root, index_dir, &mut batch);
+
+        // Debounce: keep collecting until events stop for DEBOUNCE (bounded by MAX_BATCH_DELAY)
+        let started = Instant::now();
+        loop {
+            let left = MAX_BATCH_DELAY.saturating_sub(started.elapsed());
+            if left.is_zero() {
+                break;
+            }
+            match rx.recv_timeout(DEBOUNCE.min(left)) {
+                Ok(event) => collect_paths(event, root, index_dir, &mut batch),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Err("канал событий закрыт".to_string()),
+            }
+        }
+
+        // Metadata first, as at startup: form and entry-point edges are rebuilt before modules sync
+        if batch.metadata {
+            rebuild_metadata(root, db_path);
+        }
+        if !batch.modules.is_empty() {
+            let paths: Vec<PathBuf> = batch.modules.into_iter().collect();
+            reindex(root, db_path, &paths);
+        } else if batch.metadata {
+            let size = crate::db_size_mb(db_path);
+            let built_at = index::get_built_at(db_path).unwrap_or(0);
+            crate::emit_search_status_json("ready", 100, "Индекс готов", index::symbol_count(db_path), size, built_at);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, Change};
+    use std::path::Path;
+
+    #[test]
+    fn changes_are_modules_directories_and_metadata_files() {
+        let root = Path::new("/cfg");
+        let index_dir = Path::new("/cfg/.mcp-index");
+        let check = |p: &str| classify(Path::new(p), root, Some(index_dir));
+
+        assert_eq!(check("/cfg/CommonModules/Общий/Ext/Module.bsl"), Some(Change::Module));
+        assert_eq!(check("/cfg/CommonModules/Общий"), Some(Change::Module));
+        assert_eq!(check("/cfg/CommonModules/Общий.xml"), Some(Change::Metadata));
+        assert_eq!(check("/cfg/Documents/Заказ/Forms/Форма/Ext/Form.xml"), Some(Change::Metadata));
+        assert_eq!(check("/cfg/Catalogs/Валюты/Валюты.mdo"), Some(Change::Metadata));
+        assert_eq!(check("/cfg/Roles/Админ/Rights.rights"), Some(Change::Metadata));
+        assert_eq!(check("/cfg/Documents/Заказ/Ext/Help/ru.html"), None);
+        assert_eq!(check("/cfg/.git/index"), None);
+        assert_eq!(check("/cfg/.mcp-index/symbols.db-journal"), None);
+    }
+}
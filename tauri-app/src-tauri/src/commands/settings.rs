@@ -463,6 +463,7 @@ mod tests {
                 command: None,
                 args: None,
                 env: Some(HashMap::from([("TOKEN".to_string(), "secret".to_string())])),
+                extension_paths: None,
             }],
             ..AppSettings::default()
         }
@@ -585,6 +586,7 @@ mod tests {
                 "TOKEN".to_string(),
                 "current-secret".to_string(),
             )])),
+            extension_paths: None,
         }];
         current.llm.providers.insert(
             "openai".to_string(),
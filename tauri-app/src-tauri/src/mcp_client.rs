@@ -50,6 +50,25 @@ fn format_unix_msk(unix: u64) -> String {
 
 pub(crate) const BUILTIN_1C_SEARCH_SERVER_ID: &str = "builtin-1c-search";
 
+/// Extension dump directories of the built-in 1C search joined for `ONEC_EXTENSION_PATHS`.
+pub(crate) fn builtin_search_extension_paths(config: &McpServerConfig) -> Option<std::ffi::OsString> {
+    if config.id != BUILTIN_1C_SEARCH_SERVER_ID {
+        return None;
+    }
+
+    let paths: Vec<&str> = config
+        .extension_paths
+        .iter()
+        .flatten()
+        .map(|path| path.trim())
+        .filter(|path| !path.is_empty())
+        .collect();
+    if paths.is_empty() {
+        return None;
+    }
+    std::env::join_paths(paths).ok()
+}
+
 pub(crate) fn builtin_search_unavailable_reason(config: &McpServerConfig) -> Option<String> {
     if config.id != BUILTIN_1C_SEARCH_SERVER_ID {
         return None;
@@ -1269,6 +1288,10 @@ impl McpSession {
             cmd.envs(env);
         }
 
+        if let Some(extension_paths) = builtin_search_extension_paths(&config) {
+            cmd.env("ONEC_EXTENSION_PATHS", extension_paths);
+        }
+
         // Pass global debug flag
         if debug_all {
             cmd.env("ONEC_AI_DEBUG", "true");
@@ -2082,6 +2105,40 @@ mod tests {
             .contains("не найден"));
     }
 
+    #[test]
+    fn joins_builtin_search_extension_paths() {
+        let config = McpServerConfig {
+            id: BUILTIN_1C_SEARCH_SERVER_ID.to_string(),
+            extension_paths: Some(vec![
+                " /dumps/ext1 ".to_string(),
+                String::new(),
+                "/dumps/ext2".to_string(),
+            ]),
+            ..Default::default()
+        };
+        let joined = builtin_search_extension_paths(&config).expect("expected extension paths");
+        assert_eq!(
+            std::env::split_paths(&joined).collect::<Vec<_>>(),
+            vec![
+                std::path::PathBuf::from("/dumps/ext1"),
+                std::path::PathBuf::from("/dumps/ext2")
+            ]
+        );
+
+        let other_server = McpServerConfig {
+            id: "custom".to_string(),
+            ..config.clone()
+        };
+        assert_eq!(builtin_search_extension_paths(&other_server), None);
+        assert_eq!(
+            builtin_search_extension_paths(&McpServerConfig {
+                extension_paths: Some(vec!["  ".to_string()]),
+                ..config
+            }),
+            None
+        );
+    }
+
     #[test]
     fn parses_sse_json_rpc_payload() {
         let parsed = McpSession::parse_http_rpc_response(
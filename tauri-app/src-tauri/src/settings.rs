@@ -246,6 +246,9 @@ pub struct McpServerConfig {
     pub command: Option<String>,
     pub args: Option<Vec<String>>,
     pub env: Option<std::collections::HashMap<String, String>>,
+    // Built-in 1C search: dumped configuration extensions indexed with the base configuration
+    #[serde(default)]
+    pub extension_paths: Option<Vec<String>>,
 }
 
 impl Default for McpServerConfig {
@@ -262,6 +265,7 @@ impl Default for McpServerConfig {
             command: None,
             args: None,
             env: None,
+            extension_paths: None,
         }
     }
 }
@@ -154,6 +154,8 @@ export interface McpServerConfig {
     args?: string[] | null;
     env?: Record<string, string> | null;
     headers?: Record<string, string> | null;
+    // Built-in 1C search: dumped configuration extensions
+    extension_paths?: string[] | null;
 }
 
 export interface McpServerStatus {
@@ -900,6 +902,17 @@ export function MCPSettings({ servers, bslEnabled, onUpdate }: MCPSettingsProps)
                                                             console.error('Failed to open directory dialog:', e);
                                                         }
                                                     };
+                                                    const extensionPaths = server.extension_paths || [];
+                                                    const browseExtensionDir = async () => {
+                                                        try {
+                                                            const dir = await open({ directory: true, multiple: false, title: 'Выберите директорию выгрузки расширения 1С' });
+                                                            if (dir && typeof dir === 'string' && !extensionPaths.includes(dir)) {
+                                                                handleUpdateServer(server.id, { extension_paths: [...extensionPaths.filter(p => p.trim()), dir] });
+                                                            }
+                                                        } catch (e) {
+                                                            console.error('Failed to open directory dialog:', e);
+                                                        }
+                                                    };
                                                     const selectFromHistory = (p: string) => {
                                                         const newEnv = { ...(server.env || {}), 'ONEC_CONFIG_PATH': p };
                                                         handleUpdateServer(server.id, { env: newEnv });
@@ -972,6 +985,28 @@ export function MCPSettings({ servers, bslEnabled, onUpdate }: MCPSettingsProps)
                                                                 )}
                                                                 <p className="text-[10px] text-zinc-600 mt-1">Корневая директория выгруженной конфигурации (содержит папки CommonModules, Documents и т.д.)</p>
                                                             </div>
+                                                            <div>
+                                                                <label className="text-[10px] text-zinc-500 uppercase font-bold flex items-center gap-1 mb-1">
+                                                                    <Terminal className="w-3 h-3" /> Выгрузки расширений (CFE)
+                                                                </label>
+                                                                <div className="flex gap-2">
+                                                                    <textarea
+                                                                        value={extensionPaths.join('\n')}
+                                                                        onChange={(e) => handleUpdateServer(server.id, { extension_paths: e.target.value.split('\n') })}
+                                                                        rows={Math.max(2, extensionPaths.length)}
+                                                                        className="flex-1 bg-zinc-900 border border-zinc-700 rounded-lg px-3 py-1.5 text-sm focus:ring-1 focus:ring-blue-500 focus:outline-none font-mono min-w-0 resize-none"
+                                                                        placeholder="C:\1C\extensions\MyExtension"
+                                                                    />
+                                                                    <button
+                                                                        onClick={browseExtensionDir}
+                                                                        className="flex items-center gap-1.5 px-3 py-1.5 bg-zinc-700 hover:bg-zinc-600 text-zinc-300 hover:text-zinc-100 rounded-lg text-xs font-medium transition shrink-0 self-start"
+                                                                        title="Добавить папку расширения"
+                                                                    >
+                                                                        <FolderOpen className="w-3.5 h-3.5" />
+                                                                    </button>
+                                                                </div>
+                                                                <p className="text-[10px] text-zinc-600 mt-1">Необязательно. По одной директории на строку — расширения индексируются вместе с основной конфигурацией</p>
+                                                            </div>
                                                             {searchSt === 'unavailable' ? (
                                                                 <div className="bg-amber-500/5 border border-amber-500/20 rounded-lg p-3 flex items-start gap-3">
                                                                     <AlertCircle className="w-4 h-4 text-amber-500 shrink-0 mt-0.5" />
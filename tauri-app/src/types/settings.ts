@@ -113,6 +113,7 @@ export interface McpServerConfig {
     command?: string | null;
     args?: string[] | null;
     env?: Record<string, string> | null;
+    extension_paths?: string[] | null;
 }
 
 export interface SlashCommand {